@@ -0,0 +1,419 @@
+use std::{
+    io::Read,
+    process::{Child, Command, Stdio},
+    sync::mpsc::{self, Receiver},
+    thread,
+    time::Duration,
+};
+
+use color_eyre::eyre::{bail, Context};
+use serde::Serialize;
+use tracing::{debug, error, info, info_span, warn};
+use wait_timeout::ChildExt;
+
+use crate::models::reminders::{Reminder, ReminderAction, ReminderAlert, ReminderId};
+
+/// How long an action may run if it does not specify a timeout, in seconds.
+const DEFAULT_TIMEOUT_SECS: u64 = 30;
+
+/// How long to wait for a command's output after it exits. Processes started
+/// in the background by the command can keep its output open.
+const OUTPUT_GRACE: Duration = Duration::from_secs(1);
+
+/// The environment variable containing the reminder's ID.
+pub const REMINDER_ID_VAR: &str = "REMINDER_ID";
+
+/// The environment variable containing the reminder's title.
+pub const REMINDER_TITLE_VAR: &str = "REMINDER_TITLE";
+
+/// The environment variable containing the local time of the occurrence.
+pub const REMINDER_OCCURRENCE_VAR: &str = "REMINDER_OCCURRENCE";
+
+/// The environment variable set to `true` if the occurrence was missed, for
+/// example while the system was asleep, and `false` otherwise.
+pub const REMINDER_MISSED_VAR: &str = "REMINDER_MISSED";
+
+/// The details of a fired reminder that are passed to its actions.
+#[derive(Clone, Debug, Serialize)]
+pub struct ActionPayload {
+    /// The ID of the reminder.
+    pub id: ReminderId,
+    /// The title of the reminder.
+    pub title: String,
+    /// The local time of the occurrence, formatted as an ISO 8601 date and
+    /// time without an offset.
+    pub occurrence: String,
+    /// Whether the occurrence was missed and is only being reported late.
+    pub missed: bool,
+}
+
+impl ActionPayload {
+    /// Creates the payload for an alert of the given reminder.
+    pub fn new(reminder: &Reminder, alert: &ReminderAlert) -> Self {
+        Self {
+            id: reminder.id,
+            title: reminder.message.clone(),
+            occurrence: alert.occurrence.at.format("%Y-%m-%dT%H:%M:%S").to_string(),
+            missed: alert.missed,
+        }
+    }
+}
+
+/// Checks whether the alert at `index` is superseded by a later alert for the
+/// same reminder. Only the latest of several missed occurrences runs actions,
+/// so catching up after a long suspend does not trigger a burst of them.
+pub fn is_superseded(alerts: &[ReminderAlert], index: usize) -> bool {
+    let alert = &alerts[index];
+    alert.missed
+        && alerts[index + 1..]
+            .iter()
+            .any(|later| later.occurrence.id == alert.occurrence.id)
+}
+
+/// Runs the actions of a reminder in the background. Failures are logged.
+pub fn spawn_actions(reminder: &Reminder, alert: &ReminderAlert) {
+    for action in &reminder.actions {
+        let action = action.clone();
+        let payload = ActionPayload::new(reminder, alert);
+        let result = thread::Builder::new()
+            .name(format!("reminder-{}-action", reminder.id))
+            .spawn(move || {
+                let _span = info_span!("action", id = %payload.id).entered();
+                if let Err(error) = run_action(&action, &payload) {
+                    error!(?error, "reminder action failed");
+                }
+            });
+        if let Err(error) = result {
+            error!(%error, "failed to start reminder action");
+        }
+    }
+}
+
+/// Runs an action to completion.
+pub fn run_action(action: &ReminderAction, payload: &ActionPayload) -> color_eyre::Result<()> {
+    match action {
+        ReminderAction::Command {
+            command,
+            timeout_secs,
+        } => run_command(command, timeout(*timeout_secs), payload),
+        ReminderAction::Webhook { url, timeout_secs } => {
+            send_webhook(url, timeout(*timeout_secs), payload)
+        }
+    }
+}
+
+/// Gets the timeout for an action.
+fn timeout(timeout_secs: Option<u64>) -> Duration {
+    Duration::from_secs(timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS))
+}
+
+/// Runs a shell command, killing it if it does not finish in time.
+fn run_command(
+    command: &str,
+    timeout: Duration,
+    payload: &ActionPayload,
+) -> color_eyre::Result<()> {
+    info!(command, "running command");
+    let mut shell = shell(command);
+    shell
+        .env(REMINDER_ID_VAR, payload.id.to_string())
+        .env(REMINDER_TITLE_VAR, &payload.title)
+        .env(REMINDER_OCCURRENCE_VAR, &payload.occurrence)
+        .env(REMINDER_MISSED_VAR, payload.missed.to_string())
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    // Run the command in its own process group so anything it starts can be
+    // killed along with it
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut shell, 0);
+
+    let mut child = shell
+        .spawn()
+        .wrap_err_with(|| format!("failed to start {command:?}"))?;
+
+    // Drain the output while waiting so the command does not block on a full
+    // pipe
+    let stdout = child.stdout.take().map(capture);
+    let stderr = child.stderr.take().map(capture);
+    let status = match child.wait_timeout(timeout)? {
+        Some(status) => Some(status),
+        None => {
+            warn!(command, ?timeout, "command timed out, killing it");
+            kill(&mut child);
+            child.wait()?;
+            None
+        }
+    };
+
+    let stdout = stdout
+        .and_then(|output| output.recv_timeout(OUTPUT_GRACE).ok())
+        .unwrap_or_default();
+    let stderr = stderr
+        .and_then(|output| output.recv_timeout(OUTPUT_GRACE).ok())
+        .unwrap_or_default();
+    if !stdout.trim().is_empty() {
+        info!(command, stdout = stdout.trim_end(), "command output");
+    }
+    if !stderr.trim().is_empty() {
+        warn!(command, stderr = stderr.trim_end(), "command error output");
+    }
+
+    let Some(status) = status else {
+        bail!("{command:?} timed out after {timeout:?}");
+    };
+    if !status.success() {
+        bail!("{command:?} exited with {status}");
+    }
+
+    debug!(command, "command finished");
+    Ok(())
+}
+
+/// Creates a command which runs `command` through the system shell.
+fn shell(command: &str) -> Command {
+    if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.args(["/C", command]);
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.args(["-c", command]);
+        shell
+    }
+}
+
+/// Kills a command along with the processes it started. Errors are ignored
+/// since the command may have exited on its own in the meantime.
+fn kill(child: &mut Child) {
+    #[cfg(unix)]
+    if let Ok(group) = libc::pid_t::try_from(child.id()) {
+        // SAFETY: Sending a signal has no memory safety requirements
+        unsafe {
+            libc::kill(-group, libc::SIGKILL);
+        }
+    }
+
+    let _ = child.kill();
+}
+
+/// Reads a stream to the end on a background thread, sending the output once
+/// the stream is closed.
+fn capture(mut stream: impl Read + Send + 'static) -> Receiver<String> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let mut output = Vec::new();
+        let _ = stream.read_to_end(&mut output);
+        let _ = sender.send(String::from_utf8_lossy(&output).into_owned());
+    });
+    receiver
+}
+
+/// Sends a POST request containing the payload as JSON.
+fn send_webhook(url: &str, timeout: Duration, payload: &ActionPayload) -> color_eyre::Result<()> {
+    info!(url, "sending webhook");
+    let response = ureq::post(url)
+        .timeout(timeout)
+        .send_json(payload)
+        .wrap_err_with(|| format!("failed to send webhook to {url}"))?;
+
+    let status = response.status();
+    let body = response.into_string().unwrap_or_default();
+    info!(url, status, body = body.trim_end(), "webhook response");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        thread,
+        time::{Duration, Instant},
+    };
+
+    use chrono::NaiveDate;
+
+    use crate::models::reminders::{
+        Reminder, ReminderAction, ReminderAlert, ReminderId, ReminderOccurrence,
+    };
+
+    use super::{is_superseded, run_command, send_webhook, spawn_actions, ActionPayload};
+
+    fn payload() -> ActionPayload {
+        ActionPayload {
+            id: ReminderId(7),
+            title: "Stretch \"now\"".to_string(),
+            occurrence: "2024-03-01T12:30:00".to_string(),
+            missed: false,
+        }
+    }
+
+    fn alert(id: u64, day: u32, missed: bool) -> ReminderAlert {
+        ReminderAlert {
+            occurrence: ReminderOccurrence {
+                id: ReminderId(id),
+                at: NaiveDate::from_ymd_opt(2024, 3, day)
+                    .unwrap()
+                    .and_hms_opt(9, 0, 0)
+                    .unwrap(),
+            },
+            missed,
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn command_gets_reminder_variables() {
+        let path = std::env::temp_dir().join(format!("reminder-vars-{}", std::process::id()));
+        let command = format!(
+            "printf '%s|%s|%s|%s' \"$REMINDER_ID\" \"$REMINDER_TITLE\" \"$REMINDER_OCCURRENCE\" \"$REMINDER_MISSED\" > {}",
+            path.display()
+        );
+        run_command(&command, Duration::from_secs(10), &payload()).unwrap();
+
+        let output = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(output, "7|Stretch \"now\"|2024-03-01T12:30:00|false");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn missed_alerts_run_actions() {
+        let path = std::env::temp_dir().join(format!("reminder-missed-{}", std::process::id()));
+        let reminder = Reminder {
+            id: ReminderId(3),
+            frequency: "daily @ 09:00".parse().unwrap(),
+            message: "Water plants".to_string(),
+            completed: false,
+            snoozed_until: None,
+            actions: vec![ReminderAction::Command {
+                command: format!(
+                    "printf '%s|%s' \"$REMINDER_OCCURRENCE\" \"$REMINDER_MISSED\" > {}.tmp && mv {0}.tmp {0}",
+                    path.display()
+                ),
+                timeout_secs: Some(10),
+            }],
+            contact: None,
+        };
+        spawn_actions(&reminder, &alert(3, 2, true));
+
+        let start = Instant::now();
+        let output = loop {
+            if let Ok(output) = std::fs::read_to_string(&path) {
+                break output;
+            }
+            assert!(
+                start.elapsed() < Duration::from_secs(10),
+                "action did not run"
+            );
+            thread::sleep(Duration::from_millis(50));
+        };
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(output, "2024-03-02T09:00:00|true");
+    }
+
+    #[test]
+    fn only_latest_missed_alert_per_reminder_runs_actions() {
+        let alerts = [
+            alert(1, 1, true),
+            alert(2, 1, true),
+            alert(1, 2, true),
+            alert(2, 2, false),
+            alert(1, 3, true),
+        ];
+        let superseded: Vec<_> = (0..alerts.len())
+            .map(|index| is_superseded(&alerts, index))
+            .collect();
+        assert_eq!(superseded, [true, true, true, false, false]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn command_fails_on_nonzero_exit() {
+        let error = run_command("exit 3", Duration::from_secs(10), &payload()).unwrap_err();
+        assert!(error.to_string().contains("exited with"), "{error}");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn timeout_kills_background_processes() {
+        // The background sleep keeps the output pipes open if it is not killed
+        let start = Instant::now();
+        let error =
+            run_command("sleep 30 & sleep 30", Duration::from_secs(1), &payload()).unwrap_err();
+        assert!(error.to_string().contains("timed out"), "{error}");
+        assert!(start.elapsed() < Duration::from_secs(10));
+    }
+
+    /// Accepts one HTTP request, returning its request line, headers and
+    /// body.
+    fn receive_request(listener: TcpListener) -> (String, Vec<String>, String) {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream);
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line).unwrap();
+
+        let mut headers = Vec::new();
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            let line = line.trim_end().to_string();
+            if line.is_empty() {
+                break;
+            }
+            headers.push(line);
+        }
+
+        let length = headers
+            .iter()
+            .find_map(|header| {
+                let (name, value) = header.split_once(':')?;
+                name.eq_ignore_ascii_case("content-length")
+                    .then(|| value.trim().parse::<usize>().unwrap())
+            })
+            .unwrap_or_default();
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).unwrap();
+
+        let mut stream = reader.into_inner();
+        stream
+            .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok")
+            .unwrap();
+        (
+            request_line.trim_end().to_string(),
+            headers,
+            String::from_utf8(body).unwrap(),
+        )
+    }
+
+    #[test]
+    fn webhook_posts_payload_as_json() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let handle = thread::spawn(move || receive_request(listener));
+
+        let url = format!("http://127.0.0.1:{port}/hook");
+        send_webhook(&url, Duration::from_secs(10), &payload()).unwrap();
+
+        let (request_line, headers, body) = handle.join().unwrap();
+        assert!(request_line.starts_with("POST /hook "), "{request_line}");
+        assert!(
+            headers
+                .iter()
+                .any(|header| header.eq_ignore_ascii_case("content-type: application/json")),
+            "{headers:?}"
+        );
+        let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "id": 7,
+                "title": "Stretch \"now\"",
+                "occurrence": "2024-03-01T12:30:00",
+                "missed": false,
+            })
+        );
+    }
+}
@@ -1,3 +1,5 @@
 mod painter;
+mod runtime;
 
 pub use painter::*;
+pub use runtime::*;
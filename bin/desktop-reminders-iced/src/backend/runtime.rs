@@ -0,0 +1,130 @@
+use iced_native::{clipboard, command, event, system, window, Command, Event, Subscription};
+use iced_winit::{
+    conversion,
+    winit::{
+        dpi::{LogicalPosition, LogicalSize},
+        event_loop::EventLoopProxy,
+        window::Window,
+    },
+    Clipboard, Proxy,
+};
+use tracing::warn;
+
+use crate::ui::app::AppMessage;
+
+/// The executor that runs the app's futures.
+pub type AppExecutor = iced_futures::backend::native::tokio::Executor;
+
+/// Runs the [`Command`]s and [`Subscription`]s of the app within the winit
+/// event loop. Results are sent back to the event loop as user events.
+pub struct AppRuntime {
+    runtime: iced_native::Runtime<AppExecutor, Proxy<AppMessage>, AppMessage>,
+    proxy: EventLoopProxy<AppMessage>,
+}
+
+impl AppRuntime {
+    /// Creates a new runtime which sends messages through `proxy`.
+    pub fn new(proxy: EventLoopProxy<AppMessage>) -> color_eyre::Result<Self> {
+        let executor = AppExecutor::new()?;
+        Ok(Self {
+            runtime: iced_native::Runtime::new(executor, Proxy::new(proxy.clone())),
+            proxy,
+        })
+    }
+
+    /// Runs a function within the context of the executor.
+    #[inline]
+    pub fn enter<R>(&self, f: impl FnOnce() -> R) -> R {
+        self.runtime.enter(f)
+    }
+
+    /// Starts, stops, or keeps running subscriptions so that exactly the
+    /// given subscription is running.
+    #[inline]
+    pub fn track(&mut self, subscription: Subscription<AppMessage>) {
+        self.runtime.track(subscription);
+    }
+
+    /// Sends events that were not captured by the UI to the subscriptions.
+    pub fn broadcast(&mut self, events: impl IntoIterator<Item = Event>) {
+        for event in events {
+            self.runtime.broadcast((event, event::Status::Ignored));
+        }
+    }
+
+    /// Runs the actions of a command. Returns whether the app should exit.
+    pub fn run_command(
+        &mut self,
+        command: Command<AppMessage>,
+        window: &Window,
+        clipboard: &mut Clipboard,
+    ) -> bool {
+        let mut should_exit = false;
+        for action in command.actions() {
+            match action {
+                command::Action::Future(future) => self.runtime.spawn(future),
+                command::Action::Clipboard(action) => match action {
+                    clipboard::Action::Read(tag) => self.send(tag(clipboard.read())),
+                    clipboard::Action::Write(contents) => clipboard.write(contents),
+                },
+                command::Action::Window(action) => {
+                    should_exit |= self.run_window_action(action, window);
+                }
+                command::Action::System(system::Action::QueryInformation(_)) => {
+                    warn!("querying system information is not supported");
+                }
+                command::Action::Widget(_) => {
+                    warn!("widget operations are not supported");
+                }
+            }
+        }
+
+        should_exit
+    }
+
+    /// Runs a window action. Returns whether the app should exit.
+    fn run_window_action(&mut self, action: window::Action<AppMessage>, window: &Window) -> bool {
+        match action {
+            window::Action::Close => return true,
+            window::Action::Drag => {
+                let _ = window.drag_window();
+            }
+            window::Action::Resize { width, height } => {
+                window.set_inner_size(LogicalSize { width, height });
+            }
+            window::Action::Maximize(maximized) => window.set_maximized(maximized),
+            window::Action::Minimize(minimized) => window.set_minimized(minimized),
+            window::Action::Move { x, y } => window.set_outer_position(LogicalPosition { x, y }),
+            window::Action::ChangeMode(mode) => {
+                window.set_visible(conversion::visible(mode));
+                window.set_fullscreen(conversion::fullscreen(window.current_monitor(), mode));
+            }
+            window::Action::FetchMode(tag) => {
+                let mode = if window.is_visible().unwrap_or(true) {
+                    conversion::mode(window.fullscreen())
+                } else {
+                    window::Mode::Hidden
+                };
+                self.send(tag(mode));
+            }
+            window::Action::ToggleMaximize => window.set_maximized(!window.is_maximized()),
+            window::Action::ToggleDecorations => window.set_decorations(!window.is_decorated()),
+            window::Action::RequestUserAttention(attention) => {
+                window.request_user_attention(attention.map(conversion::user_attention));
+            }
+            window::Action::GainFocus => window.focus_window(),
+            window::Action::ChangeAlwaysOnTop(on_top) => window.set_always_on_top(on_top),
+            window::Action::FetchId(tag) => self.send(tag(window.id().into())),
+            window::Action::ChangeIcon(icon) => window.set_window_icon(conversion::icon(icon)),
+        }
+
+        false
+    }
+
+    /// Sends a message to the event loop.
+    fn send(&self, message: AppMessage) {
+        if self.proxy.send_event(message).is_err() {
+            warn!("failed to send message, the event loop has stopped");
+        }
+    }
+}
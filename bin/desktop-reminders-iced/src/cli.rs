@@ -0,0 +1,14 @@
+mod args;
+mod commands;
+mod connection;
+mod filter;
+mod output;
+mod status;
+mod systemd;
+mod transfer;
+
+pub use args::*;
+pub use commands::*;
+pub use filter::*;
+pub use status::StatusFormat;
+pub use transfer::FileFormat;
@@ -0,0 +1,414 @@
+use std::path::PathBuf;
+
+use chrono::{Local, NaiveDate, NaiveTime};
+use clap::{Parser, Subcommand};
+
+use crate::models::reminders::{
+    ReminderDaysOfWeek, ReminderFrequency, ReminderFrequencyDaily, ReminderFrequencyMonthly,
+    ReminderFrequencyOnce, ReminderFrequencyWeekly, ReminderFrequencyYearly, ReminderId,
+    ReminderTimeOfDay, ReminderYearlyDate, SNOOZE_MINUTES,
+};
+
+use crate::formats::{CronExpression, Rrule};
+
+use super::{FileFormat, FilterArgs, StatusFormat};
+
+/// Reminders for your desktop.
+#[derive(Clone, Debug, Parser)]
+#[command(version, about, args_conflicts_with_subcommands = true)]
+pub struct Args {
+    /// Run without a window, delivering reminders as desktop notifications.
+    #[arg(long)]
+    pub daemon: bool,
+    /// Open the "New" tab, optionally with a title entered. If the app is
+    /// already running, its window is used instead.
+    #[arg(
+        long,
+        value_name = "TITLE",
+        num_args = 0..=1,
+        default_missing_value = "",
+        conflicts_with = "daemon"
+    )]
+    pub new: Option<String>,
+    /// Manage reminders from the command line instead of opening a window.
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// A command for managing reminders.
+#[derive(Clone, Debug, Subcommand)]
+pub enum Command {
+    /// Add a reminder.
+    Add(AddArgs),
+    /// List reminders.
+    List(ListArgs),
+    /// Mark reminders as done.
+    Done(DoneArgs),
+    /// Snooze reminders.
+    Snooze(SnoozeArgs),
+    /// Change a reminder.
+    Edit(EditArgs),
+    /// Remove reminders.
+    Remove(RemoveArgs),
+    /// Export reminders.
+    Export(ExportArgs),
+    /// Import reminders from a file.
+    Import(ImportArgs),
+    /// Print the next upcoming or overdue reminder for a status bar.
+    Status(StatusArgs),
+    /// Print or send the morning digest email.
+    Digest(DigestArgs),
+    /// Show the desktop notification for a reminder, unless it is done or
+    /// snoozed. Used by the generated systemd units.
+    Notify(NotifyArgs),
+    /// Generate systemd user timers that show reminders.
+    Systemd(SystemdArgs),
+}
+
+/// The arguments for [`Command::Add`].
+#[derive(Clone, Debug, clap::Args)]
+pub struct AddArgs {
+    /// The message for the reminder.
+    pub message: String,
+    #[command(flatten)]
+    pub frequency: FrequencyArgs,
+    #[command(flatten)]
+    pub time: TimeArgs,
+    /// Print the added reminder as JSON.
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// The arguments for [`Command::List`].
+#[derive(Clone, Debug, clap::Args)]
+pub struct ListArgs {
+    #[command(flatten)]
+    pub filter: FilterArgs,
+    /// Print the reminders as JSON.
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// The arguments for [`Command::Done`].
+#[derive(Clone, Debug, clap::Args)]
+pub struct DoneArgs {
+    /// The IDs of the reminders.
+    #[arg(required = true)]
+    pub ids: Vec<ReminderId>,
+    /// Mark the reminders as not done instead.
+    #[arg(long)]
+    pub undo: bool,
+    /// Print the changed reminders as JSON.
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// The arguments for [`Command::Snooze`].
+#[derive(Clone, Debug, clap::Args)]
+pub struct SnoozeArgs {
+    /// The IDs of the reminders.
+    #[arg(required = true)]
+    pub ids: Vec<ReminderId>,
+    /// How long to snooze for, in minutes.
+    #[arg(long, default_value_t = SNOOZE_MINUTES)]
+    pub minutes: i64,
+    /// Print the snoozed reminders as JSON.
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// The arguments for [`Command::Edit`].
+#[derive(Clone, Debug, clap::Args)]
+pub struct EditArgs {
+    /// The ID of the reminder.
+    pub id: ReminderId,
+    /// The new message for the reminder.
+    #[arg(long)]
+    pub message: Option<String>,
+    #[command(flatten)]
+    pub frequency: FrequencyArgs,
+    #[command(flatten)]
+    pub time: TimeArgs,
+    /// Print the changed reminder as JSON.
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// The arguments for [`Command::Remove`].
+#[derive(Clone, Debug, clap::Args)]
+pub struct RemoveArgs {
+    /// The IDs of the reminders.
+    #[arg(required = true)]
+    pub ids: Vec<ReminderId>,
+    /// Print the removed reminders as JSON.
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// The arguments for [`Command::Export`].
+#[derive(Clone, Debug, clap::Args)]
+pub struct ExportArgs {
+    #[command(flatten)]
+    pub filter: FilterArgs,
+    /// The file to write to. Defaults to standard output.
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+    /// The format to write. Defaults to the output file's extension, or JSON.
+    #[arg(long, value_enum)]
+    pub format: Option<FileFormat>,
+    /// Export iCalendar to-dos instead of events, which keep track of whether
+    /// reminders are done.
+    #[arg(long)]
+    pub todo: bool,
+}
+
+/// The arguments for [`Command::Import`].
+#[derive(Clone, Debug, clap::Args)]
+pub struct ImportArgs {
+    /// The file to read.
+    pub file: PathBuf,
+    /// The format to read. Defaults to the file's extension.
+    #[arg(long, value_enum)]
+    pub format: Option<FileFormat>,
+    /// Print the imported reminders as JSON.
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// The arguments for [`Command::Status`].
+#[derive(Clone, Debug, clap::Args)]
+pub struct StatusArgs {
+    /// How to print the status.
+    #[arg(long, value_enum, default_value_t)]
+    pub format: StatusFormat,
+    /// Keep running, printing a new line whenever the status changes.
+    #[arg(short, long)]
+    pub watch: bool,
+    /// How many minutes before a reminder it is considered urgent.
+    #[arg(long, value_name = "MINUTES", default_value_t = 15)]
+    pub urgent_minutes: i64,
+}
+
+/// The arguments for [`Command::Digest`].
+#[derive(Clone, Debug, clap::Args)]
+pub struct DigestArgs {
+    /// Send the digest through the configured SMTP server instead of printing
+    /// it.
+    #[arg(long)]
+    pub send: bool,
+}
+
+/// The arguments for [`Command::Notify`].
+#[derive(Clone, Debug, clap::Args)]
+pub struct NotifyArgs {
+    /// The ID of the reminder.
+    pub id: ReminderId,
+}
+
+/// The arguments for [`Command::Systemd`].
+#[derive(Clone, Debug, clap::Args)]
+pub struct SystemdArgs {
+    #[command(subcommand)]
+    pub command: SystemdSubcommand,
+}
+
+/// A command for generating systemd units.
+#[derive(Clone, Debug, Subcommand)]
+pub enum SystemdSubcommand {
+    /// Write a timer and service for each reminder into a directory.
+    Export(SystemdExportArgs),
+    /// Write a timer and service for each reminder that is not done into
+    /// ~/.config/systemd/user, remove the ones for other reminders, and
+    /// enable the timers.
+    Sync(SystemdSyncArgs),
+}
+
+/// The arguments for [`SystemdSubcommand::Export`].
+#[derive(Clone, Debug, clap::Args)]
+pub struct SystemdExportArgs {
+    /// The directory to write the units to.
+    pub dir: PathBuf,
+    #[command(flatten)]
+    pub filter: FilterArgs,
+}
+
+/// The arguments for [`SystemdSubcommand::Sync`].
+#[derive(Clone, Debug, clap::Args)]
+pub struct SystemdSyncArgs {
+    /// The directory to sync the units into, instead of the user's units.
+    #[arg(long)]
+    pub dir: Option<PathBuf>,
+    /// Only write the units, without reloading systemd or enabling the
+    /// timers.
+    #[arg(long)]
+    pub no_systemctl: bool,
+}
+
+/// Arguments for choosing how often a reminder occurs.
+#[derive(Clone, Debug, clap::Args)]
+#[group(multiple = false)]
+pub struct FrequencyArgs {
+    /// Occur once on a date, like "2024-03-01", "today" or "tomorrow".
+    #[arg(long, value_name = "DATE", value_parser = parse_date)]
+    pub on: Option<NaiveDate>,
+    /// Occur every day.
+    #[arg(long)]
+    pub daily: bool,
+    /// Occur on days of the week, like "mon,wed" or "weekdays".
+    #[arg(long, value_name = "DAYS")]
+    pub weekly: Option<ReminderDaysOfWeek>,
+    /// Occur on days of the month, like "1,15".
+    #[arg(
+        long,
+        value_name = "DATES",
+        value_delimiter = ',',
+        value_parser = clap::value_parser!(u8).range(1..=31),
+    )]
+    pub monthly: Vec<u8>,
+    /// Occur on days of the year, like "03-01,12-25".
+    #[arg(long, value_name = "DATES", value_delimiter = ',')]
+    pub yearly: Vec<ReminderYearlyDate>,
+    /// Occur according to an RFC 5545 RRULE, like "FREQ=WEEKLY;BYDAY=MO,WE".
+    /// A time of day set with BYHOUR and BYMINUTE replaces the chosen time.
+    #[arg(long, value_name = "RULE")]
+    pub rrule: Option<Rrule>,
+    /// Occur according to a five-field cron expression, like "30 12 * * 1-5".
+    /// The expression sets the time of day.
+    #[arg(long, value_name = "EXPRESSION", conflicts_with_all = ["at", "from", "all_day"])]
+    pub cron: Option<CronExpression>,
+    /// Occur according to a schedule, like "weekly mon,wed @ 12:30-16:30" or
+    /// "monthly 1,15 @ all-day". The schedule sets the time of day.
+    #[arg(long, value_name = "SCHEDULE", conflicts_with_all = ["at", "from", "all_day"])]
+    pub schedule: Option<ReminderFrequency>,
+}
+
+impl FrequencyArgs {
+    /// Gets the chosen frequency at the given time of day, if one was chosen.
+    pub fn frequency(&self, time: ReminderTimeOfDay) -> Option<ReminderFrequency> {
+        if let Some(date) = self.on {
+            return Some(ReminderFrequency::Once(ReminderFrequencyOnce {
+                date,
+                time,
+            }));
+        }
+        if self.daily {
+            return Some(ReminderFrequency::Daily(ReminderFrequencyDaily { time }));
+        }
+        if let Some(days) = self.weekly {
+            return Some(ReminderFrequency::Weekly(ReminderFrequencyWeekly {
+                days,
+                time,
+            }));
+        }
+        if !self.monthly.is_empty() {
+            return Some(ReminderFrequency::Monthly(ReminderFrequencyMonthly {
+                dates: self.monthly.clone(),
+                time,
+            }));
+        }
+        if !self.yearly.is_empty() {
+            return Some(ReminderFrequency::Yearly(ReminderFrequencyYearly {
+                dates: self.yearly.clone(),
+                time,
+            }));
+        }
+        if let Some(rule) = &self.rrule {
+            return Some(rule.frequency(time));
+        }
+        if let Some(expression) = &self.cron {
+            return Some(expression.frequency().clone());
+        }
+        if let Some(schedule) = &self.schedule {
+            return Some(schedule.clone());
+        }
+
+        None
+    }
+}
+
+/// Arguments for choosing the time of day a reminder occurs.
+#[derive(Clone, Debug, clap::Args)]
+pub struct TimeArgs {
+    /// Occur at a time of day, like "12:30".
+    #[arg(long, value_name = "TIME", value_parser = parse_time, conflicts_with_all = ["from", "all_day"])]
+    pub at: Option<NaiveTime>,
+    /// Occur during a range of time starting at this time.
+    #[arg(long, value_name = "TIME", value_parser = parse_time, requires = "until")]
+    pub from: Option<NaiveTime>,
+    /// Occur during a range of time ending at this time.
+    #[arg(long, value_name = "TIME", value_parser = parse_time, requires = "from")]
+    pub until: Option<NaiveTime>,
+    /// Occur for the whole day.
+    #[arg(long, conflicts_with = "from")]
+    pub all_day: bool,
+}
+
+impl TimeArgs {
+    /// Gets the chosen time of day, if one was chosen.
+    pub fn time_of_day(&self) -> Option<ReminderTimeOfDay> {
+        if let Some(time) = self.at {
+            return Some(ReminderTimeOfDay::Time { time });
+        }
+        if let (Some(start), Some(end)) = (self.from, self.until) {
+            return Some(ReminderTimeOfDay::TimeRange { start, end });
+        }
+        if self.all_day {
+            return Some(ReminderTimeOfDay::AllDay);
+        }
+
+        None
+    }
+}
+
+/// Parses a date, like "2024-03-01", "today" or "tomorrow".
+pub fn parse_date(s: &str) -> Result<NaiveDate, String> {
+    let today = Local::now().date_naive();
+    match s {
+        "today" => Ok(today),
+        "tomorrow" => today
+            .succ_opt()
+            .ok_or_else(|| "date out of range".to_string()),
+        _ => s
+            .parse()
+            .map_err(|_| format!("expected a date like \"2024-03-01\", got {s:?}")),
+    }
+}
+
+/// Parses a time of day, like "12:30".
+fn parse_time(s: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(s, "%H:%M")
+        .or_else(|_| NaiveTime::parse_from_str(s, "%H:%M:%S"))
+        .map_err(|_| format!("expected a time like \"12:30\", got {s:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::{Args, Command};
+
+    #[test]
+    fn add_rejects_impossible_yearly_dates() {
+        let parse = |dates: &str| {
+            Args::try_parse_from(["desktop-reminders-iced", "add", "Rent", "--yearly", dates])
+        };
+
+        let args = parse("02-29,12-31").unwrap();
+        let Some(Command::Add(add)) = args.command else {
+            panic!("expected the add command");
+        };
+        let frequency = add.frequency.frequency(Default::default()).unwrap();
+        assert_eq!(frequency.to_string(), "yearly 02-29,12-31 @ all-day");
+
+        for dates in ["02-30", "03-01,04-31", "13-01"] {
+            let error = parse(dates).unwrap_err();
+            assert_eq!(
+                error.kind(),
+                clap::error::ErrorKind::ValueValidation,
+                "{dates}"
+            );
+        }
+        let error = parse("02-31").unwrap_err().to_string();
+        assert!(error.contains("February has no day 31"), "{error}");
+    }
+}
@@ -0,0 +1,239 @@
+use chrono::{Local, Timelike};
+use color_eyre::eyre::bail;
+
+use crate::{
+    digest::{DigestEmail, DigestReport},
+    ipc::Request,
+    models::reminders::{Reminder, ReminderAlert, ReminderId, ReminderOccurrence},
+    notifications::notify_alert,
+    storage::{SettingsFile, StoreFile},
+};
+
+use super::{
+    connection::Connection,
+    output::print_reminders,
+    status::status,
+    systemd::systemd,
+    transfer::{export, import},
+    AddArgs, Command, DigestArgs, DoneArgs, EditArgs, ListArgs, NotifyArgs, RemoveArgs, SnoozeArgs,
+};
+
+/// Runs a command, preferring to send it to the running instance and editing
+/// the persisted store directly otherwise.
+pub fn run(command: Command) -> color_eyre::Result<()> {
+    let mut connection = Connection::open()?;
+    match command {
+        Command::Add(args) => add(&mut connection, args)?,
+        Command::List(args) => list(&mut connection, args)?,
+        Command::Done(args) => done(&mut connection, args)?,
+        Command::Snooze(args) => snooze(&mut connection, args)?,
+        Command::Edit(args) => edit(&mut connection, args)?,
+        Command::Remove(args) => remove(&mut connection, args)?,
+        Command::Export(args) => export(&mut connection, args)?,
+        Command::Import(args) => import(&mut connection, args)?,
+        Command::Status(args) => status(&mut connection, args)?,
+        Command::Digest(args) => digest(args)?,
+        Command::Notify(args) => notify(&mut connection, args)?,
+        Command::Systemd(args) => systemd(&mut connection, args)?,
+    }
+
+    connection.close()
+}
+
+/// Adds a reminder.
+fn add(connection: &mut Connection, args: AddArgs) -> color_eyre::Result<()> {
+    let time = args.time.time_of_day().unwrap_or_default();
+    let Some(frequency) = args.frequency.frequency(time) else {
+        bail!(
+            "a frequency is required: use --on, --daily, --weekly, --monthly, --yearly, --rrule, --cron or --schedule"
+        );
+    };
+
+    let reminder = connection.reminder(Request::Add {
+        reminder: Reminder {
+            id: Default::default(),
+            frequency,
+            message: args.message,
+            completed: false,
+            snoozed_until: None,
+            actions: Vec::new(),
+            contact: None,
+        },
+    })?;
+
+    if args.json {
+        print_reminders([&reminder], Local::now().naive_local(), true)?;
+    } else {
+        println!("Added reminder {}", reminder.id);
+    }
+
+    Ok(())
+}
+
+/// Lists reminders.
+fn list(connection: &mut Connection, args: ListArgs) -> color_eyre::Result<()> {
+    let now = Local::now().naive_local();
+    let reminders = connection.reminders()?;
+    let reminders = reminders
+        .iter()
+        .filter(|reminder| args.filter.matches(reminder, now.date()));
+    print_reminders(reminders, now, args.json)
+}
+
+/// Marks reminders as done or not done.
+fn done(connection: &mut Connection, args: DoneArgs) -> color_eyre::Result<()> {
+    check_ids(&connection.reminders()?, &args.ids)?;
+    let mut changed = Vec::new();
+    for &id in &args.ids {
+        changed.push(connection.reminder(Request::Complete {
+            id,
+            undo: args.undo,
+        })?);
+    }
+
+    if args.json {
+        print_reminders(&changed, Local::now().naive_local(), true)?;
+    } else {
+        for id in args.ids {
+            if args.undo {
+                println!("Marked reminder {id} as not done");
+            } else {
+                println!("Marked reminder {id} as done");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Snoozes reminders.
+fn snooze(connection: &mut Connection, args: SnoozeArgs) -> color_eyre::Result<()> {
+    check_ids(&connection.reminders()?, &args.ids)?;
+    let mut changed = Vec::new();
+    for &id in &args.ids {
+        changed.push(connection.reminder(Request::Snooze {
+            id,
+            minutes: args.minutes,
+        })?);
+    }
+
+    if args.json {
+        print_reminders(&changed, Local::now().naive_local(), true)?;
+    } else {
+        for reminder in &changed {
+            if let Some(until) = reminder.snoozed_until {
+                println!(
+                    "Snoozed reminder {} until {}",
+                    reminder.id,
+                    until.format("%Y-%m-%d %H:%M")
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Changes a reminder.
+fn edit(connection: &mut Connection, args: EditArgs) -> color_eyre::Result<()> {
+    let reminders = connection.reminders()?;
+    let Some(mut reminder) = reminders
+        .into_iter()
+        .find(|reminder| reminder.id == args.id)
+    else {
+        bail!("no reminder with ID {}", args.id);
+    };
+
+    if let Some(message) = args.message {
+        reminder.message = message;
+    }
+    let time = args
+        .time
+        .time_of_day()
+        .unwrap_or(*reminder.frequency.time_of_day());
+    if let Some(frequency) = args.frequency.frequency(time) {
+        reminder.frequency = frequency;
+    } else {
+        *reminder.frequency.time_of_day_mut() = time;
+    }
+
+    let reminder = connection.reminder(Request::Update { reminder })?;
+    if args.json {
+        print_reminders([&reminder], Local::now().naive_local(), true)?;
+    } else {
+        println!("Changed reminder {}", reminder.id);
+    }
+
+    Ok(())
+}
+
+/// Removes reminders.
+fn remove(connection: &mut Connection, args: RemoveArgs) -> color_eyre::Result<()> {
+    check_ids(&connection.reminders()?, &args.ids)?;
+    let mut removed = Vec::new();
+    for &id in &args.ids {
+        removed.push(connection.reminder(Request::Remove { id })?);
+    }
+
+    if args.json {
+        print_reminders(&removed, Local::now().naive_local(), true)?;
+    } else {
+        for reminder in &removed {
+            println!("Removed reminder {}", reminder.id);
+        }
+    }
+
+    Ok(())
+}
+
+/// Prints or sends the morning digest. The digest includes completions, which
+/// are only available from the persisted store.
+fn digest(args: DigestArgs) -> color_eyre::Result<()> {
+    let store = StoreFile::from_env()?.load()?;
+    let settings = SettingsFile::from_env()?.load()?.digest;
+    let report = DigestReport::new(&store, Local::now().naive_local());
+    let email = DigestEmail::render(&settings, &report)?;
+    if args.send {
+        email.send(&settings)?;
+        println!("Sent digest to {}", settings.to.join(", "));
+    } else {
+        println!("Subject: {}", email.subject);
+        println!();
+        print!("{}", email.body);
+    }
+
+    Ok(())
+}
+
+/// Shows the notification for a reminder, unless it is done or snoozed.
+fn notify(connection: &mut Connection, args: NotifyArgs) -> color_eyre::Result<()> {
+    let reminders = connection.reminders()?;
+    let Some(reminder) = reminders.iter().find(|reminder| reminder.id == args.id) else {
+        bail!("no reminder with ID {}", args.id);
+    };
+
+    let now = Local::now().naive_local();
+    if reminder.completed || reminder.snoozed_until.is_some_and(|until| now < until) {
+        return Ok(());
+    }
+
+    let alert = ReminderAlert {
+        occurrence: ReminderOccurrence {
+            id: reminder.id,
+            at: now.with_second(0).unwrap_or(now),
+        },
+        missed: false,
+    };
+    notify_alert(reminder, &alert)
+}
+
+/// Checks that every ID refers to one of the reminders.
+fn check_ids(reminders: &[Reminder], ids: &[ReminderId]) -> color_eyre::Result<()> {
+    for &id in ids {
+        if !reminders.iter().any(|reminder| reminder.id == id) {
+            bail!("no reminder with ID {id}");
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,98 @@
+use std::time::Duration;
+
+use color_eyre::eyre::bail;
+use tracing::debug;
+
+use crate::{
+    ipc::{socket_path, IpcClient, Request, Response},
+    models::{reminders::Reminder, store::ReminderStore},
+    storage::StoreFile,
+};
+
+/// Where the reminders are managed.
+pub enum Connection {
+    /// The running instance manages the reminders.
+    Instance(IpcClient),
+    /// No instance is running, so the store is edited directly.
+    File {
+        file: StoreFile,
+        store: ReminderStore,
+        changed: bool,
+    },
+}
+
+impl Connection {
+    /// Connects to the running instance, or loads the store if there is none.
+    pub fn open() -> color_eyre::Result<Self> {
+        let path = socket_path();
+        if let Some(client) = IpcClient::connect(&path)? {
+            debug!(path = %path.display(), "connected to the running instance");
+            return Ok(Connection::Instance(client));
+        }
+
+        let file = StoreFile::from_env()?;
+        let store = file.load()?;
+        Ok(Connection::File {
+            file,
+            store,
+            changed: false,
+        })
+    }
+
+    /// Sends a request.
+    pub fn call(&mut self, request: Request) -> color_eyre::Result<Response> {
+        match self {
+            Connection::Instance(client) => client.call(request),
+            Connection::File { store, changed, .. } => {
+                *changed |= request.is_change();
+                request
+                    .apply(store)
+                    .map_err(|error| color_eyre::eyre::eyre!(error.message))
+            }
+        }
+    }
+
+    /// Gets a single reminder from the response to a request.
+    pub fn reminder(&mut self, request: Request) -> color_eyre::Result<Reminder> {
+        match self.call(request)? {
+            Response::Reminder(reminder) => Ok(reminder),
+            response => bail!("expected a reminder, got {response:?}"),
+        }
+    }
+
+    /// Gets all the reminders.
+    pub fn reminders(&mut self) -> color_eyre::Result<Vec<Reminder>> {
+        match self.call(Request::List)? {
+            Response::Reminders(reminders) => Ok(reminders),
+            response => bail!("expected a list of reminders, got {response:?}"),
+        }
+    }
+
+    /// Waits until the reminders might have changed or `timeout` passes,
+    /// whichever comes first.
+    pub fn wait_for_change(&mut self, timeout: Duration) -> color_eyre::Result<()> {
+        match self {
+            Connection::Instance(client) => {
+                client.wait_for_notification(timeout)?;
+            }
+            Connection::File { file, store, .. } => {
+                std::thread::sleep(timeout);
+                *store = file.load()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Saves any changes made to the store.
+    pub fn close(self) -> color_eyre::Result<()> {
+        match self {
+            Connection::File {
+                file,
+                store,
+                changed: true,
+            } => file.save(&store),
+            _ => Ok(()),
+        }
+    }
+}
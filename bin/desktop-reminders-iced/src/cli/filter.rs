@@ -0,0 +1,122 @@
+use std::str::FromStr;
+
+use chrono::{Duration, NaiveDate};
+use clap::ValueEnum;
+
+use crate::models::reminders::{Reminder, ReminderFrequency};
+
+use super::parse_date;
+
+/// Arguments for choosing which reminders a command applies to.
+#[derive(Clone, Debug, Default, clap::Args)]
+pub struct FilterArgs {
+    /// Only include reminders due on a day: "today", "tomorrow", "week" (the
+    /// next seven days) or a date like "2024-03-01".
+    #[arg(long, value_name = "WHEN")]
+    pub due: Option<DueFilter>,
+    /// Only include reminders of these kinds.
+    #[arg(long, value_enum, value_delimiter = ',')]
+    pub kind: Vec<KindFilter>,
+    /// Include completed reminders.
+    #[arg(short, long)]
+    pub all: bool,
+}
+
+impl FilterArgs {
+    /// Checks whether a reminder passes the filter, given today's date.
+    pub fn matches(&self, reminder: &Reminder, today: NaiveDate) -> bool {
+        if reminder.completed && !self.all {
+            return false;
+        }
+        if !self.kind.is_empty() && !self.kind.iter().any(|kind| kind.matches(reminder)) {
+            return false;
+        }
+        if let Some(due) = self.due {
+            if !due.matches(reminder, today) {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+/// When a reminder is due.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DueFilter {
+    /// Due today.
+    Today,
+    /// Due tomorrow.
+    Tomorrow,
+    /// Due within the next seven days, including today.
+    Week,
+    /// Due on a specific date.
+    On(NaiveDate),
+}
+
+impl DueFilter {
+    /// Gets the first and last days included by the filter.
+    pub fn days(self, today: NaiveDate) -> (NaiveDate, NaiveDate) {
+        match self {
+            DueFilter::Today => (today, today),
+            DueFilter::Tomorrow => (today + Duration::days(1), today + Duration::days(1)),
+            DueFilter::Week => (today, today + Duration::days(6)),
+            DueFilter::On(date) => (date, date),
+        }
+    }
+
+    /// Checks whether a reminder is due within the filter.
+    pub fn matches(self, reminder: &Reminder, today: NaiveDate) -> bool {
+        let (first, last) = self.days(today);
+        let snoozed = reminder
+            .snoozed_until
+            .is_some_and(|until| first <= until.date() && until.date() <= last);
+        snoozed
+            || first
+                .iter_days()
+                .take_while(|&date| date <= last)
+                .any(|date| reminder.frequency.occurs_on(date))
+    }
+}
+
+impl FromStr for DueFilter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "today" => Ok(DueFilter::Today),
+            "tomorrow" => Ok(DueFilter::Tomorrow),
+            "week" => Ok(DueFilter::Week),
+            _ => parse_date(s).map(DueFilter::On),
+        }
+    }
+}
+
+/// A kind of reminder frequency.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, ValueEnum)]
+pub enum KindFilter {
+    /// Reminders that occur once.
+    Once,
+    /// Reminders that occur daily.
+    Daily,
+    /// Reminders that occur weekly.
+    Weekly,
+    /// Reminders that occur monthly.
+    Monthly,
+    /// Reminders that occur yearly.
+    Yearly,
+}
+
+impl KindFilter {
+    /// Checks whether a reminder is of this kind.
+    pub fn matches(self, reminder: &Reminder) -> bool {
+        matches!(
+            (self, &reminder.frequency),
+            (KindFilter::Once, ReminderFrequency::Once(_))
+                | (KindFilter::Daily, ReminderFrequency::Daily(_))
+                | (KindFilter::Weekly, ReminderFrequency::Weekly(_))
+                | (KindFilter::Monthly, ReminderFrequency::Monthly(_))
+                | (KindFilter::Yearly, ReminderFrequency::Yearly(_))
+        )
+    }
+}
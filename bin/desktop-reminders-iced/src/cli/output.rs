@@ -0,0 +1,98 @@
+use std::io::Write;
+
+use chrono::NaiveDateTime;
+use serde::Serialize;
+
+use crate::{
+    formats::{CronExpression, Rrule},
+    models::reminders::Reminder,
+};
+
+/// A reminder as printed by the command line interface.
+#[derive(Serialize)]
+struct ReminderOutput<'a> {
+    #[serde(flatten)]
+    reminder: &'a Reminder,
+    next_occurrence: Option<NaiveDateTime>,
+    /// The schedule for the reminder, like `weekly mon,wed @ 12:30`.
+    schedule: String,
+    /// The RRULE for the reminder, if it can be written as one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rrule: Option<String>,
+    /// The cron expression for the reminder, if it can be written as one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cron: Option<String>,
+}
+
+/// Prints reminders to standard output, either as a table or as JSON.
+pub fn print_reminders<'a>(
+    reminders: impl IntoIterator<Item = &'a Reminder>,
+    now: NaiveDateTime,
+    json: bool,
+) -> color_eyre::Result<()> {
+    let reminders: Vec<_> = reminders
+        .into_iter()
+        .map(|reminder| ReminderOutput {
+            reminder,
+            next_occurrence: reminder.next_occurrence_after(now),
+            schedule: reminder.frequency.to_string(),
+            rrule: Rrule::try_from(&reminder.frequency)
+                .ok()
+                .map(|rule| rule.to_string()),
+            cron: CronExpression::try_from(&reminder.frequency)
+                .ok()
+                .map(|expression| expression.to_string()),
+        })
+        .collect();
+
+    let mut stdout = std::io::stdout().lock();
+    if json {
+        serde_json::to_writer_pretty(&mut stdout, &reminders)?;
+        writeln!(stdout)?;
+        return Ok(());
+    }
+
+    let rows: Vec<_> = reminders
+        .iter()
+        .map(|output| {
+            let next = output.next_occurrence.map_or_else(
+                || "-".to_string(),
+                |next| next.format("%Y-%m-%d %H:%M").to_string(),
+            );
+            let done = if output.reminder.completed { "x" } else { " " };
+            [
+                output.reminder.id.to_string(),
+                done.to_string(),
+                next,
+                output.reminder.frequency.describe(),
+                output.reminder.message.clone(),
+            ]
+        })
+        .collect();
+    let header = [
+        "ID".to_string(),
+        " ".to_string(),
+        "NEXT".to_string(),
+        "SCHEDULE".to_string(),
+        "MESSAGE".to_string(),
+    ];
+
+    let mut widths = [0; 4];
+    for row in std::iter::once(&header).chain(&rows) {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    for [id, done, next, schedule, message] in std::iter::once(&header).chain(&rows) {
+        writeln!(
+            stdout,
+            "{id:>w0$}  {done:w1$}  {next:w2$}  {schedule:w3$}  {message}",
+            w0 = widths[0],
+            w1 = widths[1],
+            w2 = widths[2],
+            w3 = widths[3],
+        )?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,379 @@
+use std::{io::Write, time::Duration as StdDuration};
+
+use chrono::{Duration, Local, NaiveDateTime};
+use clap::ValueEnum;
+use serde::Serialize;
+use serde_json::json;
+
+use crate::{ipc::Request, models::reminders::Reminder};
+
+use super::{connection::Connection, StatusArgs};
+
+/// How often `status --watch` prints, so the countdown stays current.
+const WATCH_INTERVAL: StdDuration = StdDuration::from_secs(30);
+
+/// The color used by i3blocks for overdue reminders.
+const OVERDUE_COLOR: &str = "#ff5555";
+
+/// The color used by i3blocks for urgent reminders.
+const URGENT_COLOR: &str = "#ffb86c";
+
+/// How `status` prints its output.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, ValueEnum)]
+pub enum StatusFormat {
+    /// A single line of text, like "Standup in 12m". Works with polybar.
+    #[default]
+    Text,
+    /// The JSON object expected by waybar's custom modules.
+    Waybar,
+    /// The JSON object expected by i3blocks with `format=json`.
+    I3blocks,
+    /// A JSON object with all the details.
+    Json,
+}
+
+/// How pressing the most relevant reminder is. The name is used as a CSS
+/// class by status bars.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StatusClass {
+    /// There are no upcoming reminders.
+    None,
+    /// The next reminder is a while away.
+    Upcoming,
+    /// The next reminder is coming up soon.
+    Urgent,
+    /// A reminder was due and has not been completed.
+    Overdue,
+}
+
+/// The most relevant reminder at some point in time.
+#[derive(Clone, Debug)]
+struct Status<'a> {
+    reminder: Option<&'a Reminder>,
+    at: Option<NaiveDateTime>,
+    now: NaiveDateTime,
+    class: StatusClass,
+}
+
+impl<'a> Status<'a> {
+    /// Finds the oldest overdue reminder, or the next upcoming reminder if
+    /// none are overdue.
+    fn new(reminders: &'a [Reminder], now: NaiveDateTime, urgent: Duration) -> Self {
+        let overdue = reminders
+            .iter()
+            .filter_map(|reminder| Some((reminder, reminder.overdue_since(now)?)))
+            .min_by_key(|&(_, at)| at);
+        if let Some((reminder, at)) = overdue {
+            return Self {
+                reminder: Some(reminder),
+                at: Some(at),
+                now,
+                class: StatusClass::Overdue,
+            };
+        }
+
+        let next = reminders
+            .iter()
+            .filter_map(|reminder| Some((reminder, reminder.next_occurrence_after(now)?)))
+            .min_by_key(|&(_, at)| at);
+        match next {
+            Some((reminder, at)) => Self {
+                reminder: Some(reminder),
+                at: Some(at),
+                now,
+                class: if at - now <= urgent {
+                    StatusClass::Urgent
+                } else {
+                    StatusClass::Upcoming
+                },
+            },
+            None => Self {
+                reminder: None,
+                at: None,
+                now,
+                class: StatusClass::None,
+            },
+        }
+    }
+
+    /// Gets the countdown until the reminder, like "in 2h 5m" or "2h 5m ago".
+    fn countdown(&self) -> Option<String> {
+        let at = self.at?;
+        let until = at - self.now;
+        if until >= Duration::zero() {
+            Some(format!("in {}", format_duration(until)))
+        } else {
+            Some(format!("{} ago", format_duration(-until)))
+        }
+    }
+
+    /// Gets the full text of the status.
+    fn text(&self) -> String {
+        let Some(reminder) = self.reminder else {
+            return "No reminders".to_string();
+        };
+
+        let countdown = self.countdown().unwrap_or_default();
+        match self.class {
+            StatusClass::Overdue => format!("{} (due {countdown})", reminder.message),
+            _ => format!("{} {countdown}", reminder.message),
+        }
+    }
+
+    /// Gets a shorter version of the text for narrow bars.
+    fn short_text(&self) -> String {
+        let Some(at) = self.at else {
+            return String::new();
+        };
+
+        let until = at - self.now;
+        format_duration(if until < Duration::zero() {
+            -until
+        } else {
+            until
+        })
+    }
+
+    /// Gets a longer description, used for tooltips.
+    fn tooltip(&self) -> String {
+        match (self.reminder, self.at) {
+            (Some(reminder), Some(at)) => {
+                format!(
+                    "{}\n{}",
+                    reminder.message,
+                    at.format("%a %Y-%m-%d %-I:%M %p")
+                )
+            }
+            _ => "No upcoming reminders".to_string(),
+        }
+    }
+
+    /// Formats the status for a status bar.
+    fn format(&self, format: StatusFormat) -> String {
+        match format {
+            StatusFormat::Text => self.text(),
+            StatusFormat::Waybar => json!({
+                "text": self.text(),
+                "alt": self.class,
+                "tooltip": self.tooltip(),
+                "class": self.class,
+            })
+            .to_string(),
+            StatusFormat::I3blocks => {
+                let color = match self.class {
+                    StatusClass::Overdue => Some(OVERDUE_COLOR),
+                    StatusClass::Urgent => Some(URGENT_COLOR),
+                    StatusClass::None | StatusClass::Upcoming => None,
+                };
+                json!({
+                    "full_text": self.text(),
+                    "short_text": self.short_text(),
+                    "color": color,
+                    "urgent": self.class == StatusClass::Overdue,
+                })
+                .to_string()
+            }
+            StatusFormat::Json => json!({
+                "id": self.reminder.map(|reminder| reminder.id),
+                "message": self.reminder.map(|reminder| &reminder.message),
+                "at": self.at,
+                "seconds_until": self.at.map(|at| (at - self.now).num_seconds()),
+                "countdown": self.countdown(),
+                "text": self.text(),
+                "class": self.class,
+            })
+            .to_string(),
+        }
+    }
+}
+
+/// Prints the next upcoming or overdue reminder, either once or every time
+/// it changes.
+pub fn status(connection: &mut Connection, args: StatusArgs) -> color_eyre::Result<()> {
+    let urgent = Duration::minutes(args.urgent_minutes);
+    if !args.watch {
+        let reminders = connection.reminders()?;
+        let status = Status::new(&reminders, Local::now().naive_local(), urgent);
+        println!("{}", status.format(args.format));
+        return Ok(());
+    }
+
+    connection.call(Request::Subscribe)?;
+    let mut stdout = std::io::stdout().lock();
+    let mut last_line = None;
+    loop {
+        let reminders = connection.reminders()?;
+        let status = Status::new(&reminders, Local::now().naive_local(), urgent);
+        let line = status.format(args.format);
+        if last_line.as_ref() != Some(&line) {
+            writeln!(stdout, "{line}")?;
+            stdout.flush()?;
+            last_line = Some(line);
+        }
+
+        // The running instance may have stopped, so reconnect if needed
+        if connection.wait_for_change(WATCH_INTERVAL).is_err() {
+            *connection = Connection::open()?;
+            connection.call(Request::Subscribe)?;
+        }
+    }
+}
+
+/// Formats a duration compactly, like "1d 2h", "2h 5m" or "12m".
+fn format_duration(duration: Duration) -> String {
+    let minutes = duration.num_minutes();
+    let (days, hours, minutes) = (minutes / (24 * 60), minutes / 60 % 24, minutes % 60);
+    match (days, hours) {
+        (0, 0) => format!("{minutes}m"),
+        (0, _) => format!("{hours}h {minutes}m"),
+        _ => format!("{days}d {hours}h"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, NaiveDateTime};
+    use serde_json::json;
+
+    use crate::models::reminders::{Reminder, ReminderId};
+
+    use super::{format_duration, Status, StatusClass, StatusFormat};
+
+    fn reminder(id: u64, schedule: &str, message: &str) -> Reminder {
+        Reminder {
+            id: ReminderId(id),
+            frequency: schedule.parse().unwrap(),
+            message: message.to_string(),
+            completed: false,
+            snoozed_until: None,
+            actions: Vec::new(),
+            contact: None,
+        }
+    }
+
+    fn now() -> NaiveDateTime {
+        "2024-03-01T12:00:00".parse().unwrap()
+    }
+
+    fn status(reminders: &[Reminder]) -> Status<'_> {
+        Status::new(reminders, now(), Duration::minutes(15))
+    }
+
+    fn format(reminders: &[Reminder], format: StatusFormat) -> serde_json::Value {
+        serde_json::from_str(&status(reminders).format(format)).unwrap()
+    }
+
+    #[test]
+    fn formats_durations() {
+        let cases = [
+            (Duration::zero(), "0m"),
+            (Duration::seconds(59), "0m"),
+            (Duration::minutes(59), "59m"),
+            (Duration::minutes(60), "1h 0m"),
+            (Duration::minutes(24 * 60 - 1), "23h 59m"),
+            (Duration::days(1), "1d 0h"),
+            (Duration::days(1) + Duration::minutes(119), "1d 1h"),
+            (Duration::days(12) + Duration::hours(5), "12d 5h"),
+        ];
+        for (duration, expected) in cases {
+            assert_eq!(format_duration(duration), expected, "{duration}");
+        }
+    }
+
+    #[test]
+    fn picks_class_by_how_soon_reminders_are() {
+        let mut completed = reminder(2, "once 2024-03-01 @ 11:00", "Done");
+        completed.completed = true;
+        let cases = [
+            (vec![], StatusClass::None),
+            (vec![completed.clone()], StatusClass::None),
+            (
+                vec![reminder(1, "once 2024-03-01 @ 12:15", "Soon")],
+                StatusClass::Urgent,
+            ),
+            (
+                vec![reminder(1, "once 2024-03-01 @ 12:16", "Later")],
+                StatusClass::Upcoming,
+            ),
+            (
+                vec![reminder(1, "once 2024-03-01 @ 12:00", "Now")],
+                StatusClass::Overdue,
+            ),
+            (
+                vec![
+                    reminder(1, "daily @ 12:05", "Daily"),
+                    reminder(3, "once 2024-02-01 @ all-day", "Old"),
+                    completed,
+                ],
+                StatusClass::Overdue,
+            ),
+        ];
+        for (reminders, expected) in cases {
+            assert_eq!(status(&reminders).class, expected, "{reminders:?}");
+        }
+    }
+
+    #[test]
+    fn formats_waybar_json() {
+        let reminders = [reminder(1, "once 2024-03-01 @ 11:30", "Stretch")];
+        assert_eq!(
+            format(&reminders, StatusFormat::Waybar),
+            json!({
+                "text": "Stretch (due 30m ago)",
+                "alt": "overdue",
+                "tooltip": "Stretch\nFri 2024-03-01 11:30 AM",
+                "class": "overdue",
+            })
+        );
+        assert_eq!(
+            format(&[], StatusFormat::Waybar),
+            json!({
+                "text": "No reminders",
+                "alt": "none",
+                "tooltip": "No upcoming reminders",
+                "class": "none",
+            })
+        );
+    }
+
+    #[test]
+    fn formats_i3blocks_json() {
+        let cases = [
+            (
+                reminder(1, "once 2024-03-01 @ 11:30", "Stretch"),
+                json!({
+                    "full_text": "Stretch (due 30m ago)",
+                    "short_text": "30m",
+                    "color": "#ff5555",
+                    "urgent": true,
+                }),
+            ),
+            (
+                reminder(1, "daily @ 12:10", "Standup"),
+                json!({
+                    "full_text": "Standup in 10m",
+                    "short_text": "10m",
+                    "color": "#ffb86c",
+                    "urgent": false,
+                }),
+            ),
+            (
+                reminder(1, "weekly mon @ 14:30", "Review"),
+                json!({
+                    "full_text": "Review in 3d 2h",
+                    "short_text": "3d 2h",
+                    "color": null,
+                    "urgent": false,
+                }),
+            ),
+        ];
+        for (reminder, expected) in cases {
+            assert_eq!(
+                format(std::slice::from_ref(&reminder), StatusFormat::I3blocks),
+                expected,
+                "{reminder:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,278 @@
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use chrono::Local;
+use color_eyre::eyre::{bail, Context, ContextCompat};
+
+use crate::{
+    formats::{
+        systemd_units, SystemdCommand, SystemdUnits, SYSTEMD_UNIT_HEADER, SYSTEMD_UNIT_PREFIX,
+    },
+    ipc::SOCKET_PATH_VAR,
+    storage::{SETTINGS_PATH_VAR, STORE_PATH_VAR},
+};
+
+use super::{
+    connection::Connection, transfer::print_warnings, SystemdArgs, SystemdExportArgs,
+    SystemdSubcommand, SystemdSyncArgs,
+};
+
+/// Exports reminders as systemd units, or syncs them into the user's units.
+pub fn systemd(connection: &mut Connection, args: SystemdArgs) -> color_eyre::Result<()> {
+    match args.command {
+        SystemdSubcommand::Export(args) => export(connection, args),
+        SystemdSubcommand::Sync(args) => sync(connection, args),
+    }
+}
+
+/// Writes units for reminders into a directory.
+fn export(connection: &mut Connection, args: SystemdExportArgs) -> color_eyre::Result<()> {
+    let now = Local::now().naive_local();
+    let reminders: Vec<_> = connection
+        .reminders()?
+        .into_iter()
+        .filter(|reminder| args.filter.matches(reminder, now.date()))
+        .collect();
+
+    let converted = systemd_units(&reminders, &notify_command()?, now);
+    print_warnings(&converted.warnings);
+    std::fs::create_dir_all(&args.dir)
+        .wrap_err_with(|| format!("failed to create {}", args.dir.display()))?;
+    for units in &converted.value {
+        write_units(&args.dir, units)?;
+    }
+
+    println!(
+        "Wrote {} to {}",
+        count_timers(converted.value.len()),
+        args.dir.display()
+    );
+    Ok(())
+}
+
+/// Makes the user's units match the reminders that are not done, removing
+/// units generated for reminders that no longer exist.
+fn sync(connection: &mut Connection, args: SystemdSyncArgs) -> color_eyre::Result<()> {
+    let dir = match args.dir {
+        Some(dir) => dir,
+        None => dirs::config_dir()
+            .wrap_err("failed to find the config directory")?
+            .join("systemd")
+            .join("user"),
+    };
+    std::fs::create_dir_all(&dir)
+        .wrap_err_with(|| format!("failed to create {}", dir.display()))?;
+
+    let reminders: Vec<_> = connection
+        .reminders()?
+        .into_iter()
+        .filter(|reminder| !reminder.completed)
+        .collect();
+    let now = Local::now().naive_local();
+    let converted = systemd_units(&reminders, &notify_command()?, now);
+    print_warnings(&converted.warnings);
+
+    let mut changed = 0;
+    for units in &converted.value {
+        if write_units(&dir, units)? {
+            changed += 1;
+        }
+    }
+
+    // Units are only removed if they were generated
+    let current: BTreeSet<_> = converted
+        .value
+        .iter()
+        .flat_map(|units| [units.timer_name(), units.service_name()])
+        .collect();
+    let mut stale = Vec::new();
+    for entry in
+        std::fs::read_dir(&dir).wrap_err_with(|| format!("failed to read {}", dir.display()))?
+    {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let generated = name.starts_with(SYSTEMD_UNIT_PREFIX)
+            && (name.ends_with(".timer") || name.ends_with(".service"))
+            && !current.contains(&name)
+            && std::fs::read_to_string(entry.path())
+                .is_ok_and(|contents| contents.starts_with(SYSTEMD_UNIT_HEADER));
+        if generated {
+            stale.push(name);
+        }
+    }
+
+    if !args.no_systemctl {
+        let stale_timers: Vec<_> = stale
+            .iter()
+            .filter(|name| name.ends_with(".timer"))
+            .map(String::as_str)
+            .collect();
+        if !stale_timers.is_empty() {
+            systemctl(&[&["disable", "--now"], stale_timers.as_slice()].concat())?;
+        }
+    }
+    for name in &stale {
+        let path = dir.join(name);
+        std::fs::remove_file(&path)
+            .wrap_err_with(|| format!("failed to remove {}", path.display()))?;
+    }
+
+    if !args.no_systemctl {
+        systemctl(&["daemon-reload"])?;
+        let timers: Vec<_> = converted
+            .value
+            .iter()
+            .map(SystemdUnits::timer_name)
+            .collect();
+        if !timers.is_empty() {
+            let timers: Vec<_> = timers.iter().map(String::as_str).collect();
+            systemctl(&[&["enable", "--now"], timers.as_slice()].concat())?;
+        }
+    }
+
+    println!(
+        "Synced {} to {} ({changed} changed, {} removed)",
+        count_timers(converted.value.len()),
+        dir.display(),
+        stale.iter().filter(|name| name.ends_with(".timer")).count(),
+    );
+    Ok(())
+}
+
+/// Gets the command generated services run, which is this program's `notify`
+/// command using the same files as this program.
+fn notify_command() -> color_eyre::Result<SystemdCommand> {
+    let program = std::env::current_exe().wrap_err("failed to find the path of this program")?;
+    let environment = [STORE_PATH_VAR, SETTINGS_PATH_VAR, SOCKET_PATH_VAR]
+        .into_iter()
+        .filter_map(|name| {
+            let value = std::env::var_os(name)?;
+            let value = std::path::absolute(PathBuf::from(value)).ok()?;
+            Some((name.to_string(), value.to_string_lossy().into_owned()))
+        })
+        .collect();
+
+    Ok(SystemdCommand {
+        program,
+        args: vec!["notify".to_string()],
+        environment,
+    })
+}
+
+/// Writes a timer and service into a directory, returning whether either
+/// changed.
+fn write_units(dir: &Path, units: &SystemdUnits) -> color_eyre::Result<bool> {
+    let mut changed = false;
+    for (name, contents) in [
+        (units.timer_name(), &units.timer),
+        (units.service_name(), &units.service),
+    ] {
+        let path = dir.join(name);
+        if std::fs::read_to_string(&path).is_ok_and(|existing| existing == *contents) {
+            continue;
+        }
+
+        std::fs::write(&path, contents)
+            .wrap_err_with(|| format!("failed to write {}", path.display()))?;
+        changed = true;
+    }
+
+    Ok(changed)
+}
+
+/// Runs `systemctl --user` with some arguments.
+fn systemctl(args: &[&str]) -> color_eyre::Result<()> {
+    let status = Command::new("systemctl")
+        .arg("--user")
+        .args(args)
+        .status()
+        .wrap_err("failed to run systemctl")?;
+    if !status.success() {
+        bail!("systemctl --user {} failed with {status}", args.join(" "));
+    }
+
+    Ok(())
+}
+
+/// Describes a number of timers.
+fn count_timers(count: usize) -> String {
+    match count {
+        1 => "1 timer".to_string(),
+        count => format!("{count} timers"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        cli::{connection::Connection, SystemdSyncArgs},
+        formats::SYSTEMD_UNIT_HEADER,
+        models::{
+            reminders::{Reminder, ReminderId},
+            store::ReminderStore,
+        },
+        storage::StoreFile,
+    };
+
+    use super::sync;
+
+    #[test]
+    fn sync_removes_only_stale_generated_units() {
+        let dir =
+            std::env::temp_dir().join(format!("desktop-reminders-systemd-{}", std::process::id()));
+        let units = dir.join("units");
+        std::fs::create_dir_all(&units).unwrap();
+        let mut store = ReminderStore::default();
+        store.reminders.push(Reminder {
+            id: ReminderId(1),
+            frequency: "daily @ 09:00".parse().unwrap(),
+            message: "Stretch".to_string(),
+            completed: false,
+            snoozed_until: None,
+            actions: Vec::new(),
+            contact: None,
+        });
+        let mut connection = Connection::File {
+            file: StoreFile::new(dir.join("store.json")),
+            store,
+            changed: false,
+        };
+
+        // A timer written by hand with a generated name, and the units of a
+        // reminder that was removed
+        let handwritten = "[Timer]\nOnCalendar=daily\n";
+        std::fs::write(units.join("desktop-reminders-9.timer"), handwritten).unwrap();
+        let stale = format!("{SYSTEMD_UNIT_HEADER}\n[Timer]\nOnCalendar=daily\n");
+        std::fs::write(units.join("desktop-reminders-5.timer"), &stale).unwrap();
+        std::fs::write(units.join("desktop-reminders-5.service"), &stale).unwrap();
+
+        let args = SystemdSyncArgs {
+            dir: Some(units.clone()),
+            no_systemctl: true,
+        };
+        sync(&mut connection, args).unwrap();
+
+        let mut names: Vec<_> = std::fs::read_dir(&units)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        assert_eq!(
+            names,
+            [
+                "desktop-reminders-1.service",
+                "desktop-reminders-1.timer",
+                "desktop-reminders-9.timer",
+            ]
+        );
+        let kept = std::fs::read_to_string(units.join("desktop-reminders-9.timer")).unwrap();
+        assert_eq!(kept, handwritten);
+        let timer = std::fs::read_to_string(units.join("desktop-reminders-1.timer")).unwrap();
+        assert!(timer.contains("OnCalendar=*-*-* 09:00:00\n"), "{timer}");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
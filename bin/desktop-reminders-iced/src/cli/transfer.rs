@@ -0,0 +1,280 @@
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use chrono::Local;
+use clap::ValueEnum;
+use color_eyre::eyre::{bail, eyre, Context};
+
+use crate::{
+    formats::{
+        export_ics, export_org, export_taskwarrior, export_todo_txt, import_ics, import_org,
+        import_taskwarrior, import_todo_txt, import_vcard, store_from_toml, store_to_toml,
+        Converted, IcsComponentKind,
+    },
+    ipc::Request,
+    models::{reminders::Reminder, store::ReminderStore},
+};
+
+use super::{connection::Connection, output::print_reminders, ExportArgs, ImportArgs};
+
+/// A file format reminders can be imported from or exported to.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, ValueEnum)]
+pub enum FileFormat {
+    /// The JSON used by the store.
+    #[default]
+    Json,
+    /// An iCalendar file.
+    Ics,
+    /// The TOML used by a `.toml` store.
+    Toml,
+    /// An Org-mode file of to-do headings.
+    Org,
+    /// A todo.txt file with `due:` and `rec:` tags.
+    TodoTxt,
+    /// The JSON written by `task export`. Never guessed from the extension.
+    Taskwarrior,
+    /// A vCard file, whose birthdays and anniversaries can be imported.
+    Vcard,
+}
+
+impl FileFormat {
+    /// Guesses the format of a file from its extension.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "json" => Some(FileFormat::Json),
+            "ics" | "ical" | "ifb" | "icalendar" => Some(FileFormat::Ics),
+            "toml" => Some(FileFormat::Toml),
+            "org" => Some(FileFormat::Org),
+            "txt" => Some(FileFormat::TodoTxt),
+            "vcf" | "vcard" => Some(FileFormat::Vcard),
+            _ => None,
+        }
+    }
+}
+
+/// Exports reminders.
+pub fn export(connection: &mut Connection, args: ExportArgs) -> color_eyre::Result<()> {
+    let today = Local::now().date_naive();
+    let reminders = connection.reminders()?;
+    let reminders: Vec<_> = reminders
+        .into_iter()
+        .filter(|reminder| args.filter.matches(reminder, today))
+        .collect();
+
+    let format = args
+        .format
+        .or_else(|| args.output.as_deref().and_then(FileFormat::from_path))
+        .unwrap_or_default();
+    let converted = match format {
+        FileFormat::Json => Converted::new(serde_json::to_string_pretty(&reminders)? + "\n"),
+        FileFormat::Ics => {
+            let kind = match args.todo {
+                true => IcsComponentKind::Todo,
+                false => IcsComponentKind::Event,
+            };
+            export_ics(&reminders, kind, today)
+        }
+        FileFormat::Toml => Converted::new(store_to_toml(&ReminderStore {
+            reminders,
+            ..Default::default()
+        })?),
+        FileFormat::Org => export_org(&reminders, today),
+        FileFormat::TodoTxt => export_todo_txt(&reminders, today),
+        FileFormat::Taskwarrior => export_taskwarrior(&reminders, today)?,
+        FileFormat::Vcard => bail!("reminders cannot be exported as vCard files"),
+    };
+    print_warnings(&converted.warnings);
+
+    let mut writer: Box<dyn Write> = match &args.output {
+        Some(path) => {
+            Box::new(BufWriter::new(File::create(path).wrap_err_with(|| {
+                format!("failed to create {}", path.display())
+            })?))
+        }
+        None => Box::new(std::io::stdout().lock()),
+    };
+    writer.write_all(converted.value.as_bytes())?;
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// Imports reminders, adding them alongside the existing ones. Reminders
+/// linked to a contact update the reminder already imported from it, if any.
+pub fn import(connection: &mut Connection, args: ImportArgs) -> color_eyre::Result<()> {
+    let Some(format) = args.format.or_else(|| FileFormat::from_path(&args.file)) else {
+        bail!(
+            "could not tell the format of {} from its extension: use --format",
+            args.file.display()
+        );
+    };
+
+    let source = std::fs::read_to_string(&args.file)
+        .wrap_err_with(|| format!("failed to read {}", args.file.display()))?;
+    let converted = match format {
+        FileFormat::Json => Converted::new(
+            serde_json::from_str::<Vec<Reminder>>(&source)
+                .wrap_err_with(|| format!("failed to parse {}", args.file.display()))?,
+        ),
+        FileFormat::Ics => import_ics(&source)
+            .wrap_err_with(|| format!("failed to parse {}", args.file.display()))?,
+        FileFormat::Toml => match store_from_toml(&source) {
+            Ok(store) => Converted::new(store.reminders),
+            Err(error) => {
+                let path = args.file.display();
+                return Err(match error.position(&source) {
+                    Some((line, column)) => eyre!("{path}:{line}:{column}: {error}"),
+                    None => eyre!("{path}: {error}"),
+                });
+            }
+        },
+        FileFormat::Org => import_org(&source),
+        FileFormat::TodoTxt => import_todo_txt(&source),
+        FileFormat::Taskwarrior => import_taskwarrior(&source)
+            .wrap_err_with(|| format!("failed to parse {}", args.file.display()))?,
+        FileFormat::Vcard => import_vcard(&source)
+            .wrap_err_with(|| format!("failed to parse {}", args.file.display()))?,
+    };
+    print_warnings(&converted.warnings);
+
+    // Reminders imported from a contact replace the ones imported from it
+    // before
+    let existing = connection.reminders()?;
+    let mut added = Vec::new();
+    let mut updated = Vec::new();
+    for mut reminder in converted.value {
+        let previous = reminder.contact.as_ref().and_then(|contact| {
+            existing
+                .iter()
+                .find(|existing| existing.contact.as_ref() == Some(contact))
+        });
+        if let Some(previous) = previous {
+            reminder.id = previous.id;
+            reminder.completed = previous.completed;
+            reminder.snoozed_until = previous.snoozed_until;
+            reminder.actions = previous.actions.clone();
+            updated.push(connection.reminder(Request::Update { reminder })?);
+            continue;
+        }
+
+        let completed = reminder.completed;
+        let mut reminder = connection.reminder(Request::Add { reminder })?;
+        if completed && !reminder.completed {
+            reminder = connection.reminder(Request::Complete {
+                id: reminder.id,
+                undo: false,
+            })?;
+        }
+        added.push(reminder);
+    }
+
+    if args.json {
+        added.extend(updated);
+        print_reminders(&added, Local::now().naive_local(), true)?;
+    } else {
+        match added.len() {
+            1 => print!("Imported 1 reminder"),
+            count => print!("Imported {count} reminders"),
+        }
+        match updated.len() {
+            0 => println!(),
+            1 => println!(", updated 1"),
+            count => println!(", updated {count}"),
+        }
+    }
+
+    Ok(())
+}
+
+/// Prints what could not be converted exactly.
+pub fn print_warnings(warnings: &[String]) {
+    for warning in warnings {
+        eprintln!("warning: {warning}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        cli::{connection::Connection, ImportArgs},
+        models::reminders::{Reminder, ReminderFrequency},
+        storage::StoreFile,
+    };
+
+    use super::import;
+
+    fn contacts(jane_birthday: &str) -> String {
+        format!(
+            "BEGIN:VCARD\r\nUID:jane\r\nFN:Jane Doe\r\nBDAY:{jane_birthday}\r\nEND:VCARD\r\n\
+             BEGIN:VCARD\r\nFN:No UID\r\nBDAY:--0704\r\nEND:VCARD\r\n"
+        )
+    }
+
+    fn reminders(connection: &Connection) -> &[Reminder] {
+        match connection {
+            Connection::File { store, .. } => &store.reminders,
+            Connection::Instance(_) => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn reimporting_contacts_updates_their_reminders() {
+        let dir =
+            std::env::temp_dir().join(format!("desktop-reminders-transfer-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("contacts.vcf");
+        let args = || ImportArgs {
+            file: path.clone(),
+            format: None,
+            json: false,
+        };
+        let mut connection = Connection::File {
+            file: StoreFile::new(dir.join("store.json")),
+            store: Default::default(),
+            changed: false,
+        };
+
+        std::fs::write(&path, contacts("19850412")).unwrap();
+        import(&mut connection, args()).unwrap();
+        let first = reminders(&connection).to_vec();
+        assert_eq!(first.len(), 2);
+        let jane = first[0].id;
+        let snoozed_until = "2024-04-12T09:00:00".parse().unwrap();
+        if let Connection::File { store, .. } = &mut connection {
+            let reminder = store.get_mut(jane).unwrap();
+            reminder.completed = true;
+            reminder.snoozed_until = Some(snoozed_until);
+        }
+
+        // The contact with a UID is updated in place, keeping its state,
+        // while the one without a UID is added again
+        std::fs::write(&path, contacts("1985-04-13")).unwrap();
+        import(&mut connection, args()).unwrap();
+        let second = reminders(&connection);
+        let messages: Vec<_> = second
+            .iter()
+            .map(|reminder| reminder.message.as_str())
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                "Jane Doe's birthday",
+                "No UID's birthday",
+                "No UID's birthday"
+            ]
+        );
+        let expected: ReminderFrequency = "yearly 04-13 @ all-day".parse().unwrap();
+        assert_eq!(second[0].id, jane);
+        assert_eq!(second[0].frequency, expected);
+        assert!(second[0].completed);
+        assert_eq!(second[0].snoozed_until, Some(snoozed_until));
+        assert_eq!(second[0].contact, first[0].contact);
+        assert_ne!(second[1].id, second[2].id);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,274 @@
+use std::{
+    error::Error,
+    sync::mpsc::{self, RecvTimeoutError},
+    time::Instant,
+};
+
+use chrono::{Local, NaiveDate};
+use color_eyre::eyre::bail;
+use tracing::{error, info, warn};
+
+use crate::{
+    actions::{is_superseded, spawn_actions},
+    dbus::DbusService,
+    digest::{spawn_digest, DIGEST_RETRY_INTERVAL},
+    ipc::{lock_path, socket_path, InstanceLock, IpcRequest, IpcServer, RpcError},
+    models::{reminders::ReminderAlert, store::ReminderStore},
+    mqtt::MqttService,
+    notifications::{notify_alert, notify_summary},
+    scheduler::{ClockSample, Scheduler, CLOCK_CHECK_INTERVAL, LAST_SEEN_SAVE_INTERVAL},
+    storage::{LoadedFile, SettingsFile, StoreFile},
+    web::WebServer,
+};
+
+/// Runs the scheduler without a window, delivering alerts as desktop
+/// notifications until the process is asked to stop.
+pub fn run() -> color_eyre::Result<()> {
+    let Some(_lock) = InstanceLock::acquire(&lock_path())? else {
+        bail!("another instance is already running");
+    };
+
+    let store_file = StoreFile::from_env()?;
+    info!(path = %store_file.path().display(), "Loading reminders");
+    let mut store = LoadedFile::load(store_file)?;
+    let mut settings = LoadedFile::load(SettingsFile::from_env()?)?;
+
+    // Wake up early when asked to stop or when a request is received
+    let (event_tx, event_rx) = mpsc::channel();
+    ctrlc::set_handler({
+        let event_tx = event_tx.clone();
+        move || {
+            let _ = event_tx.send(DaemonEvent::Stop);
+        }
+    })?;
+    let digest_tx = event_tx.clone();
+    let send_request = move |request| {
+        let _ = event_tx.send(DaemonEvent::Request(request));
+    };
+    let ipc = match IpcServer::bind(socket_path(), send_request.clone()) {
+        Ok(server) => Some(server),
+        Err(error) => {
+            let error: &dyn Error = error.as_ref();
+            warn!(error, "not listening for requests");
+            None
+        }
+    };
+    let _web = if settings.value().web.enabled {
+        WebServer::start(settings.value().web.address, send_request.clone())
+            .inspect_err(|error| {
+                let error: &dyn Error = error.as_ref();
+                warn!(error, "not serving the dashboard");
+            })
+            .ok()
+    } else {
+        None
+    };
+    let mqtt = if settings.value().mqtt.enabled {
+        MqttService::start(&settings.value().mqtt, send_request.clone())
+            .inspect_err(|error| {
+                let error: &dyn Error = error.as_ref();
+                warn!(error, "not publishing events over MQTT");
+            })
+            .ok()
+    } else {
+        None
+    };
+    let dbus = match DbusService::start(send_request) {
+        Ok(service) => Some(service),
+        Err(error) => {
+            let error: &dyn Error = error.as_ref();
+            warn!(error, "not exporting reminders on the session bus");
+            None
+        }
+    };
+
+    let now = ClockSample::now();
+    let last_seen = store
+        .value()
+        .last_seen
+        .map_or(now.wall, |last_seen| last_seen.naive_local());
+    let mut scheduler = Scheduler::new(last_seen);
+    let mut last_saved = Instant::now();
+    let mut digest_attempt: Option<Instant> = None;
+
+    info!("Starting daemon");
+    loop {
+        // Pick up changes made while waiting
+        store.reload();
+        settings.reload();
+
+        // Check for reminders that came due
+        let now = ClockSample::now();
+        let due = scheduler.poll(&store.value().reminders, now);
+        let quiet = settings.value().quiet_hours.is_quiet(now.wall);
+        let deliver = !quiet && !store.value().queued_alerts.is_empty();
+        let changed = !due.is_empty() || deliver;
+        if changed || last_saved.elapsed() >= LAST_SEEN_SAVE_INTERVAL {
+            // Record the changes first, then act on them
+            let result = store.update(|store| {
+                for alert in &due {
+                    store.mark_due(&alert.occurrence);
+                    if quiet {
+                        store.queued_alerts.push(*alert);
+                    }
+                }
+                store.last_seen = Some(Local::now());
+                match deliver {
+                    true => std::mem::take(&mut store.queued_alerts),
+                    false => Vec::new(),
+                }
+            });
+            last_saved = Instant::now();
+            let queued = result.unwrap_or_else(|error| {
+                let error: &dyn Error = error.as_ref();
+                error!(error, "failed to save reminders");
+                Vec::new()
+            });
+
+            for (index, alert) in due.iter().enumerate() {
+                let reminders = &store.value().reminders;
+                let Some(reminder) = reminders.iter().find(|r| r.id == alert.occurrence.id) else {
+                    continue;
+                };
+
+                info!(
+                    id = %reminder.id,
+                    message = reminder.message,
+                    missed = alert.missed,
+                    "reminder due"
+                );
+                if !is_superseded(&due, index) {
+                    spawn_actions(reminder, alert);
+                }
+                if let Some(dbus) = &dbus {
+                    dbus.emit_due(reminder, alert);
+                }
+                if let Some(mqtt) = &mqtt {
+                    mqtt.publish_due(reminder, alert);
+                }
+                if !quiet {
+                    if let Err(error) = notify_alert(reminder, alert) {
+                        let error: &dyn Error = error.as_ref();
+                        error!(error, "failed to show notification");
+                    }
+                }
+            }
+
+            // Deliver alerts that were held back during quiet hours
+            if !queued.is_empty() {
+                deliver_queued(store.value(), &queued);
+            }
+
+            if changed {
+                notify_changed(store.value(), ipc.as_ref(), dbus.as_ref());
+            }
+        }
+
+        // Send the morning digest, even during quiet hours. Failed sends are
+        // retried after a while
+        let digest_due = settings
+            .value()
+            .digest
+            .is_due(store.value().last_digest, now.wall)
+            && digest_attempt.is_none_or(|at| at.elapsed() >= DIGEST_RETRY_INTERVAL);
+        if digest_due {
+            digest_attempt = Some(Instant::now());
+            let digest_tx = digest_tx.clone();
+            let date = now.wall.date();
+            spawn_digest(
+                &settings.value().digest,
+                store.value(),
+                now.wall,
+                move || {
+                    let _ = digest_tx.send(DaemonEvent::DigestSent(date));
+                },
+            );
+        }
+
+        // Wait until the next reminder is due, or long enough to notice if
+        // the system clock jumps
+        let mut wait = CLOCK_CHECK_INTERVAL;
+        if let Some(next_due) = scheduler.next_due(&store.value().reminders) {
+            wait = wait.min((next_due - now.wall).to_std().unwrap_or_default());
+        }
+        match event_rx.recv_timeout(wait) {
+            Ok(DaemonEvent::Request(request)) => {
+                let result = match request.request.is_change() {
+                    true => store
+                        .update(|store| {
+                            let result = request.request.clone().apply(store);
+                            store.last_seen = Some(Local::now());
+                            result
+                        })
+                        .unwrap_or_else(|error| {
+                            Err(RpcError::new(RpcError::INTERNAL_ERROR, error.to_string()))
+                        }),
+                    false => {
+                        store.reload();
+                        let mut snapshot = store.value().clone();
+                        request.request.clone().apply(&mut snapshot)
+                    }
+                };
+                let changed = request.request.is_change() && result.is_ok();
+                if let Some(mqtt) = &mqtt {
+                    mqtt.publish_applied(&request.request, &result);
+                }
+                request.respond(result);
+                if changed {
+                    notify_changed(store.value(), ipc.as_ref(), dbus.as_ref());
+                }
+            }
+            Ok(DaemonEvent::DigestSent(date)) => {
+                let result = store.update(|store| store.last_digest = Some(date));
+                if let Err(error) = result {
+                    let error: &dyn Error = error.as_ref();
+                    error!(error, "failed to save reminders");
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Ok(DaemonEvent::Stop) | Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+
+    info!("Stopping daemon");
+    store.update(|store| store.last_seen = Some(Local::now()))
+}
+
+/// Something that wakes the daemon up.
+enum DaemonEvent {
+    /// The daemon was asked to stop.
+    Stop,
+    /// A request was received on the socket.
+    Request(IpcRequest),
+    /// The digest for a day was sent.
+    DigestSent(NaiveDate),
+}
+
+/// Delivers the queued alerts as a single summary notification.
+fn deliver_queued(store: &ReminderStore, queued: &[ReminderAlert]) {
+    info!(
+        count = queued.len(),
+        "delivering alerts held back during quiet hours"
+    );
+
+    let reminders = queued.iter().filter_map(|alert| {
+        store
+            .reminders
+            .iter()
+            .find(|reminder| reminder.id == alert.occurrence.id)
+    });
+    if let Err(error) = notify_summary(reminders) {
+        let error: &dyn Error = error.as_ref();
+        error!(error, "failed to show notification");
+    }
+}
+
+/// Tells subscribers and the session bus that the reminders changed.
+fn notify_changed(store: &ReminderStore, ipc: Option<&IpcServer>, dbus: Option<&DbusService>) {
+    if let Some(ipc) = ipc {
+        ipc.notify_changed(&store.reminders);
+    }
+    if let Some(dbus) = dbus {
+        dbus.emit_changed();
+    }
+}
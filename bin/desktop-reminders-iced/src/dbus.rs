@@ -0,0 +1,384 @@
+use std::sync::Arc;
+
+use chrono::Local;
+use tracing::{info, warn};
+use zbus::{
+    blocking::{connection::Builder, Connection},
+    fdo, interface,
+    object_server::SignalEmitter,
+};
+
+use crate::{
+    actions::ActionPayload,
+    ipc::{IpcRequest, Request, Response, RpcError},
+    models::reminders::{
+        ParseFrequencyError, Reminder, ReminderAlert, ReminderFrequency, ReminderId,
+    },
+};
+
+/// The well-known name the app owns on the session bus.
+pub const BUS_NAME: &str = "io.github.TehPers.DesktopReminders";
+
+/// The path of the object the app exports on the session bus.
+pub const OBJECT_PATH: &str = "/io/github/TehPers/DesktopReminders";
+
+/// Exports the app's reminders on the session bus so desktop shells,
+/// launchers and panel applets can integrate with it.
+#[derive(Debug)]
+pub struct DbusService {
+    connection: Connection,
+}
+
+impl DbusService {
+    /// Connects to the session bus and exports the reminders object, passing
+    /// each method call to `on_request`.
+    pub fn start<F>(on_request: F) -> color_eyre::Result<Self>
+    where
+        F: Fn(IpcRequest) + Send + Sync + 'static,
+    {
+        Self::start_on(Builder::session()?, on_request)
+    }
+
+    /// Exports the reminders object on the bus `builder` connects to.
+    fn start_on<F>(builder: Builder<'_>, on_request: F) -> color_eyre::Result<Self>
+    where
+        F: Fn(IpcRequest) + Send + Sync + 'static,
+    {
+        let interface = RemindersInterface {
+            on_request: Arc::new(on_request),
+        };
+        let connection = builder
+            .name(BUS_NAME)?
+            .serve_at(OBJECT_PATH, interface)?
+            .build()?;
+        info!(name = BUS_NAME, "exported reminders on the session bus");
+
+        Ok(Self { connection })
+    }
+
+    /// Emits the `ReminderDue` signal.
+    pub fn emit_due(&self, reminder: &Reminder, alert: &ReminderAlert) {
+        let payload = ActionPayload::new(reminder, alert);
+        let result = self.emit(|emitter| async move {
+            RemindersInterface::reminder_due(
+                &emitter,
+                payload.id.0,
+                &payload.title,
+                &payload.occurrence,
+                payload.missed,
+            )
+            .await
+        });
+        if let Err(error) = result {
+            warn!(%error, "failed to emit ReminderDue");
+        }
+    }
+
+    /// Emits the `Changed` signal.
+    pub fn emit_changed(&self) {
+        let result =
+            self.emit(|emitter| async move { RemindersInterface::changed(&emitter).await });
+        if let Err(error) = result {
+            warn!(%error, "failed to emit Changed");
+        }
+    }
+
+    /// Emits a signal from the reminders object.
+    fn emit<F, Fut>(&self, f: F) -> zbus::Result<()>
+    where
+        F: FnOnce(SignalEmitter<'static>) -> Fut,
+        Fut: std::future::Future<Output = zbus::Result<()>>,
+    {
+        let interface = self
+            .connection
+            .object_server()
+            .interface::<_, RemindersInterface>(OBJECT_PATH)?;
+        let emitter = interface.signal_emitter().clone();
+        zbus::block_on(f(emitter))
+    }
+}
+
+/// The D-Bus interface for the reminders object.
+struct RemindersInterface {
+    on_request: Arc<dyn Fn(IpcRequest) + Send + Sync>,
+}
+
+impl RemindersInterface {
+    /// Passes a request to the app and waits for it to be handled.
+    async fn call(&self, request: Request) -> fdo::Result<Response> {
+        let (request, response) = IpcRequest::new(request);
+        (self.on_request)(request);
+        response
+            .recv()
+            .await
+            .map_err(|error: RpcError| match error.code {
+                RpcError::NOT_FOUND => fdo::Error::InvalidArgs(error.message),
+                _ => fdo::Error::Failed(error.message),
+            })
+    }
+}
+
+#[interface(name = "io.github.TehPers.DesktopReminders1")]
+impl RemindersInterface {
+    /// Lists the reminders as `(id, message, completed, next occurrence)`.
+    /// The next occurrence is a local time like `2024-03-01T09:30:00`, or
+    /// empty if the reminder will not occur again.
+    async fn list(&self) -> fdo::Result<Vec<(u64, String, bool, String)>> {
+        let Response::Reminders(reminders) = self.call(Request::List).await? else {
+            return Err(fdo::Error::Failed("expected a list of reminders".into()));
+        };
+
+        let now = Local::now().naive_local();
+        Ok(reminders
+            .into_iter()
+            .map(|reminder| {
+                let next = reminder
+                    .next_occurrence_after(now)
+                    .map(|at| at.format("%Y-%m-%dT%H:%M:%S").to_string())
+                    .unwrap_or_default();
+                (reminder.id.0, reminder.message, reminder.completed, next)
+            })
+            .collect())
+    }
+
+    /// Adds a reminder, returning its ID. The schedule is written like
+    /// `weekly mon,wed @ 12:30`, as accepted by `reminders add --schedule`.
+    async fn add(&self, message: String, schedule: &str) -> fdo::Result<u64> {
+        let frequency: ReminderFrequency = schedule
+            .parse()
+            .map_err(|error: ParseFrequencyError| fdo::Error::InvalidArgs(error.to_string()))?;
+        let request = Request::Add {
+            reminder: Reminder {
+                id: Default::default(),
+                frequency,
+                message,
+                completed: false,
+                snoozed_until: None,
+                actions: Vec::new(),
+                contact: None,
+            },
+        };
+        match self.call(request).await? {
+            Response::Reminder(reminder) => Ok(reminder.id.0),
+            _ => Err(fdo::Error::Failed("expected a reminder".into())),
+        }
+    }
+
+    /// Marks a reminder as done.
+    async fn mark_done(&self, id: u64) -> fdo::Result<()> {
+        let request = Request::Complete {
+            id: ReminderId(id),
+            undo: false,
+        };
+        self.call(request).await.map(drop)
+    }
+
+    /// Snoozes a reminder for some number of minutes.
+    async fn snooze(&self, id: u64, minutes: i64) -> fdo::Result<()> {
+        let request = Request::Snooze {
+            id: ReminderId(id),
+            minutes,
+        };
+        self.call(request).await.map(drop)
+    }
+
+    /// Brings the app's window to the front.
+    async fn activate(&self) -> fdo::Result<()> {
+        let request = Request::Activate { new_reminder: None };
+        self.call(request).await.map(drop)
+    }
+
+    /// Emitted when a reminder comes due. The occurrence is a local time like
+    /// `2024-03-01T09:30:00`.
+    #[zbus(signal)]
+    async fn reminder_due(
+        emitter: &SignalEmitter<'_>,
+        id: u64,
+        message: &str,
+        occurrence: &str,
+        missed: bool,
+    ) -> zbus::Result<()>;
+
+    /// Emitted when the reminders change.
+    #[zbus(signal)]
+    async fn changed(emitter: &SignalEmitter<'_>) -> zbus::Result<()>;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader},
+        process::{Child, Command, Stdio},
+        sync::{mpsc, Arc, Mutex},
+        thread,
+        time::Duration,
+    };
+
+    use chrono::NaiveDate;
+    use zbus::{
+        blocking::{connection::Builder, Proxy},
+        fdo,
+    };
+
+    use crate::{
+        ipc::{Request, Response},
+        models::{
+            reminders::{ReminderAlert, ReminderId, ReminderOccurrence},
+            store::ReminderStore,
+        },
+    };
+
+    use super::{DbusService, BUS_NAME, OBJECT_PATH};
+
+    const INTERFACE: &str = "io.github.TehPers.DesktopReminders1";
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    /// A private bus that is stopped when dropped.
+    struct PrivateBus {
+        daemon: Child,
+        address: String,
+    }
+
+    impl PrivateBus {
+        fn start() -> Self {
+            let mut daemon = Command::new("dbus-daemon")
+                .args(["--session", "--print-address", "--nofork"])
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null())
+                .spawn()
+                .expect("failed to start dbus-daemon");
+
+            let mut address = String::new();
+            BufReader::new(daemon.stdout.take().unwrap())
+                .read_line(&mut address)
+                .unwrap();
+            Self {
+                daemon,
+                address: address.trim().to_string(),
+            }
+        }
+
+        fn builder(&self) -> Builder<'_> {
+            Builder::address(self.address.as_str()).unwrap()
+        }
+    }
+
+    impl Drop for PrivateBus {
+        fn drop(&mut self) {
+            let _ = self.daemon.kill();
+            let _ = self.daemon.wait();
+        }
+    }
+
+    /// Collects the arguments of the next signal called `name`.
+    fn next_signal<B>(proxy: &Proxy<'static>, name: &'static str) -> mpsc::Receiver<B>
+    where
+        B: for<'de> serde::Deserialize<'de> + zbus::zvariant::Type + Send + 'static,
+    {
+        let mut signals = proxy.receive_signal(name).unwrap();
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            if let Some(message) = signals.next() {
+                let _ = tx.send(message.body().deserialize().unwrap());
+            }
+        });
+        rx
+    }
+
+    #[test]
+    #[ignore = "needs dbus-daemon"]
+    fn interface_on_private_bus() {
+        let bus = PrivateBus::start();
+
+        // Apply requests to an in-memory store, like the daemon does
+        let store = Arc::new(Mutex::new(ReminderStore::default()));
+        let (activated_tx, activated_rx) = mpsc::channel();
+        let service = DbusService::start_on(bus.builder(), {
+            let store = store.clone();
+            let activated_tx = Mutex::new(activated_tx);
+            move |request| {
+                let result = match request.request {
+                    Request::Activate { .. } => {
+                        let _ = activated_tx.lock().unwrap().send(());
+                        Ok(Response::None)
+                    }
+                    _ => request.request.clone().apply(&mut store.lock().unwrap()),
+                };
+                request.respond(result);
+            }
+        })
+        .unwrap();
+
+        let connection = bus.builder().build().unwrap();
+        let proxy = Proxy::new(&connection, BUS_NAME, OBJECT_PATH, INTERFACE).unwrap();
+
+        // Add
+        let id: u64 = proxy.call("Add", &("Stretch", "daily @ 09:00")).unwrap();
+        let error = proxy
+            .call::<_, _, u64>("Add", &("Stretch", "fortnightly @ 09:00"))
+            .unwrap_err();
+        assert!(matches!(
+            fdo::Error::from(error),
+            fdo::Error::InvalidArgs(_)
+        ));
+
+        // List
+        let reminders: Vec<(u64, String, bool, String)> = proxy.call("List", &()).unwrap();
+        assert_eq!(reminders.len(), 1);
+        let (listed_id, message, completed, next) = &reminders[0];
+        assert_eq!(*listed_id, id);
+        assert_eq!(message, "Stretch");
+        assert!(!completed);
+        assert!(
+            next.ends_with("T09:00:00"),
+            "unexpected next occurrence {next}"
+        );
+
+        // MarkDone
+        proxy.call::<_, _, ()>("MarkDone", &(id,)).unwrap();
+        assert_eq!(store.lock().unwrap().completions.len(), 1);
+        let error = proxy.call::<_, _, ()>("MarkDone", &(id + 1,)).unwrap_err();
+        assert!(matches!(
+            fdo::Error::from(error),
+            fdo::Error::InvalidArgs(_)
+        ));
+
+        // Snooze
+        proxy.call::<_, _, ()>("Snooze", &(id, 15i64)).unwrap();
+        assert!(store.lock().unwrap().reminders[0].snoozed_until.is_some());
+
+        // Activate
+        proxy.call::<_, _, ()>("Activate", &()).unwrap();
+        activated_rx.recv_timeout(TIMEOUT).unwrap();
+
+        // ReminderDue
+        let due = next_signal::<(u64, String, String, bool)>(&proxy, "ReminderDue");
+        let reminder = store.lock().unwrap().reminders[0].clone();
+        let at = NaiveDate::from_ymd_opt(2024, 3, 1)
+            .unwrap()
+            .and_hms_opt(9, 0, 0)
+            .unwrap();
+        let alert = ReminderAlert {
+            occurrence: ReminderOccurrence {
+                id: ReminderId(id),
+                at,
+            },
+            missed: true,
+        };
+        service.emit_due(&reminder, &alert);
+        assert_eq!(
+            due.recv_timeout(TIMEOUT).unwrap(),
+            (
+                id,
+                "Stretch".to_string(),
+                "2024-03-01T09:00:00".to_string(),
+                true
+            )
+        );
+
+        // Changed
+        let changed = next_signal::<()>(&proxy, "Changed");
+        service.emit_changed();
+        changed.recv_timeout(TIMEOUT).unwrap();
+    }
+}
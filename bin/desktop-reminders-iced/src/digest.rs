@@ -0,0 +1,7 @@
+mod mail;
+mod report;
+mod template;
+
+pub use mail::*;
+pub use report::*;
+pub use template::*;
@@ -0,0 +1,261 @@
+use std::thread;
+
+use chrono::NaiveDateTime;
+use color_eyre::eyre::{bail, Context};
+use lettre::{
+    message::{header::ContentType, Mailbox},
+    transport::smtp::authentication::Credentials,
+    Message, SmtpTransport, Transport,
+};
+use tracing::{error, info};
+
+use crate::models::{
+    settings::{DigestSettings, SmtpSecurity},
+    store::ReminderStore,
+};
+
+use super::{DigestReport, Template, DEFAULT_TEMPLATE};
+
+/// A rendered digest email.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct DigestEmail {
+    /// The subject of the email.
+    pub subject: String,
+    /// The plain text body of the email.
+    pub body: String,
+}
+
+impl DigestEmail {
+    /// Renders the digest using the templates from the settings.
+    pub fn render(settings: &DigestSettings, report: &DigestReport) -> color_eyre::Result<Self> {
+        let body_source = match &settings.template {
+            Some(path) => std::fs::read_to_string(path)
+                .wrap_err_with(|| format!("failed to read {}", path.display()))?,
+            None => DEFAULT_TEMPLATE.to_string(),
+        };
+        let body = Template::parse(&body_source).wrap_err("invalid digest template")?;
+        let subject = Template::parse(&settings.subject).wrap_err("invalid digest subject")?;
+
+        let context = serde_json::to_value(report)?;
+        Ok(Self {
+            // Headers cannot span multiple lines
+            subject: subject.render(&context).replace(['\r', '\n'], " "),
+            body: body.render(&context),
+        })
+    }
+
+    /// Sends the email through the SMTP server from the settings.
+    pub fn send(&self, settings: &DigestSettings) -> color_eyre::Result<()> {
+        if settings.to.is_empty() {
+            bail!("the digest has no recipients");
+        }
+
+        let from: Mailbox = settings
+            .from
+            .parse()
+            .wrap_err_with(|| format!("invalid sender {:?}", settings.from))?;
+        let mut builder = Message::builder()
+            .from(from)
+            .subject(&self.subject)
+            .header(ContentType::TEXT_PLAIN);
+        for to in &settings.to {
+            let to: Mailbox = to
+                .parse()
+                .wrap_err_with(|| format!("invalid recipient {to:?}"))?;
+            builder = builder.to(to);
+        }
+        let message = builder.body(self.body.clone())?;
+
+        let smtp = &settings.smtp;
+        let mut transport = match smtp.security {
+            SmtpSecurity::StartTls => SmtpTransport::starttls_relay(&smtp.host)?,
+            SmtpSecurity::Tls => SmtpTransport::relay(&smtp.host)?,
+            SmtpSecurity::None => SmtpTransport::builder_dangerous(&smtp.host),
+        }
+        .port(smtp.port);
+        if let Some(username) = &smtp.username {
+            let password = smtp.password.clone().unwrap_or_default();
+            transport = transport.credentials(Credentials::new(username.clone(), password));
+        }
+
+        transport
+            .build()
+            .send(&message)
+            .wrap_err_with(|| format!("failed to send through {}:{}", smtp.host, smtp.port))?;
+        Ok(())
+    }
+}
+
+/// How long to wait before trying to send the digest again after a failure.
+pub const DIGEST_RETRY_INTERVAL: std::time::Duration = std::time::Duration::from_secs(15 * 60);
+
+/// Renders the digest and sends it through the SMTP server from the settings.
+pub fn send_digest(settings: &DigestSettings, report: &DigestReport) -> color_eyre::Result<()> {
+    DigestEmail::render(settings, report)?.send(settings)
+}
+
+/// Sends the digest for the reminders in a store in the background, calling
+/// `on_sent` once it was sent. Failures are logged.
+pub fn spawn_digest<F>(
+    settings: &DigestSettings,
+    store: &ReminderStore,
+    now: NaiveDateTime,
+    on_sent: F,
+) where
+    F: FnOnce() + Send + 'static,
+{
+    let settings = settings.clone();
+    let report = DigestReport::new(store, now);
+    let result = thread::Builder::new().name("digest".into()).spawn(move || {
+        match send_digest(&settings, &report) {
+            Ok(()) => {
+                info!(to = ?settings.to, "sent digest");
+                on_sent();
+            }
+            Err(error) => error!(?error, "failed to send digest"),
+        }
+    });
+    if let Err(error) = result {
+        error!(%error, "failed to start sending digest");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+        thread::{self, JoinHandle},
+    };
+
+    use chrono::NaiveDate;
+
+    use crate::{
+        digest::DigestReport,
+        models::{
+            settings::{DigestSettings, SmtpSecurity, SmtpSettings},
+            store::ReminderStore,
+        },
+    };
+
+    use super::send_digest;
+
+    /// What a client sent to an SMTP server.
+    #[derive(Debug, Default)]
+    struct Transcript {
+        commands: Vec<String>,
+        data: String,
+    }
+
+    /// Starts an SMTP server on a loopback port that accepts a single
+    /// message.
+    fn smtp_sink() -> (u16, JoinHandle<Transcript>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut transcript = Transcript::default();
+            stream.write_all(b"220 localhost ESMTP\r\n").unwrap();
+
+            let mut line = String::new();
+            loop {
+                line.clear();
+                if reader.read_line(&mut line).unwrap() == 0 {
+                    break;
+                }
+
+                let command = line.trim_end().to_string();
+                let reply: &[u8] = match command.split(' ').next().unwrap_or_default() {
+                    "DATA" => {
+                        stream
+                            .write_all(b"354 End data with <CR><LF>.<CR><LF>\r\n")
+                            .unwrap();
+                        loop {
+                            line.clear();
+                            reader.read_line(&mut line).unwrap();
+                            if line == ".\r\n" {
+                                break;
+                            }
+                            transcript.data.push_str(&line);
+                        }
+                        b"250 OK\r\n"
+                    }
+                    "QUIT" => {
+                        stream.write_all(b"221 Bye\r\n").unwrap();
+                        break;
+                    }
+                    _ => b"250 OK\r\n",
+                };
+                stream.write_all(reply).unwrap();
+                transcript.commands.push(command);
+            }
+
+            transcript
+        });
+
+        (port, handle)
+    }
+
+    fn settings(port: u16) -> DigestSettings {
+        DigestSettings {
+            enabled: true,
+            from: "Reminders <me@example.com>".into(),
+            to: vec!["you@example.com".into()],
+            smtp: SmtpSettings {
+                host: "127.0.0.1".into(),
+                port,
+                security: SmtpSecurity::None,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    fn report() -> DigestReport {
+        let now = NaiveDate::from_ymd_opt(2024, 3, 5)
+            .unwrap()
+            .and_hms_opt(7, 0, 0)
+            .unwrap();
+        DigestReport::new(&ReminderStore::default(), now)
+    }
+
+    #[test]
+    fn sends_through_smtp() {
+        let (port, sink) = smtp_sink();
+        send_digest(&settings(port), &report()).unwrap();
+        let transcript = sink.join().unwrap();
+
+        assert!(transcript
+            .commands
+            .iter()
+            .any(|command| command == "MAIL FROM:<me@example.com>"));
+        assert!(transcript
+            .commands
+            .iter()
+            .any(|command| command == "RCPT TO:<you@example.com>"));
+        assert!(transcript
+            .data
+            .contains("Subject: Reminders for Tuesday, March 5\r\n"));
+        assert!(transcript.data.contains("Nothing scheduled."));
+    }
+
+    #[test]
+    fn fails_without_server() {
+        let port = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        assert!(send_digest(&settings(port), &report()).is_err());
+    }
+
+    #[test]
+    fn fails_without_recipients() {
+        let settings = DigestSettings {
+            to: Vec::new(),
+            ..settings(0)
+        };
+        assert!(send_digest(&settings, &report()).is_err());
+    }
+}
@@ -0,0 +1,221 @@
+use chrono::{Duration, NaiveDateTime};
+use serde::Serialize;
+
+use crate::{
+    models::{reminders::ReminderId, store::ReminderStore},
+    web::Dashboard,
+};
+
+/// The contents of the morning digest email.
+#[derive(Clone, Debug, Serialize)]
+pub struct DigestReport {
+    /// Today's date, like "Monday, March 4".
+    pub date: String,
+    /// How many reminders occur today.
+    pub today_count: usize,
+    /// The occurrences today.
+    pub today: Vec<DigestEntry>,
+    /// How many reminders are overdue.
+    pub overdue_count: usize,
+    /// The reminders that were due and have not been completed.
+    pub overdue: Vec<DigestEntry>,
+    /// How many of yesterday's reminders were completed.
+    pub yesterday: CompletionStats,
+}
+
+/// A reminder in the digest.
+#[derive(Clone, Debug, Serialize)]
+pub struct DigestEntry {
+    /// The ID of the reminder.
+    pub id: ReminderId,
+    /// The message for the reminder.
+    pub title: String,
+    /// When the reminder occurs today, like "9:30 AM" or "All day".
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time: Option<String>,
+    /// When the reminder became overdue, like "Fri Mar 1 9:30 AM".
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since: Option<String>,
+}
+
+/// How many reminders were completed on a day.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize)]
+pub struct CompletionStats {
+    /// How many occurrences were scheduled.
+    pub due: usize,
+    /// How many reminders were completed.
+    pub completed: usize,
+    /// The completed reminders as a percentage of the scheduled ones.
+    pub percent: usize,
+}
+
+impl DigestReport {
+    /// Summarizes the reminders in a store for the day of `now`.
+    pub fn new(store: &ReminderStore, now: NaiveDateTime) -> Self {
+        let today = Dashboard::new(&store.reminders, now)
+            .today
+            .into_iter()
+            .map(|entry| DigestEntry {
+                id: entry.id,
+                time: Some(entry.time_text()),
+                title: entry.message,
+                since: None,
+            })
+            .collect::<Vec<_>>();
+
+        let mut overdue: Vec<_> = store
+            .reminders
+            .iter()
+            .filter_map(|reminder| Some((reminder, reminder.overdue_since(now)?)))
+            .collect();
+        overdue.sort_by_key(|&(reminder, since)| (since, reminder.id));
+        let overdue = overdue
+            .into_iter()
+            .map(|(reminder, since)| DigestEntry {
+                id: reminder.id,
+                title: reminder.message.clone(),
+                time: None,
+                since: Some(since.format("%a %b %-d %-I:%M %p").to_string()),
+            })
+            .collect::<Vec<_>>();
+
+        // Completed reminders have no occurrences, so look at the schedules
+        // directly
+        let start_of_today = now.date().and_hms_opt(0, 0, 0).unwrap_or(now);
+        let start_of_yesterday = start_of_today - Duration::days(1);
+        let due = store
+            .reminders
+            .iter()
+            .map(|reminder| {
+                reminder
+                    .frequency
+                    .occurrences_between(
+                        start_of_yesterday - Duration::nanoseconds(1),
+                        start_of_today - Duration::nanoseconds(1),
+                    )
+                    .count()
+            })
+            .sum();
+        let completed = store
+            .completions
+            .iter()
+            .filter(|completion| {
+                start_of_yesterday <= completion.at && completion.at < start_of_today
+            })
+            .count();
+        let percent = match due {
+            0 => 0,
+            due => (completed * 100 / due).min(100),
+        };
+
+        Self {
+            date: now.format("%A, %B %-d").to_string(),
+            today_count: today.len(),
+            today,
+            overdue_count: overdue.len(),
+            overdue,
+            yesterday: CompletionStats {
+                due,
+                completed,
+                percent,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, NaiveDateTime};
+
+    use crate::models::{
+        reminders::{Reminder, ReminderCompletion, ReminderId},
+        store::ReminderStore,
+    };
+
+    use super::{CompletionStats, DigestReport};
+
+    fn at(day: u32, hour: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2024, 3, day)
+            .unwrap()
+            .and_hms_opt(hour, 0, 0)
+            .unwrap()
+    }
+
+    fn reminder(id: u64, message: &str, schedule: &str, completed: bool) -> Reminder {
+        Reminder {
+            id: ReminderId(id),
+            frequency: schedule.parse().unwrap(),
+            message: message.to_string(),
+            completed,
+            snoozed_until: None,
+            actions: Vec::new(),
+            contact: None,
+        }
+    }
+
+    #[test]
+    fn summarizes_today_overdue_and_yesterday() {
+        let store = ReminderStore {
+            reminders: vec![
+                reminder(1, "Stretch", "daily @ 09:30", false),
+                reminder(2, "Pay rent", "once 2024-03-05 @ 12:00", false),
+                reminder(3, "Call mom", "once 2024-03-04 @ 08:00", false),
+                reminder(4, "Dentist", "once 2024-03-03 @ 10:00", true),
+            ],
+            completions: vec![
+                ReminderCompletion {
+                    id: ReminderId(1),
+                    at: at(4, 10),
+                },
+                ReminderCompletion {
+                    id: ReminderId(4),
+                    at: at(3, 10),
+                },
+            ],
+            ..Default::default()
+        };
+        let report = DigestReport::new(&store, at(5, 7));
+
+        assert_eq!(report.date, "Tuesday, March 5");
+
+        let today: Vec<_> = report
+            .today
+            .iter()
+            .map(|entry| (entry.id.0, entry.title.as_str(), entry.time.as_deref()))
+            .collect();
+        assert_eq!(
+            today,
+            [
+                (1, "Stretch", Some("9:30 AM")),
+                (2, "Pay rent", Some("12:00 PM")),
+            ]
+        );
+        assert_eq!(report.today_count, 2);
+
+        let overdue: Vec<_> = report
+            .overdue
+            .iter()
+            .map(|entry| (entry.id.0, entry.since.as_deref()))
+            .collect();
+        assert_eq!(overdue, [(3, Some("Mon Mar 4 8:00 AM"))]);
+        assert_eq!(report.overdue_count, 1);
+
+        // The daily reminder and the one-off reminder were due yesterday
+        assert_eq!(
+            report.yesterday,
+            CompletionStats {
+                due: 2,
+                completed: 1,
+                percent: 50,
+            }
+        );
+    }
+
+    #[test]
+    fn empty_store() {
+        let report = DigestReport::new(&ReminderStore::default(), at(5, 7));
+        assert!(report.today.is_empty());
+        assert!(report.overdue.is_empty());
+        assert_eq!(report.yesterday, CompletionStats::default());
+    }
+}
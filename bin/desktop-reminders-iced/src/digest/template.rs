@@ -0,0 +1,403 @@
+use serde_json::Value;
+use thiserror::Error;
+
+/// The built-in template for the body of the digest.
+pub const DEFAULT_TEMPLATE: &str = "\
+Good morning! Here is what's on for {{date}}.
+
+Today ({{today_count}}):
+{{#today}}
+  - {{time}}  {{title}}
+{{/today}}
+{{^today}}
+  Nothing scheduled.
+{{/today}}
+{{#overdue_count}}
+
+Overdue ({{overdue_count}}):
+{{#overdue}}
+  - {{title}} (since {{since}})
+{{/overdue}}
+{{/overdue_count}}
+{{#yesterday.due}}
+
+Yesterday you completed {{yesterday.completed}} of {{yesterday.due}} reminders ({{yesterday.percent}}%).
+{{/yesterday.due}}
+";
+
+/// A simple text template with `{{name}}` placeholders.
+///
+/// Placeholders are looked up in a JSON value, with dots separating the
+/// fields of nested objects. `{{#name}}...{{/name}}` renders its contents
+/// once for each item of a list, or once if the value is anything other than
+/// `false`, `null`, `0`, `""` or an empty list. `{{^name}}...{{/name}}`
+/// renders its contents only if the value is one of those. Within a list,
+/// `{{.}}` refers to the current item. `{{! ... }}` is a comment.
+///
+/// Section tags on a line of their own do not leave an empty line behind.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Template {
+    nodes: Vec<Node>,
+}
+
+/// A part of a [`Template`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+enum Node {
+    /// Text that is rendered as-is.
+    Text(String),
+    /// A placeholder for a value.
+    Value(String),
+    /// A section that is rendered depending on a value.
+    Section {
+        name: String,
+        inverted: bool,
+        nodes: Vec<Node>,
+    },
+}
+
+/// An error parsing a [`Template`].
+#[derive(Clone, PartialEq, Eq, Debug, Error)]
+pub enum TemplateError {
+    /// A tag was opened with `{{` but never closed.
+    #[error("line {line}: `{{{{` is never closed with `}}}}`")]
+    UnclosedTag { line: usize },
+    /// A tag has no name.
+    #[error("line {line}: tag has no name")]
+    EmptyTag { line: usize },
+    /// A section was opened but never closed.
+    #[error("line {line}: section `{name}` is never closed")]
+    UnclosedSection { line: usize, name: String },
+    /// A section was closed without being opened.
+    #[error("line {line}: `{name}` closes a section that is not open")]
+    UnexpectedClose { line: usize, name: String },
+}
+
+impl Template {
+    /// Parses a template.
+    pub fn parse(source: &str) -> Result<Self, TemplateError> {
+        let line_at = |index: usize| source[..index].matches('\n').count() + 1;
+
+        // The sections that are currently open, along with the nodes before
+        // each of them
+        let mut open: Vec<(String, bool, usize, Vec<Node>)> = Vec::new();
+        let mut nodes = Vec::new();
+        let mut offset = 0;
+        while let Some(start) = source[offset..].find("{{").map(|start| offset + start) {
+            let line = line_at(start);
+            let len = source[start + 2..]
+                .find("}}")
+                .ok_or(TemplateError::UnclosedTag { line })?;
+            let tag = source[start + 2..start + 2 + len].trim();
+            let end = start + 2 + len + 2;
+            let (sigil, name) = match tag.chars().next() {
+                Some(sigil @ ('#' | '^' | '/' | '!')) => (Some(sigil), tag[1..].trim()),
+                _ => (None, tag),
+            };
+            if name.is_empty() && sigil != Some('!') {
+                return Err(TemplateError::EmptyTag { line });
+            }
+
+            // Drop the rest of the line if the tag is on its own line
+            let mut text_end = start;
+            let mut next = end;
+            if sigil.is_some() {
+                let line_start = source[..start].rfind('\n').map_or(0, |index| index + 1);
+                let line_end = source[end..]
+                    .find('\n')
+                    .map_or(source.len(), |index| end + index + 1);
+                if offset <= line_start
+                    && source[line_start..start].trim().is_empty()
+                    && source[end..line_end].trim().is_empty()
+                {
+                    text_end = line_start;
+                    next = line_end;
+                }
+            }
+            if offset < text_end {
+                nodes.push(Node::Text(source[offset..text_end].to_string()));
+            }
+            offset = next;
+
+            match sigil {
+                Some(sigil @ ('#' | '^')) => {
+                    let before = std::mem::take(&mut nodes);
+                    open.push((name.to_string(), sigil == '^', line, before));
+                }
+                Some('/') => {
+                    let Some((open_name, inverted, _, before)) = open.pop() else {
+                        return Err(TemplateError::UnexpectedClose {
+                            line,
+                            name: name.to_string(),
+                        });
+                    };
+                    if open_name != name {
+                        return Err(TemplateError::UnexpectedClose {
+                            line,
+                            name: name.to_string(),
+                        });
+                    }
+
+                    let section = std::mem::replace(&mut nodes, before);
+                    nodes.push(Node::Section {
+                        name: open_name,
+                        inverted,
+                        nodes: section,
+                    });
+                }
+                Some(_) => {}
+                None => nodes.push(Node::Value(name.to_string())),
+            }
+        }
+
+        if let Some((name, _, line, _)) = open.pop() {
+            return Err(TemplateError::UnclosedSection { line, name });
+        }
+        if offset < source.len() {
+            nodes.push(Node::Text(source[offset..].to_string()));
+        }
+
+        Ok(Self { nodes })
+    }
+
+    /// Renders the template with the values in `context`.
+    #[must_use]
+    pub fn render(&self, context: &Value) -> String {
+        let mut output = String::new();
+        render_nodes(&self.nodes, &mut vec![context], &mut output);
+        output
+    }
+}
+
+/// Renders nodes, looking up values in the innermost context first.
+fn render_nodes<'a>(nodes: &'a [Node], contexts: &mut Vec<&'a Value>, output: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => output.push_str(text),
+            Node::Value(name) => match lookup(contexts, name) {
+                Some(Value::String(value)) => output.push_str(value),
+                Some(Value::Null) | None => {}
+                Some(value) => output.push_str(&value.to_string()),
+            },
+            Node::Section {
+                name,
+                inverted,
+                nodes,
+            } => {
+                let value = lookup(contexts, name).unwrap_or(&Value::Null);
+                if *inverted {
+                    if !is_truthy(value) {
+                        render_nodes(nodes, contexts, output);
+                    }
+                    continue;
+                }
+
+                match value {
+                    Value::Array(items) => {
+                        for item in items {
+                            contexts.push(item);
+                            render_nodes(nodes, contexts, output);
+                            contexts.pop();
+                        }
+                    }
+                    value if is_truthy(value) => {
+                        contexts.push(value);
+                        render_nodes(nodes, contexts, output);
+                        contexts.pop();
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+/// Looks up a dotted name, starting from the innermost context that has the
+/// first part of the name.
+fn lookup<'a>(contexts: &[&'a Value], name: &str) -> Option<&'a Value> {
+    if name == "." {
+        return contexts.last().copied();
+    }
+
+    let mut parts = name.split('.');
+    let first = parts.next()?;
+    let mut value = contexts
+        .iter()
+        .rev()
+        .find_map(|context| context.get(first))?;
+    for part in parts {
+        value = value.get(part)?;
+    }
+
+    Some(value)
+}
+
+/// Checks whether a value causes a section to be rendered.
+fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(value) => *value,
+        Value::Number(value) => value.as_f64() != Some(0.0),
+        Value::String(value) => !value.is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        Value::Object(_) => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{Template, TemplateError, DEFAULT_TEMPLATE};
+
+    fn render(source: &str, context: serde_json::Value) -> String {
+        Template::parse(source).unwrap().render(&context)
+    }
+
+    #[test]
+    fn values() {
+        let context = json!({
+            "name": "Stretch",
+            "count": 3,
+            "done": true,
+            "nested": { "inner": "deep" },
+        });
+        assert_eq!(
+            render("{{name}} {{ count }} {{done}} {{nested.inner}}", context),
+            "Stretch 3 true deep"
+        );
+    }
+
+    #[test]
+    fn missing_values_render_nothing() {
+        let context = json!({ "nested": {}, "empty": null });
+        assert_eq!(
+            render("[{{missing}}][{{nested.missing}}][{{empty}}]", context),
+            "[][][]"
+        );
+    }
+
+    #[test]
+    fn values_are_not_escaped_or_expanded() {
+        let context = json!({ "title": "<b>Tom & Jerry</b> {{title}}" });
+        assert_eq!(render("{{title}}", context), "<b>Tom & Jerry</b> {{title}}");
+    }
+
+    #[test]
+    fn list_sections() {
+        let context = json!({
+            "items": [{ "title": "a" }, { "title": "b" }],
+            "names": ["x", "y"],
+            "header": "H",
+        });
+        assert_eq!(
+            render("{{#items}}{{header}}{{title}};{{/items}}", context.clone()),
+            "Ha;Hb;"
+        );
+        assert_eq!(render("{{#names}}{{.}},{{/names}}", context), "x,y,");
+    }
+
+    #[test]
+    fn conditional_sections() {
+        let cases = [
+            (json!(true), true),
+            (json!(1), true),
+            (json!("text"), true),
+            (json!({}), true),
+            (json!([1]), true),
+            (json!(false), false),
+            (json!(0), false),
+            (json!(""), false),
+            (json!([]), false),
+            (json!(null), false),
+        ];
+        for (value, truthy) in cases {
+            let context = json!({ "value": value });
+            let expected = if truthy { "yes" } else { "no" };
+            assert_eq!(
+                render("{{#value}}yes{{/value}}{{^value}}no{{/value}}", context),
+                expected,
+                "{value}"
+            );
+        }
+
+        // Missing values are falsy
+        assert_eq!(render("{{^missing}}no{{/missing}}", json!({})), "no");
+    }
+
+    #[test]
+    fn object_sections_push_context() {
+        let context = json!({ "stats": { "done": 2 } });
+        assert_eq!(render("{{#stats}}{{done}}{{/stats}}", context), "2");
+    }
+
+    #[test]
+    fn standalone_tags_leave_no_lines() {
+        let source = "Start\n  {{#items}}\n- {{.}}\n  {{/items}}\n{{! comment }}\nEnd\n";
+        assert_eq!(
+            render(source, json!({ "items": ["a", "b"] })),
+            "Start\n- a\n- b\nEnd\n"
+        );
+    }
+
+    #[test]
+    fn inline_tags_keep_their_lines() {
+        let source = "a {{#on}}b{{/on}} c\n";
+        assert_eq!(render(source, json!({ "on": true })), "a b c\n");
+    }
+
+    #[test]
+    fn errors() {
+        let cases = [
+            ("one\n{{name", TemplateError::UnclosedTag { line: 2 }),
+            ("{{ }}", TemplateError::EmptyTag { line: 1 }),
+            ("{{#}}", TemplateError::EmptyTag { line: 1 }),
+            (
+                "{{#items}}\n{{.}}",
+                TemplateError::UnclosedSection {
+                    line: 1,
+                    name: "items".into(),
+                },
+            ),
+            (
+                "{{/items}}",
+                TemplateError::UnexpectedClose {
+                    line: 1,
+                    name: "items".into(),
+                },
+            ),
+            (
+                "{{#a}}\n{{#b}}\n{{/a}}",
+                TemplateError::UnexpectedClose {
+                    line: 3,
+                    name: "a".into(),
+                },
+            ),
+        ];
+        for (source, expected) in cases {
+            assert_eq!(Template::parse(source), Err(expected), "{source:?}");
+        }
+    }
+
+    #[test]
+    fn default_template() {
+        let context = json!({
+            "date": "Friday, March 1",
+            "today_count": 1,
+            "today": [{ "time": "9:30 AM", "title": "Stretch" }],
+            "overdue_count": 0,
+            "overdue": [],
+            "yesterday": { "due": 2, "completed": 1, "percent": 50 },
+        });
+        assert_eq!(
+            render(DEFAULT_TEMPLATE, context),
+            "\
+Good morning! Here is what's on for Friday, March 1.
+
+Today (1):
+  - 9:30 AM  Stretch
+
+Yesterday you completed 1 of 2 reminders (50%).
+"
+        );
+    }
+}
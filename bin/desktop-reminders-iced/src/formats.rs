@@ -0,0 +1,44 @@
+mod converted;
+mod cron;
+mod ical;
+mod org;
+mod quick_add;
+mod rrule;
+mod split;
+mod systemd;
+mod taskwarrior;
+mod todo_txt;
+mod toml_store;
+mod vcard;
+
+pub use converted::*;
+pub use cron::*;
+pub use ical::*;
+pub use org::*;
+pub use quick_add::*;
+pub use rrule::*;
+pub use split::*;
+pub use systemd::*;
+pub use taskwarrior::*;
+pub use todo_txt::*;
+pub use toml_store::*;
+pub use vcard::*;
+
+/// Helpers shared by the tests of the formats.
+#[cfg(test)]
+mod test_helpers {
+    use crate::models::reminders::{Reminder, ReminderId};
+
+    /// Creates a reminder named after its ID.
+    pub fn reminder(id: u64, schedule: &str, completed: bool) -> Reminder {
+        Reminder {
+            id: ReminderId(id),
+            frequency: schedule.parse().unwrap(),
+            message: format!("Reminder {id}"),
+            completed,
+            snoozed_until: None,
+            actions: Vec::new(),
+            contact: None,
+        }
+    }
+}
@@ -0,0 +1,20 @@
+/// The result of converting reminders to or from another format, along with
+/// anything that could not be converted exactly.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Converted<T> {
+    /// The converted value.
+    pub value: T,
+    /// Descriptions of what was lost or changed during the conversion.
+    pub warnings: Vec<String>,
+}
+
+impl<T> Converted<T> {
+    /// Creates a conversion result without any warnings.
+    #[inline]
+    pub fn new(value: T) -> Self {
+        Self {
+            value,
+            warnings: Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,596 @@
+use std::{
+    collections::BTreeSet,
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+use chrono::{NaiveTime, Timelike};
+use thiserror::Error;
+
+use crate::models::reminders::{
+    ReminderDaysOfWeek, ReminderFrequency, ReminderFrequencyDaily, ReminderFrequencyMonthly,
+    ReminderFrequencyWeekly, ReminderFrequencyYearly, ReminderMonth, ReminderTimeOfDay,
+    ReminderYearlyDate,
+};
+
+/// The numbers cron uses for the days of the week. Sunday can also be 7.
+const CRON_DAYS: [(ReminderDaysOfWeek, u32); 7] = [
+    (ReminderDaysOfWeek::SUNDAY, 0),
+    (ReminderDaysOfWeek::MONDAY, 1),
+    (ReminderDaysOfWeek::TUESDAY, 2),
+    (ReminderDaysOfWeek::WEDNESDAY, 3),
+    (ReminderDaysOfWeek::THURSDAY, 4),
+    (ReminderDaysOfWeek::FRIDAY, 5),
+    (ReminderDaysOfWeek::SATURDAY, 6),
+];
+
+/// The names cron accepts for the days of the week, starting from Sunday.
+const DAY_NAMES: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+/// The names cron accepts for the months, starting from January.
+const MONTH_NAMES: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+
+/// A field of a cron expression.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CronField {
+    /// The minute of the hour.
+    Minute,
+    /// The hour of the day.
+    Hour,
+    /// The day of the month.
+    DayOfMonth,
+    /// The month of the year.
+    Month,
+    /// The day of the week.
+    DayOfWeek,
+}
+
+impl CronField {
+    /// Gets the smallest and largest values of the field.
+    fn bounds(self) -> (u32, u32) {
+        match self {
+            CronField::Minute => (0, 59),
+            CronField::Hour => (0, 23),
+            CronField::DayOfMonth => (1, 31),
+            CronField::Month => (1, 12),
+            CronField::DayOfWeek => (0, 7),
+        }
+    }
+
+    /// Gets the names that can be used instead of numbers, for the value of
+    /// the first name.
+    fn names(self) -> Option<(&'static [&'static str], u32)> {
+        match self {
+            CronField::Month => Some((&MONTH_NAMES, 1)),
+            CronField::DayOfWeek => Some((&DAY_NAMES, 0)),
+            _ => None,
+        }
+    }
+}
+
+impl Display for CronField {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            CronField::Minute => "minute",
+            CronField::Hour => "hour",
+            CronField::DayOfMonth => "day-of-month",
+            CronField::Month => "month",
+            CronField::DayOfWeek => "day-of-week",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// An error converting between a cron expression and a
+/// [`ReminderFrequency`].
+#[derive(Clone, PartialEq, Eq, Debug, Error)]
+pub enum CronError {
+    /// The expression does not have five fields.
+    #[error("expected 5 fields (minute hour day-of-month month day-of-week), found {0}")]
+    FieldCount(usize),
+    /// A field has a value that is not valid.
+    #[error("invalid {field} {value:?}")]
+    InvalidValue {
+        /// The field.
+        field: CronField,
+        /// The invalid value.
+        value: String,
+    },
+    /// The minute or hour matches more than one value.
+    #[error("{field} {value:?} is not supported: reminders occur at most once a day")]
+    SeveralTimes {
+        /// The field.
+        field: CronField,
+        /// The value that matches more than once.
+        value: String,
+    },
+    /// The expression is a macro reminders cannot represent.
+    #[error("{0} is not supported: reminders repeat at most daily")]
+    UnsupportedMacro(String),
+    /// The fields are combined in a way reminders cannot represent.
+    #[error("{0}")]
+    UnsupportedCombination(&'static str),
+    /// The days of the month are not in any of the months, like
+    /// `0 9 31 2 *`.
+    #[error("day-of-month {month_days:?} is never in month {months:?}")]
+    ImpossibleDates {
+        /// The day-of-month field.
+        month_days: String,
+        /// The month field.
+        months: String,
+    },
+    /// The reminder does not occur at a single time of day.
+    #[error("cron runs at a single time of day, but the reminder {0}")]
+    UnsupportedTime(&'static str),
+    /// The reminder occurs once, so it has no expression.
+    #[error("the reminder occurs once, so it has no cron expression")]
+    OccursOnce,
+    /// The reminder's dates cannot be written as a single expression.
+    #[error("the reminder's dates do not share the same days and months")]
+    UnsupportedDates,
+    /// The reminder never occurs, so it has no expression.
+    #[error("the reminder never occurs")]
+    NeverOccurs,
+}
+
+/// A standard five-field cron expression that a reminder can follow, like
+/// `30 12 * * 1-5`. The time of day is always part of the expression.
+#[derive(Clone, PartialEq, Debug)]
+pub struct CronExpression {
+    frequency: ReminderFrequency,
+}
+
+impl CronExpression {
+    /// Gets the frequency the expression describes.
+    #[inline]
+    pub fn frequency(&self) -> &ReminderFrequency {
+        &self.frequency
+    }
+
+    /// Gets the time of day the expression runs at.
+    pub fn time(&self) -> NaiveTime {
+        match *self.frequency.time_of_day() {
+            ReminderTimeOfDay::Time { time } => time,
+            // Expressions always have a single time of day
+            _ => NaiveTime::default(),
+        }
+    }
+}
+
+impl FromStr for CronExpression {
+    type Err = CronError;
+
+    /// Parses an expression like `30 12 * * 1-5`. Names like `mon` and `jan`,
+    /// ranges, steps and the macros `@daily`, `@weekly`, `@monthly` and
+    /// `@yearly` are supported.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let expanded = match s.to_ascii_lowercase().as_str() {
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            macro_name if macro_name.starts_with('@') => {
+                return Err(CronError::UnsupportedMacro(s.to_string()))
+            }
+            _ => s,
+        };
+
+        let fields: Vec<_> = expanded.split_whitespace().collect();
+        let &[minute, hour, month_days, months, days] = fields.as_slice() else {
+            return Err(CronError::FieldCount(fields.len()));
+        };
+
+        let minute = parse_single(CronField::Minute, minute)?;
+        let hour = parse_single(CronField::Hour, hour)?;
+        let time = ReminderTimeOfDay::Time {
+            time: NaiveTime::from_hms_opt(hour, minute, 0).unwrap_or_default(),
+        };
+        let (month_days_text, months_text) = (month_days, months);
+        let month_days = parse_field(CronField::DayOfMonth, month_days)?;
+        let months = parse_field(CronField::Month, months)?;
+        let days = parse_field(CronField::DayOfWeek, days)?.map(|numbers| {
+            CRON_DAYS
+                .iter()
+                .filter(|&&(_, number)| numbers.contains(&number))
+                .fold(ReminderDaysOfWeek::empty(), |days, &(day, _)| days | day)
+        });
+
+        let frequency = match (month_days, months, days) {
+            (None, None, None) => ReminderFrequency::Daily(ReminderFrequencyDaily { time }),
+            (None, None, Some(days)) => {
+                ReminderFrequency::Weekly(ReminderFrequencyWeekly { days, time })
+            }
+            (Some(dates), None, None) => ReminderFrequency::Monthly(ReminderFrequencyMonthly {
+                dates: dates.into_iter().map(|date| date as u8).collect(),
+                time,
+            }),
+            (Some(dates), Some(months), None) => {
+                // Days that are not in a month, like February 30, never run
+                let dates: Vec<_> = months
+                    .iter()
+                    .filter_map(|&month| ReminderMonth::from_number(month))
+                    .flat_map(|month| {
+                        dates
+                            .iter()
+                            .filter_map(move |&date| ReminderYearlyDate::new(month, date as u8))
+                    })
+                    .collect();
+                if dates.is_empty() {
+                    return Err(CronError::ImpossibleDates {
+                        month_days: month_days_text.to_string(),
+                        months: months_text.to_string(),
+                    });
+                }
+                ReminderFrequency::Yearly(ReminderFrequencyYearly { dates, time })
+            }
+            (Some(_), _, Some(_)) => {
+                return Err(CronError::UnsupportedCombination(
+                    "day-of-month and day-of-week cannot both be restricted: cron runs when \
+                     either matches",
+                ))
+            }
+            (None, Some(_), Some(_)) => {
+                return Err(CronError::UnsupportedCombination(
+                    "day-of-week cannot be restricted to some months",
+                ))
+            }
+            (None, Some(_), None) => {
+                return Err(CronError::UnsupportedCombination(
+                    "month needs a day-of-month: reminders cannot repeat every day of some \
+                     months",
+                ))
+            }
+        };
+
+        Ok(Self { frequency })
+    }
+}
+
+impl TryFrom<&ReminderFrequency> for CronExpression {
+    type Error = CronError;
+
+    /// Creates the expression for a frequency. Cron runs at a minute, so any
+    /// seconds are dropped.
+    fn try_from(frequency: &ReminderFrequency) -> Result<Self, Self::Error> {
+        match frequency.time_of_day() {
+            ReminderTimeOfDay::Time { .. } => {}
+            ReminderTimeOfDay::AllDay => return Err(CronError::UnsupportedTime("is all day")),
+            ReminderTimeOfDay::TimeRange { .. } => {
+                return Err(CronError::UnsupportedTime("lasts a range of time"))
+            }
+        }
+
+        let never = match frequency {
+            ReminderFrequency::Once(_) => return Err(CronError::OccursOnce),
+            ReminderFrequency::Daily(_) => false,
+            ReminderFrequency::Weekly(weekly) => weekly.days.is_empty(),
+            ReminderFrequency::Monthly(monthly) => monthly.dates.is_empty(),
+            ReminderFrequency::Yearly(yearly) => {
+                let dates: BTreeSet<_> = yearly
+                    .dates
+                    .iter()
+                    .map(|date| (date.month.number(), date.date))
+                    .collect();
+                let months: BTreeSet<_> = dates.iter().map(|&(month, _)| month).collect();
+                let days: BTreeSet<_> = dates.iter().map(|&(_, day)| day).collect();
+                if months.len() * days.len() != dates.len() {
+                    return Err(CronError::UnsupportedDates);
+                }
+                dates.is_empty()
+            }
+        };
+        if never {
+            return Err(CronError::NeverOccurs);
+        }
+
+        Ok(Self {
+            frequency: frequency.clone(),
+        })
+    }
+}
+
+impl Display for CronExpression {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let time = self.time();
+        write!(f, "{} {} ", time.minute(), time.hour())?;
+        match &self.frequency {
+            ReminderFrequency::Once(_) | ReminderFrequency::Daily(_) => write!(f, "* * *"),
+            ReminderFrequency::Weekly(weekly) if weekly.days.is_all() => write!(f, "* * *"),
+            ReminderFrequency::Weekly(weekly) => {
+                let days = CRON_DAYS
+                    .iter()
+                    .filter(|&&(day, _)| weekly.days.contains(day))
+                    .map(|&(_, number)| number);
+                write!(f, "* * {}", format_list(days))
+            }
+            ReminderFrequency::Monthly(monthly) => {
+                let dates = monthly.dates.iter().map(|&date| u32::from(date));
+                write!(f, "{} * *", format_list(dates))
+            }
+            ReminderFrequency::Yearly(yearly) => {
+                let dates = yearly.dates.iter().map(|date| u32::from(date.date));
+                let months = yearly.dates.iter().map(|date| date.month.number());
+                write!(f, "{} {} *", format_list(dates), format_list(months))
+            }
+        }
+    }
+}
+
+/// Parses a field that must match a single value.
+fn parse_single(field: CronField, value: &str) -> Result<u32, CronError> {
+    match parse_field(field, value)? {
+        Some(values) if values.len() == 1 => Ok(values[0]),
+        _ => Err(CronError::SeveralTimes {
+            field,
+            value: value.to_string(),
+        }),
+    }
+}
+
+/// Parses a field into the values it matches, or `None` if it matches every
+/// value.
+fn parse_field(field: CronField, value: &str) -> Result<Option<Vec<u32>>, CronError> {
+    if matches!(value, "*" | "?") {
+        return Ok(None);
+    }
+
+    let invalid = || CronError::InvalidValue {
+        field,
+        value: value.to_string(),
+    };
+    let (min, max) = field.bounds();
+    let mut values = BTreeSet::new();
+    for item in value.split(',') {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step.parse().map_err(|_| invalid())?;
+                if step == 0 {
+                    return Err(invalid());
+                }
+                (range, step)
+            }
+            None => (item, 1),
+        };
+        let (start, end) = match range {
+            "*" => (min, max),
+            range => match range.split_once('-') {
+                Some((start, end)) => (
+                    parse_value(field, start).ok_or_else(invalid)?,
+                    parse_value(field, end).ok_or_else(invalid)?,
+                ),
+                // A single value with a step continues to the end
+                None if step > 1 => (parse_value(field, range).ok_or_else(invalid)?, max),
+                None => {
+                    let value = parse_value(field, range).ok_or_else(invalid)?;
+                    (value, value)
+                }
+            },
+        };
+        if start > end || start < min || end > max {
+            return Err(invalid());
+        }
+        values.extend((start..=end).step_by(step as usize));
+    }
+
+    // Sunday can be either 0 or 7
+    let mut max = max;
+    if field == CronField::DayOfWeek {
+        if values.remove(&7) {
+            values.insert(0);
+        }
+        max = 6;
+    }
+
+    // Steps over the whole range still match every value
+    let all = (min..=max).all(|value| values.contains(&value));
+    Ok(match all {
+        true => None,
+        false => Some(values.into_iter().collect()),
+    })
+}
+
+/// Parses a number or name in a field.
+fn parse_value(field: CronField, value: &str) -> Option<u32> {
+    if let Ok(number) = value.parse() {
+        return Some(number);
+    }
+
+    let (names, first) = field.names()?;
+    let value = value.to_ascii_lowercase();
+    let index = names.iter().position(|&name| name == value)?;
+    Some(first + index as u32)
+}
+
+/// Formats values as a list, using ranges for runs of three or more values.
+fn format_list(values: impl IntoIterator<Item = u32>) -> String {
+    let values: BTreeSet<_> = values.into_iter().collect();
+    let mut runs: Vec<(u32, u32)> = Vec::new();
+    for value in values {
+        match runs.last_mut() {
+            Some((_, end)) if *end + 1 == value => *end = value,
+            _ => runs.push((value, value)),
+        }
+    }
+
+    let parts: Vec<_> = runs
+        .into_iter()
+        .flat_map(|(start, end)| match end - start {
+            0 => vec![start.to_string()],
+            1 => vec![start.to_string(), end.to_string()],
+            _ => vec![format!("{start}-{end}")],
+        })
+        .collect();
+    parts.join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::reminders::ReminderFrequency;
+
+    use super::{CronError, CronExpression, CronField};
+
+    fn schedule(schedule: &str) -> ReminderFrequency {
+        schedule.parse().unwrap()
+    }
+
+    fn parse(expression: &str) -> Result<ReminderFrequency, CronError> {
+        expression
+            .parse::<CronExpression>()
+            .map(|expression| expression.frequency().clone())
+    }
+
+    #[test]
+    fn parses_expressions() {
+        let cases = [
+            ("30 12 * * *", "daily @ 12:30"),
+            ("0 9 ? * ?", "daily @ 09:00"),
+            // Lists
+            ("0 9 * * 1,3,5", "weekly mon,wed,fri @ 09:00"),
+            ("0 9 1,15 * *", "monthly 1,15 @ 09:00"),
+            ("0 9 1 3,12 *", "yearly 03-01,12-01 @ 09:00"),
+            // Days that are not in a month never run
+            ("0 9 30 1,2 *", "yearly 01-30 @ 09:00"),
+            // Ranges
+            ("0 9 * * 1-5", "weekly weekdays @ 09:00"),
+            ("0 9 1-3 * *", "monthly 1,2,3 @ 09:00"),
+            ("0 9 1,10-11 * *", "monthly 1,10,11 @ 09:00"),
+            // Steps
+            ("0 9 */10 * *", "monthly 1,11,21,31 @ 09:00"),
+            ("0 9 5/10 * *", "monthly 5,15,25 @ 09:00"),
+            ("0 9 * * 1-5/2", "weekly mon,wed,fri @ 09:00"),
+            ("0 9 15 */6 *", "yearly 01-15,07-15 @ 09:00"),
+            ("0 9 * * */1", "daily @ 09:00"),
+            // Names
+            ("0 9 * * mon,WED,Fri", "weekly mon,wed,fri @ 09:00"),
+            ("0 9 * * mon-fri", "weekly weekdays @ 09:00"),
+            ("0 9 25 dec *", "yearly 12-25 @ 09:00"),
+            ("0 9 1 jan-mar *", "yearly 01-01,02-01,03-01 @ 09:00"),
+            // Sunday is 0 or 7
+            ("0 9 * * 0", "weekly sun @ 09:00"),
+            ("0 9 * * 7", "weekly sun @ 09:00"),
+            ("0 9 * * 6-7", "weekly weekends @ 09:00"),
+            ("0 9 * * 0-7", "daily @ 09:00"),
+            // Macros
+            ("@daily", "daily @ 00:00"),
+            ("@midnight", "daily @ 00:00"),
+            ("@weekly", "weekly sun @ 00:00"),
+            ("@monthly", "monthly 1 @ 00:00"),
+            ("@YEARLY", "yearly 01-01 @ 00:00"),
+            ("@annually", "yearly 01-01 @ 00:00"),
+        ];
+        for (expression, expected) in cases {
+            assert_eq!(parse(expression), Ok(schedule(expected)), "{expression}");
+        }
+    }
+
+    #[test]
+    fn writes_expressions() {
+        let cases = [
+            ("daily @ 12:30", "30 12 * * *"),
+            ("daily @ 09:00:45", "0 9 * * *"),
+            ("weekly mon,wed,fri @ 09:00", "0 9 * * 1,3,5"),
+            ("weekly weekdays @ 09:00", "0 9 * * 1-5"),
+            ("weekly sat,sun @ 09:00", "0 9 * * 0,6"),
+            ("weekly mon,tue,wed,thu,fri,sat,sun @ 09:00", "0 9 * * *"),
+            ("monthly 1,2,3,15 @ 09:00", "0 9 1-3,15 * *"),
+            ("yearly 03-01,12-01 @ 09:00", "0 9 1 3,12 *"),
+        ];
+        for (frequency, expected) in cases {
+            let expression = CronExpression::try_from(&schedule(frequency)).unwrap();
+            assert_eq!(expression.to_string(), expected, "{frequency}");
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_expressions() {
+        let invalid = |field, value: &str| CronError::InvalidValue {
+            field,
+            value: value.to_string(),
+        };
+        let cases = [
+            ("0 9 * *", CronError::FieldCount(4)),
+            ("0 9 * * * 2024", CronError::FieldCount(6)),
+            ("60 9 * * *", invalid(CronField::Minute, "60")),
+            ("0 24 * * *", invalid(CronField::Hour, "24")),
+            ("0 9 0 * *", invalid(CronField::DayOfMonth, "0")),
+            ("0 9 32 * *", invalid(CronField::DayOfMonth, "32")),
+            ("0 9 1 13 *", invalid(CronField::Month, "13")),
+            ("0 9 * * 8", invalid(CronField::DayOfWeek, "8")),
+            ("0 9 * * fri-mon", invalid(CronField::DayOfWeek, "fri-mon")),
+            ("0 9 * * sat-sun", invalid(CronField::DayOfWeek, "sat-sun")),
+            ("0 9 * * funday", invalid(CronField::DayOfWeek, "funday")),
+            ("0 9 */0 * *", invalid(CronField::DayOfMonth, "*/0")),
+            ("0 9 1 mon *", invalid(CronField::Month, "mon")),
+            ("0 9 L * *", invalid(CronField::DayOfMonth, "L")),
+        ];
+        for (expression, expected) in cases {
+            assert_eq!(parse(expression), Err(expected), "{expression}");
+        }
+    }
+
+    #[test]
+    fn rejects_expressions_reminders_cannot_represent() {
+        let several = |field, value: &str| CronError::SeveralTimes {
+            field,
+            value: value.to_string(),
+        };
+        let cases = [
+            ("* 9 * * *", several(CronField::Minute, "*")),
+            ("*/15 9 * * *", several(CronField::Minute, "*/15")),
+            ("0 9,17 * * *", several(CronField::Hour, "9,17")),
+            ("0 9-17 * * *", several(CronField::Hour, "9-17")),
+            ("@hourly", CronError::UnsupportedMacro("@hourly".into())),
+            ("@reboot", CronError::UnsupportedMacro("@reboot".into())),
+            (
+                "0 9 31 2 *",
+                CronError::ImpossibleDates {
+                    month_days: "31".into(),
+                    months: "2".into(),
+                },
+            ),
+            (
+                "0 9 30,31 feb *",
+                CronError::ImpossibleDates {
+                    month_days: "30,31".into(),
+                    months: "feb".into(),
+                },
+            ),
+        ];
+        for (expression, expected) in cases {
+            assert_eq!(parse(expression), Err(expected), "{expression}");
+        }
+
+        for expression in ["0 9 1 * 1", "0 9 * 3 1", "0 9 * 3 *"] {
+            assert!(
+                matches!(parse(expression), Err(CronError::UnsupportedCombination(_))),
+                "{expression}"
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_frequencies_without_an_expression() {
+        let cases = [
+            ("once 2024-03-01 @ 09:00", CronError::OccursOnce),
+            ("daily @ all-day", CronError::UnsupportedTime("is all day")),
+            (
+                "daily @ 09:00-10:00",
+                CronError::UnsupportedTime("lasts a range of time"),
+            ),
+            ("weekly none @ 09:00", CronError::NeverOccurs),
+            ("monthly none @ 09:00", CronError::NeverOccurs),
+            ("yearly none @ 09:00", CronError::NeverOccurs),
+            ("yearly 03-01,12-25 @ 09:00", CronError::UnsupportedDates),
+        ];
+        for (frequency, expected) in cases {
+            assert_eq!(
+                CronExpression::try_from(&schedule(frequency)),
+                Err(expected),
+                "{frequency}"
+            );
+        }
+    }
+}
@@ -0,0 +1,276 @@
+use chrono::{Duration, NaiveDate, NaiveDateTime, Utc};
+
+use crate::{
+    formats::{rrules_for_frequency, Converted},
+    models::reminders::{Reminder, ReminderFrequency, ReminderTimeOfDay},
+};
+
+use super::{escape, write_line, IcsDateTime};
+
+/// The product that created exported calendars.
+const PRODUCT_ID: &str = "-//TehPers//Desktop Reminders//EN";
+
+/// The kind of component reminders are exported as.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum IcsComponentKind {
+    /// `VEVENT`s, which show up in most calendar apps.
+    #[default]
+    Event,
+    /// `VTODO`s, which keep track of whether the reminders are completed.
+    Todo,
+}
+
+impl IcsComponentKind {
+    /// Gets the name of the component.
+    fn name(self) -> &'static str {
+        match self {
+            IcsComponentKind::Event => "VEVENT",
+            IcsComponentKind::Todo => "VTODO",
+        }
+    }
+}
+
+/// Writes reminders as an iCalendar file. Recurring reminders start on their
+/// first occurrence on or after `today`. Reminders that cannot be represented
+/// are skipped and reported as warnings.
+pub fn export_ics(
+    reminders: &[Reminder],
+    kind: IcsComponentKind,
+    today: NaiveDate,
+) -> Converted<String> {
+    let mut output = String::new();
+    write_line(&mut output, "BEGIN:VCALENDAR");
+    write_line(&mut output, "VERSION:2.0");
+    write_line(&mut output, &format!("PRODID:{PRODUCT_ID}"));
+    write_line(&mut output, "CALSCALE:GREGORIAN");
+
+    let stamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+    let before_today = today.and_hms_opt(0, 0, 0).unwrap_or_default() - Duration::nanoseconds(1);
+    let mut warnings = Vec::new();
+    for reminder in reminders {
+        let name = format!("reminder {} ({:?})", reminder.id, reminder.message);
+        let rules = match rrules_for_frequency(&reminder.frequency) {
+            Ok(rules) if rules.is_empty() => vec![(reminder.frequency.clone(), None)],
+            Ok(rules) => rules
+                .into_iter()
+                .map(|(part, rule)| (part, Some(rule)))
+                .collect(),
+            Err(error) => {
+                warnings.push(format!("{name}: skipped: {error}"));
+                continue;
+            }
+        };
+        if rules.len() > 1 {
+            warnings.push(format!(
+                "{name}: split into {} components because its dates do not share a day of the \
+                 month",
+                rules.len()
+            ));
+        }
+
+        let split = rules.len() > 1;
+        for (index, (part, rule)) in rules.into_iter().enumerate() {
+            let start = match &part {
+                ReminderFrequency::Once(once) => Some(once.date),
+                part => part
+                    .next_occurrence_after(before_today)
+                    .map(|at: NaiveDateTime| at.date()),
+            };
+            let Some(start) = start else {
+                warnings.push(format!("{name}: skipped: it never occurs"));
+                continue;
+            };
+
+            let uid = match split {
+                true => format!("reminder-{}-{}@{}", reminder.id, index + 1, PRODUCT_HOST),
+                false => format!("reminder-{}@{}", reminder.id, PRODUCT_HOST),
+            };
+            write_component(
+                &mut output,
+                kind,
+                reminder,
+                &uid,
+                &stamp,
+                start,
+                rule.as_deref(),
+            );
+        }
+    }
+
+    write_line(&mut output, "END:VCALENDAR");
+    Converted {
+        value: output,
+        warnings,
+    }
+}
+
+/// The domain used in the UIDs of exported components.
+const PRODUCT_HOST: &str = "desktop-reminders";
+
+/// Writes a single component for a reminder.
+fn write_component(
+    output: &mut String,
+    kind: IcsComponentKind,
+    reminder: &Reminder,
+    uid: &str,
+    stamp: &str,
+    start: NaiveDate,
+    rule: Option<&str>,
+) {
+    write_line(output, &format!("BEGIN:{}", kind.name()));
+    write_line(output, &format!("UID:{uid}"));
+    write_line(output, &format!("DTSTAMP:{stamp}"));
+    write_line(output, &format!("SUMMARY:{}", escape(&reminder.message)));
+
+    // To-dos are due at the end of the reminder, and must not be due at the
+    // moment they start
+    let end_name = match kind {
+        IcsComponentKind::Event => "DTEND",
+        IcsComponentKind::Todo => "DUE",
+    };
+    let (dtstart, end) = match *reminder.frequency.time_of_day() {
+        ReminderTimeOfDay::AllDay => (
+            IcsDateTime::Date(start),
+            Some(IcsDateTime::Date(start + Duration::days(1))),
+        ),
+        ReminderTimeOfDay::Time { time } => (IcsDateTime::DateTime(start.and_time(time)), None),
+        ReminderTimeOfDay::TimeRange {
+            start: start_time,
+            end: end_time,
+        } => {
+            // Ranges that end before they start continue past midnight
+            let end_date = match end_time <= start_time {
+                true => start + Duration::days(1),
+                false => start,
+            };
+            (
+                IcsDateTime::DateTime(start.and_time(start_time)),
+                Some(IcsDateTime::DateTime(end_date.and_time(end_time))),
+            )
+        }
+    };
+    write_line(output, &dtstart.to_property("DTSTART"));
+    if let Some(end) = end {
+        write_line(output, &end.to_property(end_name));
+    }
+    if let Some(rule) = rule {
+        write_line(output, &format!("RRULE:{rule}"));
+    }
+    if kind == IcsComponentKind::Todo {
+        let status = match reminder.completed {
+            true => "COMPLETED",
+            false => "NEEDS-ACTION",
+        };
+        write_line(output, &format!("STATUS:{status}"));
+    }
+
+    write_line(output, &format!("END:{}", kind.name()));
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use crate::{
+        formats::{import_ics, test_helpers::reminder},
+        models::reminders::{Reminder, ReminderFrequency},
+    };
+
+    use super::{export_ics, IcsComponentKind};
+
+    /// Gives a reminder a message that needs escaping.
+    fn escaped(reminder: Reminder) -> Reminder {
+        Reminder {
+            message: format!(
+                "Reminder; {}, with \\ escapes\nand a line break",
+                reminder.id
+            ),
+            ..reminder
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let schedules = [
+            "once 2024-03-05 @ 12:30",
+            "once 2024-03-05 @ all-day",
+            "once 2024-03-05 @ 09:00-10:30",
+            "once 2024-03-05 @ 22:00-01:00",
+            "daily @ 09:00",
+            "daily @ all-day",
+            "weekly mon,wed,fri @ 12:30",
+            "weekly sat,sun @ 10:00-12:00",
+            "monthly 1,15 @ all-day",
+            "monthly 31 @ 08:00",
+            "yearly 03-01 @ all-day",
+            "yearly 12-25 @ 09:00",
+        ];
+        for kind in [IcsComponentKind::Event, IcsComponentKind::Todo] {
+            for schedule in schedules {
+                let original = escaped(reminder(1, schedule, false));
+                let exported = export_ics(std::slice::from_ref(&original), kind, today);
+                assert_eq!(exported.warnings, Vec::<String>::new(), "{schedule}");
+
+                let imported = import_ics(&exported.value).unwrap();
+                assert_eq!(imported.warnings, Vec::<String>::new(), "{schedule}");
+                let [reminder] = imported.value.as_slice() else {
+                    panic!("{schedule}: expected one reminder: {:?}", imported.value);
+                };
+                assert_eq!(
+                    reminder.frequency, original.frequency,
+                    "{kind:?} {schedule}"
+                );
+                assert_eq!(reminder.message, original.message);
+            }
+        }
+    }
+
+    #[test]
+    fn round_trip_completed_todo() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let reminders = [
+            escaped(reminder(1, "once 2024-03-05 @ 12:30", true)),
+            escaped(reminder(2, "once 2024-03-05 @ 12:30", false)),
+        ];
+        let exported = export_ics(&reminders, IcsComponentKind::Todo, today);
+        let imported = import_ics(&exported.value).unwrap().value;
+        let completed: Vec<_> = imported.iter().map(|reminder| reminder.completed).collect();
+        assert_eq!(completed, [true, false]);
+    }
+
+    #[test]
+    fn splits_yearly_dates_on_different_days() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let original = escaped(reminder(1, "yearly 03-01,12-25 @ 09:00", false));
+        let exported = export_ics(&[original], IcsComponentKind::Event, today);
+        assert_eq!(exported.warnings.len(), 1);
+
+        let imported = import_ics(&exported.value).unwrap().value;
+        let frequencies: Vec<_> = imported
+            .into_iter()
+            .map(|reminder| reminder.frequency)
+            .collect();
+        let expected: Vec<ReminderFrequency> = vec![
+            "yearly 03-01 @ 09:00".parse().unwrap(),
+            "yearly 12-25 @ 09:00".parse().unwrap(),
+        ];
+        assert_eq!(frequencies, expected);
+    }
+
+    #[test]
+    fn folds_long_lines() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let mut original = escaped(reminder(1, "daily @ 09:00", false));
+        original.message = "word ".repeat(40).trim_end().to_string();
+        let exported = export_ics(
+            std::slice::from_ref(&original),
+            IcsComponentKind::Event,
+            today,
+        );
+        assert!(exported.value.lines().all(|line| line.len() <= 75));
+
+        let imported = import_ics(&exported.value).unwrap().value;
+        assert_eq!(imported[0].message, original.message);
+    }
+}
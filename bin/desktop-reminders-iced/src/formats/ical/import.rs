@@ -0,0 +1,289 @@
+use chrono::Duration;
+
+use crate::{
+    formats::{frequency_from_rrule, Converted},
+    models::reminders::{Reminder, ReminderFrequency, ReminderFrequencyOnce, ReminderTimeOfDay},
+};
+
+use super::{IcsComponent, IcsDateTime, IcsError};
+
+/// Reads reminders from the `VEVENT` and `VTODO` components of an iCalendar
+/// file. Components that cannot be represented as reminders are skipped and
+/// reported as warnings.
+pub fn import_ics(source: &str) -> Result<Converted<Vec<Reminder>>, IcsError> {
+    let calendars: Vec<_> = IcsComponent::parse_all(source)?
+        .into_iter()
+        .filter(|component| component.name == "VCALENDAR")
+        .collect();
+    if calendars.is_empty() {
+        return Err(IcsError::NoCalendar);
+    }
+
+    let mut converted = Converted::new(Vec::new());
+    let components = calendars
+        .iter()
+        .flat_map(|calendar| &calendar.children)
+        .filter(|component| matches!(component.name.as_str(), "VEVENT" | "VTODO"));
+    for component in components {
+        let name = component
+            .text("SUMMARY")
+            .map_or_else(|| component.name.clone(), |summary| format!("{summary:?}"));
+        let mut warn = |message: String| {
+            converted
+                .warnings
+                .push(format!("line {}: {name}: {message}", component.line));
+        };
+
+        match import_component(component, &mut warn) {
+            Ok(reminder) => converted.value.push(reminder),
+            Err(error) => warn(format!("skipped: {error}")),
+        }
+    }
+
+    Ok(converted)
+}
+
+/// Converts a `VEVENT` or `VTODO` into a reminder.
+fn import_component(
+    component: &IcsComponent,
+    warn: &mut impl FnMut(String),
+) -> Result<Reminder, String> {
+    let message = component.text("SUMMARY").ok_or("it has no SUMMARY")?;
+
+    // To-dos might only have a due date
+    let mut start_property = component.property("DTSTART");
+    let mut end_property = component
+        .property("DTEND")
+        .or_else(|| component.property("DUE"));
+    if start_property.is_none() && component.name == "VTODO" {
+        start_property = end_property.take();
+    }
+    let start_property = start_property.ok_or("it has no DTSTART")?;
+    let (start, other_zone) = IcsDateTime::parse(start_property)
+        .ok_or_else(|| format!("invalid DTSTART {:?}", start_property.value))?;
+    if other_zone {
+        warn("its time zone is treated as local time".to_string());
+    }
+
+    let end = match end_property {
+        Some(property) => Some(
+            IcsDateTime::parse(property)
+                .ok_or_else(|| format!("invalid {} {:?}", property.name, property.value))?
+                .0,
+        ),
+        None => match (start, component.property("DURATION")) {
+            (IcsDateTime::DateTime(start), Some(property)) => Some(IcsDateTime::DateTime(
+                start
+                    + parse_duration(&property.value)
+                        .ok_or_else(|| format!("invalid DURATION {:?}", property.value))?,
+            )),
+            _ => None,
+        },
+    };
+
+    let time = match (start, end) {
+        (IcsDateTime::Date(start), end) => {
+            if end.is_some_and(|end| end.date() > start + Duration::days(1)) {
+                warn("it lasts several days, but only its first day is kept".to_string());
+            }
+            ReminderTimeOfDay::AllDay
+        }
+        (IcsDateTime::DateTime(start), Some(IcsDateTime::DateTime(end))) if start < end => {
+            if end - start >= Duration::days(1) {
+                warn("it lasts a day or longer, but only its start time is kept".to_string());
+                ReminderTimeOfDay::Time { time: start.time() }
+            } else {
+                ReminderTimeOfDay::TimeRange {
+                    start: start.time(),
+                    end: end.time(),
+                }
+            }
+        }
+        (IcsDateTime::DateTime(start), _) => ReminderTimeOfDay::Time { time: start.time() },
+    };
+
+    let frequency = match component.property("RRULE") {
+        Some(rule) => frequency_from_rrule(&rule.value, start.date(), time)
+            .map_err(|error| format!("RRULE: {error}"))?,
+        None => ReminderFrequency::Once(ReminderFrequencyOnce {
+            date: start.date(),
+            time,
+        }),
+    };
+    for name in ["RDATE", "EXDATE"] {
+        if component.property(name).is_some() {
+            warn(format!("{name} is ignored"));
+        }
+    }
+
+    let completed = component.property("COMPLETED").is_some()
+        || component
+            .property("STATUS")
+            .is_some_and(|status| status.value.eq_ignore_ascii_case("COMPLETED"));
+
+    Ok(Reminder {
+        id: Default::default(),
+        frequency,
+        message,
+        completed,
+        snoozed_until: None,
+        actions: Vec::new(),
+        contact: None,
+    })
+}
+
+/// Parses a duration like `PT1H30M` or `P1D`. Negative durations are not
+/// supported.
+fn parse_duration(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let value = value.strip_prefix('+').unwrap_or(value);
+    let mut rest = value.strip_prefix('P')?;
+    let mut duration = Duration::zero();
+    let mut in_time = false;
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix('T') {
+            in_time = true;
+            rest = after;
+            continue;
+        }
+
+        let digits = rest.find(|c: char| !c.is_ascii_digit())?;
+        let amount: i64 = rest[..digits].parse().ok()?;
+        let mut unit = rest[digits..].chars();
+        duration = duration
+            + match (unit.next()?, in_time) {
+                ('W', false) => Duration::weeks(amount),
+                ('D', false) => Duration::days(amount),
+                ('H', true) => Duration::hours(amount),
+                ('M', true) => Duration::minutes(amount),
+                ('S', true) => Duration::seconds(amount),
+                _ => return None,
+            };
+        rest = unit.as_str();
+    }
+
+    Some(duration)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::reminders::ReminderFrequency;
+
+    use super::{import_ics, IcsError};
+
+    /// A calendar like the ones calendar apps export.
+    const CALENDAR: &str = "\
+BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//Example Corp//Calendar 1.0//EN\r
+BEGIN:VTIMEZONE\r
+TZID:America/New_York\r
+BEGIN:STANDARD\r
+DTSTART:20071104T020000\r
+RRULE:FREQ=YEARLY;BYMONTH=11;BYDAY=1SU\r
+TZOFFSETFROM:-0400\r
+TZOFFSETTO:-0500\r
+END:STANDARD\r
+END:VTIMEZONE\r
+BEGIN:VEVENT\r
+UID:standup@example.com\r
+DTSTAMP:20240301T120000Z\r
+DTSTART;TZID=America/New_York:20240304T093000\r
+DTEND;TZID=America/New_York:20240304T094500\r
+RRULE:FREQ=WEEKLY;BYDAY=MO,WE,FR\r
+EXDATE;TZID=America/New_York:20240306T093000\r
+SUMMARY:Team standup\\, daily-ish\r
+END:VEVENT\r
+BEGIN:VTODO\r
+UID:rent@example.com\r
+DTSTAMP:20240301T120000Z\r
+DUE;VALUE=DATE:20240301\r
+RRULE:FREQ=MONTHLY;BYMONTHDAY=1\r
+SUMMARY:Pay rent\r
+STATUS:NEEDS-ACTION\r
+END:VTODO\r
+BEGIN:VEVENT\r
+UID:anniversary@example.com\r
+DTSTAMP:20240301T120000Z\r
+DTSTART;VALUE=DATE:20240615\r
+DTEND;VALUE=DATE:20240616\r
+RRULE:FREQ=YEARLY\r
+SUMMARY:Anniversary\r
+END:VEVENT\r
+BEGIN:VTODO\r
+UID:taxes@example.com\r
+DTSTAMP:20240301T120000Z\r
+DUE:20240415T170000\r
+COMPLETED:20240410T120000Z\r
+SUMMARY:File ta\r
+ xes\r
+END:VTODO\r
+BEGIN:VEVENT\r
+UID:water@example.com\r
+DTSTAMP:20240301T120000Z\r
+DTSTART:20240301T090000\r
+RRULE:FREQ=HOURLY\r
+SUMMARY:Drink water\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+    #[test]
+    fn imports_calendar() {
+        let imported = import_ics(CALENDAR).unwrap();
+        let reminders: Vec<_> = imported
+            .value
+            .iter()
+            .map(|reminder| {
+                (
+                    reminder.message.as_str(),
+                    reminder.frequency.clone(),
+                    reminder.completed,
+                )
+            })
+            .collect();
+        let schedule = |schedule: &str| schedule.parse::<ReminderFrequency>().unwrap();
+        assert_eq!(
+            reminders,
+            [
+                (
+                    "Team standup, daily-ish",
+                    schedule("weekly mon,wed,fri @ 09:30-09:45"),
+                    false,
+                ),
+                ("Pay rent", schedule("monthly 1 @ all-day"), false),
+                ("Anniversary", schedule("yearly 06-15 @ all-day"), false),
+                ("File taxes", schedule("once 2024-04-15 @ 17:00"), true),
+            ]
+        );
+
+        assert_eq!(
+            imported.warnings,
+            [
+                "line 13: \"Team standup, daily-ish\": its time zone is treated as local time",
+                "line 13: \"Team standup, daily-ish\": EXDATE is ignored",
+                "line 46: \"Drink water\": skipped: RRULE: FREQ=HOURLY is not supported: \
+                 reminders repeat at most daily",
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_files_without_calendars() {
+        let source = "BEGIN:VCARD\r\nFN:Jane\r\nEND:VCARD\r\n";
+        assert_eq!(import_ics(source), Err(IcsError::NoCalendar));
+    }
+
+    #[test]
+    fn rejects_unclosed_components() {
+        let source = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nEND:VCALENDAR\r\n";
+        assert_eq!(
+            import_ics(source),
+            Err(IcsError::MismatchedEnd {
+                line: 3,
+                expected: "VEVENT".into(),
+                found: "VCALENDAR".into(),
+            })
+        );
+    }
+}
@@ -0,0 +1,325 @@
+use chrono::{Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use thiserror::Error;
+
+/// The longest a line of an iCalendar file should be, in bytes.
+const MAX_LINE_LENGTH: usize = 75;
+
+/// An error reading the structure of an iCalendar file.
+#[derive(Clone, PartialEq, Eq, Debug, Error)]
+pub enum IcsError {
+    /// A line is not a valid content line.
+    #[error("line {line}: expected NAME:VALUE")]
+    MalformedLine {
+        /// The line number.
+        line: usize,
+    },
+    /// A property is outside of any component.
+    #[error("line {line}: {name} is outside of a component")]
+    PropertyOutsideComponent {
+        /// The line number.
+        line: usize,
+        /// The name of the property.
+        name: String,
+    },
+    /// A component was ended without being started.
+    #[error("line {line}: END:{found} does not match BEGIN:{expected}")]
+    MismatchedEnd {
+        /// The line number.
+        line: usize,
+        /// The component that is open, if any.
+        expected: String,
+        /// The component that was ended.
+        found: String,
+    },
+    /// A component was started but never ended.
+    #[error("line {line}: BEGIN:{name} is never ended")]
+    UnclosedComponent {
+        /// The line number.
+        line: usize,
+        /// The name of the component.
+        name: String,
+    },
+    /// The file does not contain a calendar.
+    #[error("the file does not contain a VCALENDAR")]
+    NoCalendar,
+    /// The file does not contain a contact.
+    #[error("the file does not contain a VCARD")]
+    NoContact,
+}
+
+/// A property of an iCalendar component, like `DTSTART;VALUE=DATE:20240301`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct IcsProperty {
+    /// The line the property starts on.
+    pub line: usize,
+    /// The name of the property, in uppercase.
+    pub name: String,
+    /// The parameters of the property, with names in uppercase.
+    pub params: Vec<(String, String)>,
+    /// The raw value of the property.
+    pub value: String,
+}
+
+impl IcsProperty {
+    /// Gets the value of a parameter.
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(param, _)| param == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// A component of an iCalendar file, like a `VEVENT`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct IcsComponent {
+    /// The line the component starts on.
+    pub line: usize,
+    /// The name of the component, in uppercase.
+    pub name: String,
+    /// The properties of the component.
+    pub properties: Vec<IcsProperty>,
+    /// The components nested in this component.
+    pub children: Vec<IcsComponent>,
+}
+
+impl IcsComponent {
+    /// Reads the components of an iCalendar file, or a file using the same
+    /// syntax like a vCard file.
+    pub fn parse_all(source: &str) -> Result<Vec<Self>, IcsError> {
+        let mut roots = Vec::new();
+        let mut open: Vec<IcsComponent> = Vec::new();
+        for property in content_lines(source)? {
+            match property.name.as_str() {
+                "BEGIN" => open.push(IcsComponent {
+                    line: property.line,
+                    name: property.value.to_ascii_uppercase(),
+                    properties: Vec::new(),
+                    children: Vec::new(),
+                }),
+                "END" => {
+                    let found = property.value.to_ascii_uppercase();
+                    let component = match open.pop() {
+                        Some(component) if component.name == found => component,
+                        component => {
+                            return Err(IcsError::MismatchedEnd {
+                                line: property.line,
+                                expected: component.map(|c| c.name).unwrap_or_default(),
+                                found,
+                            })
+                        }
+                    };
+                    match open.last_mut() {
+                        Some(parent) => parent.children.push(component),
+                        None => roots.push(component),
+                    }
+                }
+                _ => match open.last_mut() {
+                    Some(component) => component.properties.push(property),
+                    None => {
+                        return Err(IcsError::PropertyOutsideComponent {
+                            line: property.line,
+                            name: property.name,
+                        })
+                    }
+                },
+            }
+        }
+
+        if let Some(component) = open.pop() {
+            return Err(IcsError::UnclosedComponent {
+                line: component.line,
+                name: component.name,
+            });
+        }
+
+        Ok(roots)
+    }
+
+    /// Gets the first property with the given name.
+    pub fn property(&self, name: &str) -> Option<&IcsProperty> {
+        self.properties
+            .iter()
+            .find(|property| property.name == name)
+    }
+
+    /// Gets the unescaped text of the first property with the given name.
+    pub fn text(&self, name: &str) -> Option<String> {
+        self.property(name)
+            .map(|property| unescape(&property.value))
+    }
+}
+
+/// Reads the unfolded content lines of a file.
+fn content_lines(source: &str) -> Result<Vec<IcsProperty>, IcsError> {
+    // Lines starting with whitespace continue the previous line
+    let mut lines: Vec<(usize, String)> = Vec::new();
+    for (index, line) in source.lines().enumerate() {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some((_, previous))) => previous.push_str(rest),
+            _ if line.trim().is_empty() => {}
+            _ => lines.push((index + 1, line.to_string())),
+        }
+    }
+
+    lines
+        .into_iter()
+        .map(|(line, text)| parse_content_line(line, &text))
+        .collect()
+}
+
+/// Parses a single unfolded content line.
+fn parse_content_line(line: usize, text: &str) -> Result<IcsProperty, IcsError> {
+    // Split on the separators that are not quoted
+    let mut quoted = false;
+    let mut separators = Vec::new();
+    let mut value_start = None;
+    for (index, c) in text.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            ';' if !quoted => separators.push(index),
+            ':' if !quoted => {
+                value_start = Some(index);
+                break;
+            }
+            _ => {}
+        }
+    }
+    let value_start = value_start.ok_or(IcsError::MalformedLine { line })?;
+
+    let name_end = separators.first().copied().unwrap_or(value_start);
+    let name = text[..name_end].trim();
+    if name.is_empty() {
+        return Err(IcsError::MalformedLine { line });
+    }
+
+    // Property names can be grouped, like "item1.X-ABDATE"
+    let name = name.rsplit('.').next().unwrap_or(name);
+    let bounds = separators.iter().copied().chain([value_start]);
+    let params = separators
+        .iter()
+        .zip(bounds.skip(1))
+        .filter_map(|(&start, end)| {
+            let (name, value) = text[start + 1..end].split_once('=')?;
+            let value = value.trim_matches('"');
+            Some((name.trim().to_ascii_uppercase(), value.to_string()))
+        })
+        .collect();
+
+    Ok(IcsProperty {
+        line,
+        name: name.to_ascii_uppercase(),
+        params,
+        value: text[value_start + 1..].to_string(),
+    })
+}
+
+/// Unescapes a text value.
+pub fn unescape(value: &str) -> String {
+    let mut text = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            text.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n' | 'N') => text.push('\n'),
+            Some(c) => text.push(c),
+            None => text.push('\\'),
+        }
+    }
+
+    text
+}
+
+/// Escapes a text value.
+pub fn escape(text: &str) -> String {
+    let mut value = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | ';' | ',' => {
+                value.push('\\');
+                value.push(c);
+            }
+            '\n' => value.push_str("\\n"),
+            '\r' => {}
+            c => value.push(c),
+        }
+    }
+
+    value
+}
+
+/// Writes a content line, folding it so no line is too long.
+pub fn write_line(output: &mut String, line: &str) {
+    let mut rest = line;
+    let mut limit = MAX_LINE_LENGTH;
+    while rest.len() > limit {
+        let mut split = limit;
+        while !rest.is_char_boundary(split) {
+            split -= 1;
+        }
+
+        output.push_str(&rest[..split]);
+        output.push_str("\r\n ");
+        rest = &rest[split..];
+        // The space at the start of the next line counts towards its length
+        limit = MAX_LINE_LENGTH - 1;
+    }
+
+    output.push_str(rest);
+    output.push_str("\r\n");
+}
+
+/// A date or date and time value.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum IcsDateTime {
+    /// A whole day.
+    Date(NaiveDate),
+    /// A local date and time.
+    DateTime(NaiveDateTime),
+}
+
+impl IcsDateTime {
+    /// Parses the value of a property like `DTSTART`. Times in UTC are
+    /// converted to local time, and times in other time zones are treated as
+    /// local time, so `in_other_zone` is set for those.
+    pub fn parse(property: &IcsProperty) -> Option<(Self, bool)> {
+        let value = property.value.trim();
+        if property.param("VALUE") == Some("DATE") || value.len() == 8 {
+            let date = NaiveDate::parse_from_str(value, "%Y%m%d").ok()?;
+            return Some((IcsDateTime::Date(date), false));
+        }
+
+        if let Some(utc) = value.strip_suffix(['Z', 'z']) {
+            let at = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+            let at = Utc
+                .from_utc_datetime(&at)
+                .with_timezone(&Local)
+                .naive_local();
+            return Some((IcsDateTime::DateTime(at), false));
+        }
+
+        let at = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+        Some((IcsDateTime::DateTime(at), property.param("TZID").is_some()))
+    }
+
+    /// Gets the date of the value.
+    pub fn date(self) -> NaiveDate {
+        match self {
+            IcsDateTime::Date(date) => date,
+            IcsDateTime::DateTime(at) => at.date(),
+        }
+    }
+
+    /// Formats the value as a property, like `DTSTART;VALUE=DATE:20240301`.
+    pub fn to_property(self, name: &str) -> String {
+        match self {
+            IcsDateTime::Date(date) => format!("{name};VALUE=DATE:{}", date.format("%Y%m%d")),
+            IcsDateTime::DateTime(at) => format!("{name}:{}", at.format("%Y%m%dT%H%M%S")),
+        }
+    }
+}
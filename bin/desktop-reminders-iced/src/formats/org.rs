@@ -0,0 +1,7 @@
+mod export;
+mod import;
+mod syntax;
+
+pub use export::*;
+pub use import::*;
+pub use syntax::*;
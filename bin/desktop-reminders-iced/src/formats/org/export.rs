@@ -0,0 +1,181 @@
+use chrono::NaiveDate;
+
+use crate::{
+    formats::{first_date_from, split_frequency, Converted},
+    models::reminders::{Reminder, ReminderFrequency},
+};
+
+use super::{OrgRepeater, OrgRepeaterKind, OrgTimestamp, OrgUnit};
+
+/// Writes reminders as to-do headings of an Org file. Recurring reminders are
+/// scheduled on their first occurrence on or after `today`. Reminders that
+/// cannot be represented are skipped and reported as warnings.
+pub fn export_org(reminders: &[Reminder], today: NaiveDate) -> Converted<String> {
+    let mut output = String::new();
+    let mut warnings = Vec::new();
+    for reminder in reminders {
+        let name = format!("reminder {} ({:?})", reminder.id, reminder.message);
+        let parts = split_frequency(&reminder.frequency);
+        if parts.len() > 1 {
+            warnings.push(format!(
+                "{name}: split into {} headings because Org timestamps only repeat on one day",
+                parts.len()
+            ));
+        }
+
+        let title = reminder.message.lines().collect::<Vec<_>>().join(" ");
+        if title != reminder.message {
+            warnings.push(format!("{name}: its lines were joined into one heading"));
+        }
+
+        let keyword = match reminder.completed {
+            true => "DONE",
+            false => "TODO",
+        };
+        let mut written = false;
+        for part in parts {
+            let Some(date) = first_date_from(&part, today) else {
+                continue;
+            };
+
+            let timestamp = OrgTimestamp {
+                active: true,
+                date,
+                time: *part.time_of_day(),
+                repeater: repeater_for(&part),
+                has_delay: false,
+                is_range: false,
+            };
+            output.push_str(&format!("* {keyword} {title}\n"));
+            output.push_str(&format!("  SCHEDULED: {timestamp}\n"));
+            written = true;
+        }
+        if !written {
+            warnings.push(format!("{name}: skipped: it never occurs"));
+        }
+    }
+
+    Converted {
+        value: output,
+        warnings,
+    }
+}
+
+/// Gets the repeater of a frequency that occurs on a single day.
+fn repeater_for(frequency: &ReminderFrequency) -> Option<OrgRepeater> {
+    let unit = match frequency {
+        ReminderFrequency::Once(_) => return None,
+        ReminderFrequency::Daily(_) => OrgUnit::Day,
+        ReminderFrequency::Weekly(_) => OrgUnit::Week,
+        ReminderFrequency::Monthly(_) => OrgUnit::Month,
+        ReminderFrequency::Yearly(_) => OrgUnit::Year,
+    };
+
+    Some(OrgRepeater {
+        kind: OrgRepeaterKind::Cumulate,
+        amount: 1,
+        unit,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use crate::{
+        formats::{import_org, test_helpers::reminder},
+        models::reminders::ReminderFrequency,
+    };
+
+    use super::export_org;
+
+    #[test]
+    fn writes_headings() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let reminders = [
+            reminder(1, "once 2024-03-05 @ 12:30", false),
+            reminder(2, "weekly mon @ 09:00-10:30", true),
+            reminder(3, "monthly 15 @ all-day", false),
+        ];
+        let exported = export_org(&reminders, today);
+        assert_eq!(exported.warnings, Vec::<String>::new());
+        assert_eq!(
+            exported.value,
+            "\
+* TODO Reminder 1
+  SCHEDULED: <2024-03-05 Tue 12:30>
+* DONE Reminder 2
+  SCHEDULED: <2024-03-04 Mon 09:00-10:30 +1w>
+* TODO Reminder 3
+  SCHEDULED: <2024-03-15 Fri +1m>
+"
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let schedules = [
+            "once 2024-03-05 @ 12:30",
+            "once 2024-03-05 @ all-day",
+            "once 2024-03-05 @ 22:00-01:00",
+            "daily @ 09:00",
+            "daily @ all-day",
+            "weekly sat @ 10:00-12:00",
+            "monthly 31 @ 08:00",
+            "yearly 02-29 @ all-day",
+            "yearly 12-25 @ 09:00",
+        ];
+        for completed in [false, true] {
+            for schedule in schedules {
+                let original = reminder(1, schedule, completed);
+                let exported = export_org(std::slice::from_ref(&original), today);
+                assert_eq!(exported.warnings, Vec::<String>::new(), "{schedule}");
+
+                let imported = import_org(&exported.value);
+                assert_eq!(imported.warnings, Vec::<String>::new(), "{schedule}");
+                let [reminder] = imported.value.as_slice() else {
+                    panic!("{schedule}: expected one reminder: {:?}", imported.value);
+                };
+                assert_eq!(reminder.frequency, original.frequency, "{schedule}");
+                assert_eq!(reminder.message, original.message);
+                assert_eq!(reminder.completed, completed, "{schedule}");
+            }
+        }
+    }
+
+    #[test]
+    fn splits_frequencies_on_several_days() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let mut original = reminder(1, "weekly mon,wed @ 09:00", false);
+        original.message = "Stand\nup".to_string();
+        let exported = export_org(&[original], today);
+        assert_eq!(exported.warnings.len(), 2);
+
+        let imported = import_org(&exported.value);
+        let reminders: Vec<_> = imported
+            .value
+            .into_iter()
+            .map(|reminder| (reminder.message, reminder.frequency))
+            .collect();
+        let schedule = |schedule: &str| schedule.parse::<ReminderFrequency>().unwrap();
+        assert_eq!(
+            reminders,
+            [
+                ("Stand up".to_string(), schedule("weekly mon @ 09:00")),
+                ("Stand up".to_string(), schedule("weekly wed @ 09:00")),
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_reminders_that_never_occur() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let exported = export_org(&[reminder(1, "weekly none @ all-day", false)], today);
+        assert_eq!(exported.value, "");
+        assert_eq!(
+            exported.warnings,
+            ["reminder 1 (\"Reminder 1\"): skipped: it never occurs"]
+        );
+    }
+}
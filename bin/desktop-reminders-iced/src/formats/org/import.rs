@@ -0,0 +1,243 @@
+use chrono::Datelike;
+
+use crate::{
+    formats::Converted,
+    models::reminders::{
+        Reminder, ReminderDaysOfWeek, ReminderFrequency, ReminderFrequencyDaily,
+        ReminderFrequencyMonthly, ReminderFrequencyOnce, ReminderFrequencyWeekly,
+        ReminderFrequencyYearly, ReminderMonth, ReminderYearlyDate,
+    },
+};
+
+use super::{OrgHeading, OrgRepeater, OrgRepeaterKind, OrgTimestamp, OrgUnit};
+
+/// Reads reminders from the to-do headings of an Org file, using their
+/// `SCHEDULED:` or `DEADLINE:` timestamps. Headings that cannot be represented
+/// as reminders are skipped and reported as warnings.
+pub fn import_org(source: &str) -> Converted<Vec<Reminder>> {
+    let mut converted = Converted::new(Vec::new());
+    let headings = OrgHeading::parse_all(source)
+        .into_iter()
+        .filter(|heading| heading.keyword.is_some());
+    for heading in headings {
+        let mut warn = |message: String| {
+            converted.warnings.push(format!(
+                "line {}: {:?}: {message}",
+                heading.line, heading.title
+            ));
+        };
+
+        match import_heading(&heading, &mut warn) {
+            Ok(reminder) => converted.value.push(reminder),
+            Err(error) => warn(format!("skipped: {error}")),
+        }
+    }
+
+    converted
+}
+
+/// Converts a to-do heading into a reminder.
+fn import_heading(heading: &OrgHeading, warn: &mut impl FnMut(String)) -> Result<Reminder, String> {
+    if heading.title.is_empty() {
+        return Err("it has no title".to_string());
+    }
+
+    let (name, text) = match (&heading.scheduled, &heading.deadline) {
+        (Some(scheduled), Some(_)) => {
+            warn("its DEADLINE is ignored because it is also SCHEDULED".to_string());
+            ("SCHEDULED", scheduled)
+        }
+        (Some(scheduled), None) => ("SCHEDULED", scheduled),
+        (None, Some(deadline)) => ("DEADLINE", deadline),
+        (None, None) => return Err("it is not SCHEDULED and has no DEADLINE".to_string()),
+    };
+    let timestamp =
+        OrgTimestamp::parse(text).ok_or_else(|| format!("invalid {name} timestamp {text:?}"))?;
+    if timestamp.is_range {
+        warn(format!(
+            "its {name} range is ignored, only its start is kept"
+        ));
+    }
+    if timestamp.has_delay {
+        warn(format!("the warning period of its {name} is ignored"));
+    }
+
+    let frequency = match timestamp.repeater {
+        Some(repeater) => frequency_from_repeater(&timestamp, repeater, warn),
+        None => ReminderFrequency::Once(ReminderFrequencyOnce {
+            date: timestamp.date,
+            time: timestamp.time,
+        }),
+    };
+
+    Ok(Reminder {
+        id: Default::default(),
+        frequency,
+        message: heading.title.clone(),
+        completed: heading.done,
+        snoozed_until: None,
+        actions: Vec::new(),
+        contact: None,
+    })
+}
+
+/// Converts a repeating timestamp into a frequency. Intervals that reminders
+/// cannot repeat on are kept as a single occurrence.
+fn frequency_from_repeater(
+    timestamp: &OrgTimestamp,
+    repeater: OrgRepeater,
+    warn: &mut impl FnMut(String),
+) -> ReminderFrequency {
+    let time = timestamp.time;
+    let date = timestamp.date;
+    let frequency = match (repeater.unit, repeater.amount) {
+        (OrgUnit::Day, 1) => Some(ReminderFrequency::Daily(ReminderFrequencyDaily { time })),
+        (OrgUnit::Week, 1) | (OrgUnit::Day, 7) => {
+            Some(ReminderFrequency::Weekly(ReminderFrequencyWeekly {
+                days: ReminderDaysOfWeek::from(date.weekday()),
+                time,
+            }))
+        }
+        (OrgUnit::Month, 1) => Some(ReminderFrequency::Monthly(ReminderFrequencyMonthly {
+            dates: vec![date.day() as u8],
+            time,
+        })),
+        (OrgUnit::Year, 1) | (OrgUnit::Month, 12) => {
+            ReminderMonth::from_number(date.month()).map(|month| {
+                ReminderFrequency::Yearly(ReminderFrequencyYearly {
+                    dates: vec![ReminderYearlyDate {
+                        month,
+                        date: date.day() as u8,
+                    }],
+                    time,
+                })
+            })
+        }
+        _ => None,
+    };
+
+    match frequency {
+        Some(frequency) => {
+            if repeater.kind == OrgRepeaterKind::Restart {
+                warn(format!(
+                    "its repeater {repeater} restarts when done, but it is imported as a fixed \
+                     schedule"
+                ));
+            }
+            frequency
+        }
+        None => {
+            warn(format!(
+                "its repeater {repeater} is not supported, so it only occurs once"
+            ));
+            ReminderFrequency::Once(ReminderFrequencyOnce { date, time })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::reminders::ReminderFrequency;
+
+    use super::import_org;
+
+    /// An agenda file like the ones kept in Emacs.
+    const AGENDA: &str = "\
+#+TITLE: Agenda
+* Home
+** TODO Water plants :home:
+   SCHEDULED: <2024-03-04 Mon +1w>
+** TODO [#A] Stretch
+   SCHEDULED: <2024-03-04 Mon 09:00 .+1d>
+** DONE Pay rent :home:money:
+   DEADLINE: <2024-03-01 Fri 09:00-09:30 ++1m>
+** TODO Dentist
+   SCHEDULED: <2024-03-05 Tue 14:00>
+** TODO Birthday
+   DEADLINE: <2024-03-01 Fri +1y -3d>
+** TODO Review
+   SCHEDULED: <2024-03-04 Mon> DEADLINE: <2024-03-08 Fri>
+** TODO Fortnightly sync
+   SCHEDULED: <2024-03-04 Mon 10:00 +2w>
+** TODO Trip
+   SCHEDULED: <2024-03-04 Mon>--<2024-03-06 Wed>
+** TODO Someday
+** Notes
+   SCHEDULED: <2024-03-04 Mon>
+** TODO Broken
+   SCHEDULED: <2024-13-04 Mon>
+** TODO Typo
+   SCHEDULED: <2024-03-04 Mon +1é>
+";
+
+    #[test]
+    fn imports_agenda() {
+        let imported = import_org(AGENDA);
+        let reminders: Vec<_> = imported
+            .value
+            .iter()
+            .map(|reminder| {
+                (
+                    reminder.message.as_str(),
+                    reminder.frequency.clone(),
+                    reminder.completed,
+                )
+            })
+            .collect();
+        let schedule = |schedule: &str| schedule.parse::<ReminderFrequency>().unwrap();
+        assert_eq!(
+            reminders,
+            [
+                ("Water plants", schedule("weekly mon @ all-day"), false),
+                ("Stretch", schedule("daily @ 09:00"), false),
+                ("Pay rent", schedule("monthly 1 @ 09:00-09:30"), true),
+                ("Dentist", schedule("once 2024-03-05 @ 14:00"), false),
+                ("Birthday", schedule("yearly 03-01 @ all-day"), false),
+                ("Review", schedule("once 2024-03-04 @ all-day"), false),
+                (
+                    "Fortnightly sync",
+                    schedule("once 2024-03-04 @ 10:00"),
+                    false
+                ),
+                ("Trip", schedule("once 2024-03-04 @ all-day"), false),
+            ]
+        );
+
+        assert_eq!(
+            imported.warnings,
+            [
+                "line 5: \"Stretch\": its repeater .+1d restarts when done, but it is imported \
+                 as a fixed schedule",
+                "line 11: \"Birthday\": the warning period of its DEADLINE is ignored",
+                "line 13: \"Review\": its DEADLINE is ignored because it is also SCHEDULED",
+                "line 15: \"Fortnightly sync\": its repeater +2w is not supported, so it only \
+                 occurs once",
+                "line 17: \"Trip\": its SCHEDULED range is ignored, only its start is kept",
+                "line 19: \"Someday\": skipped: it is not SCHEDULED and has no DEADLINE",
+                "line 22: \"Broken\": skipped: invalid SCHEDULED timestamp \"<2024-13-04 Mon>\"",
+                "line 24: \"Typo\": skipped: invalid SCHEDULED timestamp \"<2024-03-04 Mon +1é>\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn uses_keywords_set_in_the_file() {
+        let source = "\
+#+TODO: NEXT WAITING | DONE CANCELLED
+* NEXT Call plumber
+  SCHEDULED: <2024-03-04 Mon 08:30>
+* CANCELLED Book flights
+  SCHEDULED: <2024-03-05 Tue>
+* TODO Not a keyword here
+  SCHEDULED: <2024-03-06 Wed>
+";
+        let imported = import_org(source);
+        let reminders: Vec<_> = imported
+            .value
+            .iter()
+            .map(|reminder| (reminder.message.as_str(), reminder.completed))
+            .collect();
+        assert_eq!(reminders, [("Call plumber", false), ("Book flights", true)]);
+        assert_eq!(imported.warnings, Vec::<String>::new());
+    }
+}
@@ -0,0 +1,334 @@
+use std::fmt::{Display, Formatter};
+
+use chrono::{NaiveDate, NaiveTime, Timelike};
+
+use crate::models::reminders::ReminderTimeOfDay;
+
+/// The keywords used when a file does not set its own with `#+TODO:`.
+const DEFAULT_KEYWORDS: (&[&str], &[&str]) = (&["TODO"], &["DONE"]);
+
+/// A heading of an Org file, like `** TODO [#A] Pay rent :home:`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct OrgHeading {
+    /// The line the heading is on.
+    pub line: usize,
+    /// The to-do keyword of the heading, if it has one.
+    pub keyword: Option<String>,
+    /// Whether the keyword means the heading is done, like `DONE`.
+    pub done: bool,
+    /// The title of the heading, without its keyword, priority or tags.
+    pub title: String,
+    /// The `SCHEDULED:` timestamp, if any.
+    pub scheduled: Option<String>,
+    /// The `DEADLINE:` timestamp, if any.
+    pub deadline: Option<String>,
+}
+
+impl OrgHeading {
+    /// Reads the headings of an Org file, along with the planning line right
+    /// after each one. Keywords set with `#+TODO:` lines are recognized.
+    pub fn parse_all(source: &str) -> Vec<Self> {
+        let (mut active, mut done) = (Vec::new(), Vec::new());
+        for line in source.lines() {
+            let Some((name, value)) = line.trim().split_once(':') else {
+                continue;
+            };
+            let name = name.to_ascii_uppercase();
+            if !matches!(name.as_str(), "#+TODO" | "#+SEQ_TODO" | "#+TYP_TODO") {
+                continue;
+            }
+
+            // Keywords can have fast access keys, like `TODO(t)`
+            let words = value
+                .split_whitespace()
+                .map(|word| word.split('(').next().unwrap_or(word).to_string());
+            let words: Vec<_> = words.collect();
+            match words.iter().position(|word| word == "|") {
+                Some(split) => {
+                    active.extend_from_slice(&words[..split]);
+                    done.extend_from_slice(&words[split + 1..]);
+                }
+                None => {
+                    if let Some((last, rest)) = words.split_last() {
+                        active.extend_from_slice(rest);
+                        done.push(last.clone());
+                    }
+                }
+            }
+        }
+        if active.is_empty() && done.is_empty() {
+            active = DEFAULT_KEYWORDS.0.iter().map(ToString::to_string).collect();
+            done = DEFAULT_KEYWORDS.1.iter().map(ToString::to_string).collect();
+        }
+
+        let lines: Vec<_> = source.lines().collect();
+        let mut headings = Vec::new();
+        for (index, line) in lines.iter().enumerate() {
+            let stars = line.bytes().take_while(|&b| b == b'*').count();
+            let Some(rest) = line[stars..].strip_prefix(' ') else {
+                continue;
+            };
+            if stars == 0 {
+                continue;
+            }
+
+            let mut rest = rest.trim();
+            let mut keyword = None;
+            let mut is_done = false;
+            if let Some(word) = rest.split_whitespace().next() {
+                let is_active = active.iter().any(|keyword| keyword == word);
+                is_done = done.iter().any(|keyword| keyword == word);
+                if is_active || is_done {
+                    keyword = Some(word.to_string());
+                    rest = rest[word.len()..].trim_start();
+                }
+            }
+
+            // Priority cookie, like `[#A]`
+            if rest.starts_with("[#") {
+                if let Some(end) = rest.find(']') {
+                    rest = rest[end + 1..].trim_start();
+                }
+            }
+
+            let planning = lines.get(index + 1).copied().unwrap_or("");
+            headings.push(OrgHeading {
+                line: index + 1,
+                keyword,
+                done: is_done,
+                title: strip_tags(rest).to_string(),
+                scheduled: planning_timestamp(planning, "SCHEDULED:"),
+                deadline: planning_timestamp(planning, "DEADLINE:"),
+            });
+        }
+
+        headings
+    }
+}
+
+/// Removes the tags from the end of a heading's title, like `:home:work:`.
+fn strip_tags(title: &str) -> &str {
+    let title = title.trim_end();
+    let Some(start) = title.rfind(char::is_whitespace) else {
+        return title;
+    };
+    let tags = &title[start + 1..];
+    let is_tags = tags.len() > 2
+        && tags.starts_with(':')
+        && tags.ends_with(':')
+        && tags[1..tags.len() - 1].split(':').all(|tag| {
+            !tag.is_empty()
+                && tag
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || matches!(c, '_' | '@' | '#' | '%'))
+        });
+    match is_tags {
+        true => title[..start].trim_end(),
+        false => title,
+    }
+}
+
+/// Finds the timestamp after a keyword like `SCHEDULED:` in a planning line,
+/// including the `<` and `>` around it.
+fn planning_timestamp(line: &str, keyword: &str) -> Option<String> {
+    let start = line.find(keyword)? + keyword.len();
+    let rest = line[start..].trim_start();
+    let close = match rest.chars().next()? {
+        '<' => '>',
+        '[' => ']',
+        _ => return None,
+    };
+    let end = rest.find(close)?;
+    let mut timestamp = rest[..=end].to_string();
+
+    // Ranges like `<...>--<...>` are kept whole so they can be reported
+    if let Some(range) = rest[end + 1..].strip_prefix("--") {
+        if let Some(range_end) = range.find(close) {
+            timestamp.push_str("--");
+            timestamp.push_str(&range[..=range_end]);
+        }
+    }
+
+    Some(timestamp)
+}
+
+/// How an Org repeater moves a task's date when it is done.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum OrgRepeaterKind {
+    /// `+`, which moves the date by the interval once.
+    Cumulate,
+    /// `++`, which moves the date by the interval until it is in the future.
+    CatchUp,
+    /// `.+`, which moves the date to the interval after it was done.
+    Restart,
+}
+
+/// The unit of an Org repeater's interval.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum OrgUnit {
+    /// `h`.
+    Hour,
+    /// `d`.
+    Day,
+    /// `w`.
+    Week,
+    /// `m`.
+    Month,
+    /// `y`.
+    Year,
+}
+
+/// A repeater of an Org timestamp, like `+1w`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct OrgRepeater {
+    /// How the date moves.
+    pub kind: OrgRepeaterKind,
+    /// How many units the date moves by.
+    pub amount: u32,
+    /// The unit the date moves by.
+    pub unit: OrgUnit,
+}
+
+impl OrgRepeater {
+    /// Parses a repeater like `+1w`, `++1m` or `.+1d`.
+    fn parse(text: &str) -> Option<Self> {
+        let (kind, rest) = if let Some(rest) = text.strip_prefix("++") {
+            (OrgRepeaterKind::CatchUp, rest)
+        } else if let Some(rest) = text.strip_prefix(".+") {
+            (OrgRepeaterKind::Restart, rest)
+        } else {
+            (OrgRepeaterKind::Cumulate, text.strip_prefix('+')?)
+        };
+        let (amount, unit) = rest.split_at(rest.char_indices().last()?.0);
+        let unit = match unit {
+            "h" => OrgUnit::Hour,
+            "d" => OrgUnit::Day,
+            "w" => OrgUnit::Week,
+            "m" => OrgUnit::Month,
+            "y" => OrgUnit::Year,
+            _ => return None,
+        };
+
+        Some(OrgRepeater {
+            kind,
+            amount: amount.parse().ok().filter(|&amount| amount > 0)?,
+            unit,
+        })
+    }
+}
+
+impl Display for OrgRepeater {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let kind = match self.kind {
+            OrgRepeaterKind::Cumulate => "+",
+            OrgRepeaterKind::CatchUp => "++",
+            OrgRepeaterKind::Restart => ".+",
+        };
+        let unit = match self.unit {
+            OrgUnit::Hour => 'h',
+            OrgUnit::Day => 'd',
+            OrgUnit::Week => 'w',
+            OrgUnit::Month => 'm',
+            OrgUnit::Year => 'y',
+        };
+        write!(f, "{kind}{}{unit}", self.amount)
+    }
+}
+
+/// An Org timestamp, like `<2024-03-01 Fri 09:00-10:30 +1w>`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct OrgTimestamp {
+    /// Whether the timestamp is active, written with `<` and `>`.
+    pub active: bool,
+    /// The date of the timestamp.
+    pub date: NaiveDate,
+    /// The time or range of time of the timestamp.
+    pub time: ReminderTimeOfDay,
+    /// The repeater of the timestamp, if any.
+    pub repeater: Option<OrgRepeater>,
+    /// Whether the timestamp has a warning period, like `-2d`.
+    pub has_delay: bool,
+    /// Whether the timestamp was the start of a date range, like
+    /// `<...>--<...>`.
+    pub is_range: bool,
+}
+
+impl OrgTimestamp {
+    /// Parses a timestamp, including the `<` and `>` or `[` and `]` around
+    /// it.
+    pub fn parse(text: &str) -> Option<Self> {
+        let (text, is_range) = match text.split_once("--") {
+            Some((start, _)) => (start, true),
+            None => (text, false),
+        };
+        let (active, inner) = if let Some(inner) = text.strip_prefix('<') {
+            (true, inner.strip_suffix('>')?)
+        } else {
+            (false, text.strip_prefix('[')?.strip_suffix(']')?)
+        };
+
+        let mut words = inner.split_whitespace();
+        let date = NaiveDate::parse_from_str(words.next()?, "%Y-%m-%d").ok()?;
+        let mut timestamp = OrgTimestamp {
+            active,
+            date,
+            time: ReminderTimeOfDay::AllDay,
+            repeater: None,
+            has_delay: false,
+            is_range,
+        };
+        for word in words {
+            if word.starts_with(|c: char| c.is_alphabetic()) {
+                // The name of the day is not needed
+            } else if word.starts_with(|c: char| c.is_ascii_digit()) {
+                timestamp.time = match word.split_once('-') {
+                    Some((start, end)) => ReminderTimeOfDay::TimeRange {
+                        start: parse_time(start)?,
+                        end: parse_time(end)?,
+                    },
+                    None => ReminderTimeOfDay::Time {
+                        time: parse_time(word)?,
+                    },
+                };
+            } else if word.starts_with('-') {
+                timestamp.has_delay = true;
+            } else {
+                timestamp.repeater = Some(OrgRepeater::parse(word)?);
+            }
+        }
+
+        Some(timestamp)
+    }
+}
+
+impl Display for OrgTimestamp {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let (open, close) = match self.active {
+            true => ('<', '>'),
+            false => ('[', ']'),
+        };
+        write!(f, "{open}{}", self.date.format("%Y-%m-%d %a"))?;
+        match self.time {
+            ReminderTimeOfDay::AllDay => {}
+            ReminderTimeOfDay::Time { time } => write!(f, " {}", format_time(time))?,
+            ReminderTimeOfDay::TimeRange { start, end } => {
+                write!(f, " {}-{}", format_time(start), format_time(end))?
+            }
+        }
+        if let Some(repeater) = self.repeater {
+            write!(f, " {repeater}")?;
+        }
+        write!(f, "{close}")
+    }
+}
+
+/// Parses a time like `9:00` or `09:00`.
+fn parse_time(text: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(text, "%H:%M").ok()
+}
+
+/// Formats a time like `09:00`. Org times have no seconds.
+fn format_time(time: NaiveTime) -> String {
+    let time = time.with_second(0).unwrap_or(time);
+    time.format("%H:%M").to_string()
+}
@@ -0,0 +1,1229 @@
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Weekday};
+use thiserror::Error;
+
+use crate::models::reminders::{
+    Reminder, ReminderDaysOfWeek, ReminderFrequency, ReminderFrequencyDaily,
+    ReminderFrequencyMonthly, ReminderFrequencyOnce, ReminderFrequencyWeekly,
+    ReminderFrequencyYearly, ReminderMonth, ReminderTimeOfDay, ReminderYearlyDate,
+};
+
+/// Words that are dropped when they come right before a date or time, like
+/// the "on the" in "on the 1st".
+const CONNECTORS: &[&str] = &["on", "at", "the", "by", "from", "of"];
+
+/// The time used for "tonight" when no other time is given.
+const TONIGHT_HOUR: u32 = 20;
+
+/// An error understanding the text typed into quick add.
+#[derive(Clone, PartialEq, Eq, Debug, Error)]
+pub enum QuickAddError {
+    /// Nothing was typed.
+    #[error("type a reminder, like \"call mom next tuesday 6pm\"")]
+    Empty,
+    /// Only a date or time was typed.
+    #[error("the reminder needs a title")]
+    MissingTitle,
+    /// No date, time or repetition was found.
+    #[error("add when it happens, like \"tomorrow at 9am\" or \"every monday\"")]
+    MissingSchedule,
+    /// The text gives two dates or times, or a repetition that does not fit
+    /// the dates it gives.
+    #[error("{0}")]
+    Conflict(String),
+    /// A date that does not exist was given.
+    #[error("{0} is not a date")]
+    InvalidDate(String),
+}
+
+/// A reminder typed as free text, like "pay rent on the 1st every month at
+/// 9am" or "call mom next tuesday 6pm".
+#[derive(Clone, PartialEq, Debug)]
+pub struct QuickAdd {
+    /// What is left of the text once the dates and times are taken out.
+    pub title: String,
+    /// When the reminder occurs.
+    pub frequency: ReminderFrequency,
+}
+
+impl QuickAdd {
+    /// Understands the text typed into quick add. Dates are relative to
+    /// `now`, and reminders without a time of day last all day.
+    ///
+    /// Recognized phrases include:
+    ///
+    /// - times like "9am", "6:30 pm", "18:00", "at 9", "noon" and "midnight",
+    ///   ranges like "9am-5pm" or "from 12:30 to 16:30", and "all day"
+    /// - dates like "today", "tonight", "tomorrow", "friday", "next tuesday",
+    ///   "in 3 days", "in 2 hours", "march 1", "the 15th" and "2024-03-01"
+    /// - repetition like "daily", "every day", "every monday and wednesday",
+    ///   "on weekdays", "every month on the 1st" and "every year on march 1"
+    pub fn parse(text: &str, now: NaiveDateTime) -> Result<Self, QuickAddError> {
+        let mut parser = Parser::new(text);
+        if parser.words.is_empty() {
+            return Err(QuickAddError::Empty);
+        }
+
+        parser.strip_prefix(&["remind", "me", "to"]);
+        parser.strip_prefix(&["remind", "me"]);
+        let mut found = Found::default();
+        let mut index = 0;
+        while index < parser.words.len() {
+            match parser.match_at(index, now, &mut found)? {
+                Some(len) => {
+                    parser.consume(index, len);
+                    index += len;
+                }
+                None => index += 1,
+            }
+        }
+
+        let title = parser.title();
+        let frequency = found.into_frequency(now)?;
+        if title.is_empty() {
+            return Err(QuickAddError::MissingTitle);
+        }
+
+        Ok(QuickAdd { title, frequency })
+    }
+
+    /// Creates a reminder from what was typed.
+    #[must_use]
+    pub fn into_reminder(self) -> Reminder {
+        Reminder {
+            id: Default::default(),
+            frequency: self.frequency,
+            message: self.title,
+            completed: false,
+            snoozed_until: None,
+            actions: Vec::new(),
+            contact: None,
+        }
+    }
+}
+
+/// How often the text says the reminder repeats.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Repeat {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl Repeat {
+    /// Gets the name used in error messages.
+    fn name(self) -> &'static str {
+        match self {
+            Repeat::Daily => "daily",
+            Repeat::Weekly => "weekly",
+            Repeat::Monthly => "monthly",
+            Repeat::Yearly => "yearly",
+        }
+    }
+}
+
+/// The dates and times found in the text so far.
+#[derive(Default)]
+struct Found {
+    repeat: Option<Repeat>,
+    days: ReminderDaysOfWeek,
+    /// Whether a day of the week had to come after today, like "next
+    /// tuesday".
+    days_after_today: bool,
+    days_of_month: Vec<u8>,
+    yearly_dates: Vec<ReminderYearlyDate>,
+    date: Option<NaiveDate>,
+    time: Option<ReminderTimeOfDay>,
+    /// Whether the time is a bare hour like "at 3", which is read as the
+    /// morning until it is worked out.
+    bare_hour: bool,
+    /// Whether "tonight" was used, which sets the time if nothing else does.
+    tonight: bool,
+}
+
+impl Found {
+    fn set_repeat(&mut self, repeat: Repeat) -> Result<(), QuickAddError> {
+        match self.repeat {
+            Some(existing) if existing != repeat => Err(QuickAddError::Conflict(format!(
+                "it cannot repeat both {} and {}",
+                existing.name(),
+                repeat.name()
+            ))),
+            _ => {
+                self.repeat = Some(repeat);
+                Ok(())
+            }
+        }
+    }
+
+    fn set_date(&mut self, date: NaiveDate) -> Result<(), QuickAddError> {
+        match self.date {
+            Some(existing) if existing != date => Err(QuickAddError::Conflict(format!(
+                "it cannot be on both {existing} and {date}"
+            ))),
+            _ => {
+                self.date = Some(date);
+                Ok(())
+            }
+        }
+    }
+
+    fn set_time(&mut self, time: ReminderTimeOfDay) -> Result<(), QuickAddError> {
+        match self.time {
+            Some(existing) if existing != time => Err(QuickAddError::Conflict(
+                "it has more than one time of day".to_string(),
+            )),
+            _ => {
+                self.time = Some(time);
+                Ok(())
+            }
+        }
+    }
+
+    /// Works out the frequency from what was found.
+    fn into_frequency(self, now: NaiveDateTime) -> Result<ReminderFrequency, QuickAddError> {
+        let today = now.date();
+        let time = match (self.time, self.tonight) {
+            (Some(ReminderTimeOfDay::Time { time }), _) if self.bare_hour => {
+                ReminderTimeOfDay::Time {
+                    time: self.resolve_hour(time, now),
+                }
+            }
+            (Some(time), _) => time,
+            (None, true) => ReminderTimeOfDay::Time {
+                time: NaiveTime::from_hms_opt(TONIGHT_HOUR, 0, 0).unwrap_or_default(),
+            },
+            (None, false) => ReminderTimeOfDay::AllDay,
+        };
+        let conflict = |repeat: Repeat, with: &str| {
+            Err(QuickAddError::Conflict(format!(
+                "a {} reminder cannot also be on {with}",
+                repeat.name()
+            )))
+        };
+        let has_days = !self.days.is_empty();
+        let has_days_of_month = !self.days_of_month.is_empty();
+        let has_yearly_dates = !self.yearly_dates.is_empty();
+
+        let frequency = match self.repeat {
+            Some(Repeat::Daily) => {
+                if has_days || has_days_of_month || has_yearly_dates || self.date.is_some() {
+                    return conflict(Repeat::Daily, "particular days");
+                }
+                ReminderFrequency::Daily(ReminderFrequencyDaily { time })
+            }
+            Some(Repeat::Weekly) => {
+                if has_days_of_month || has_yearly_dates {
+                    return conflict(Repeat::Weekly, "days of the month");
+                }
+                let days = match (has_days, self.date) {
+                    (true, _) => self.days,
+                    (false, Some(date)) => date.weekday().into(),
+                    (false, None) => today.weekday().into(),
+                };
+                ReminderFrequency::Weekly(ReminderFrequencyWeekly { days, time })
+            }
+            Some(Repeat::Monthly) => {
+                if has_days || has_yearly_dates {
+                    return conflict(Repeat::Monthly, "days of the week or year");
+                }
+                let dates = match (has_days_of_month, self.date) {
+                    (true, _) => self.days_of_month,
+                    (false, Some(date)) => vec![date.day() as u8],
+                    (false, None) => vec![today.day() as u8],
+                };
+                ReminderFrequency::Monthly(ReminderFrequencyMonthly { dates, time })
+            }
+            Some(Repeat::Yearly) => {
+                if has_days || has_days_of_month {
+                    return conflict(Repeat::Yearly, "days of the week or month");
+                }
+                let dates = match (has_yearly_dates, self.date.unwrap_or(today)) {
+                    (true, _) => self.yearly_dates,
+                    (false, date) => vec![ReminderYearlyDate {
+                        month: ReminderMonth::from_number(date.month()).unwrap_or_default(),
+                        date: date.day() as u8,
+                    }],
+                };
+                ReminderFrequency::Yearly(ReminderFrequencyYearly { dates, time })
+            }
+            None => {
+                let date = self.once_date(now, time, has_days, has_days_of_month)?;
+                ReminderFrequency::Once(ReminderFrequencyOnce { date, time })
+            }
+        };
+
+        Ok(frequency)
+    }
+
+    /// Works out whether a bare hour like "at 3" is in the morning or the
+    /// afternoon. Today it is the next time that hour comes around, and on
+    /// other days it is during the day, so "at 3" is 15:00 and "at 9" is
+    /// 09:00.
+    fn resolve_hour(&self, time: NaiveTime, now: NaiveDateTime) -> NaiveTime {
+        let hour = time.hour() % 12;
+        let morning = time.with_hour(hour).unwrap_or(time);
+        let afternoon = time.with_hour(hour + 12).unwrap_or(time);
+        let no_date = self.date.is_none()
+            && self.days.is_empty()
+            && self.days_of_month.is_empty()
+            && self.yearly_dates.is_empty();
+
+        if self.tonight {
+            afternoon
+        } else if self.repeat.is_none() && (no_date || self.date == Some(now.date())) {
+            [morning, afternoon]
+                .into_iter()
+                .find(|&time| time > now.time())
+                .unwrap_or(morning)
+        } else if hour < 7 {
+            afternoon
+        } else {
+            morning
+        }
+    }
+
+    /// Works out the date of a reminder that does not repeat.
+    fn once_date(
+        &self,
+        now: NaiveDateTime,
+        time: ReminderTimeOfDay,
+        has_days: bool,
+        has_days_of_month: bool,
+    ) -> Result<NaiveDate, QuickAddError> {
+        let today = now.date();
+        // Today only counts if the reminder has not already happened
+        let upcoming = |date: &NaiveDate| {
+            *date > today || time == ReminderTimeOfDay::AllDay || time.start() > now.time()
+        };
+        let several = |what: &str| {
+            Err(QuickAddError::Conflict(format!(
+                "a reminder that does not repeat can only be on one {what}; add \"every\" to repeat it"
+            )))
+        };
+
+        let candidates = [
+            self.date.is_some(),
+            has_days,
+            has_days_of_month,
+            !self.yearly_dates.is_empty(),
+        ];
+        if candidates.into_iter().filter(|&found| found).count() > 1 {
+            return Err(QuickAddError::Conflict(
+                "it has more than one date".to_string(),
+            ));
+        }
+
+        if let Some(date) = self.date {
+            return Ok(date);
+        }
+        if has_days {
+            if self.days.bits().count_ones() > 1 {
+                return several("day of the week");
+            }
+            let start = if self.days_after_today {
+                today.succ_opt()
+            } else {
+                Some(today)
+            };
+            return Ok(start
+                .into_iter()
+                .flat_map(|start| start.iter_days().take(8))
+                .filter(upcoming)
+                .find(|date| self.days.contains(date.weekday().into()))
+                .unwrap_or(today));
+        }
+        if has_days_of_month {
+            let [day] = self.days_of_month[..] else {
+                return several("day of the month");
+            };
+            return today
+                .iter_days()
+                .take(366)
+                .filter(upcoming)
+                .find(|date| date.day() == u32::from(day))
+                .ok_or_else(|| QuickAddError::InvalidDate(format!("the {}", ordinal(day))));
+        }
+        if let [ref date] = self.yearly_dates[..] {
+            let name = format!("{} {}", date.month, date.date);
+            let in_year =
+                |year| NaiveDate::from_ymd_opt(year, date.month.number(), u32::from(date.date));
+            return (today.year()..today.year() + 8)
+                .filter_map(in_year)
+                .find(|date| *date >= today && upcoming(date))
+                .ok_or(QuickAddError::InvalidDate(name));
+        }
+        if !self.yearly_dates.is_empty() {
+            return several("date");
+        }
+
+        // Only a time was given, so the next time it comes around is used
+        match time {
+            ReminderTimeOfDay::AllDay => Err(QuickAddError::MissingSchedule),
+            time if time.start() > now.time() => Ok(today),
+            _ => Ok(today.succ_opt().unwrap_or(today)),
+        }
+    }
+}
+
+/// A word of the typed text.
+struct Word<'a> {
+    /// The word as it was typed.
+    text: &'a str,
+    /// The word in lowercase, without punctuation around it.
+    key: String,
+    /// Whether the word has been understood as part of a date or time.
+    used: bool,
+}
+
+/// Finds dates and times among the words of the typed text.
+struct Parser<'a> {
+    words: Vec<Word<'a>>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(text: &'a str) -> Self {
+        let words = text
+            .split_whitespace()
+            .map(|text| Word {
+                text,
+                key: text
+                    .trim_matches(|c: char| matches!(c, ',' | '.' | '!' | '?' | ';' | '(' | ')'))
+                    .to_lowercase(),
+                used: false,
+            })
+            .collect();
+        Self { words }
+    }
+
+    /// Gets the key of a word, or an empty string past the end.
+    fn key(&self, index: usize) -> &str {
+        self.words.get(index).map_or("", |word| word.key.as_str())
+    }
+
+    /// Checks whether a word ends with a comma, which separates items of a
+    /// list.
+    fn ends_list_item(&self, index: usize) -> bool {
+        self.words
+            .get(index)
+            .is_some_and(|word| word.text.ends_with(','))
+    }
+
+    /// Drops words from the start of the text, like "remind me to".
+    fn strip_prefix(&mut self, prefix: &[&str]) {
+        let matches = prefix
+            .iter()
+            .enumerate()
+            .all(|(index, &key)| self.words.get(index).is_some_and(|word| word.key == key));
+        if matches {
+            self.words.drain(..prefix.len());
+        }
+    }
+
+    /// Marks words as understood, along with connecting words like "on the"
+    /// right before them.
+    fn consume(&mut self, index: usize, len: usize) {
+        for word in &mut self.words[index..index + len] {
+            word.used = true;
+        }
+        for word in self.words[..index].iter_mut().rev() {
+            if word.used || !CONNECTORS.contains(&word.key.as_str()) {
+                break;
+            }
+            word.used = true;
+        }
+    }
+
+    /// Gets the words that were not understood as dates or times.
+    fn title(&self) -> String {
+        let words: Vec<_> = self
+            .words
+            .iter()
+            .filter(|word| !word.used)
+            .map(|word| word.text)
+            .collect();
+        words
+            .join(" ")
+            .trim_matches(|c: char| matches!(c, ',' | ';' | ':' | '-') || c.is_whitespace())
+            .to_string()
+    }
+
+    /// Tries to understand the words starting at `index`, returning how many
+    /// words were understood.
+    fn match_at(
+        &self,
+        index: usize,
+        now: NaiveDateTime,
+        found: &mut Found,
+    ) -> Result<Option<usize>, QuickAddError> {
+        let today = now.date();
+        let key = self.key(index);
+
+        // Repetition
+        match key {
+            "daily" => return found.set_repeat(Repeat::Daily).map(|()| Some(1)),
+            "weekly" => return found.set_repeat(Repeat::Weekly).map(|()| Some(1)),
+            "monthly" => return found.set_repeat(Repeat::Monthly).map(|()| Some(1)),
+            "yearly" | "annually" => return found.set_repeat(Repeat::Yearly).map(|()| Some(1)),
+            "weekdays" | "weekends" => {
+                found.set_repeat(Repeat::Weekly)?;
+                found.days |= group(key);
+                return Ok(Some(1));
+            }
+            "every" | "each" => {
+                let next = self.key(index + 1);
+                let repeat = match next {
+                    "day" => Some(Repeat::Daily),
+                    "week" => Some(Repeat::Weekly),
+                    "month" => Some(Repeat::Monthly),
+                    "year" => Some(Repeat::Yearly),
+                    _ => None,
+                };
+                if let Some(repeat) = repeat {
+                    found.set_repeat(repeat)?;
+                    return Ok(Some(2));
+                }
+                if matches!(next, "weekday" | "weekdays" | "weekend" | "weekends") {
+                    found.set_repeat(Repeat::Weekly)?;
+                    found.days |= group(next);
+                    return Ok(Some(2));
+                }
+                if let Some((days, len)) = self.weekdays(index + 1) {
+                    found.set_repeat(Repeat::Weekly)?;
+                    found.days |= days;
+                    return Ok(Some(1 + len));
+                }
+                if let Some((dates, len)) = self.month_dates(index + 1) {
+                    found.set_repeat(Repeat::Yearly)?;
+                    found.yearly_dates.extend(yearly_dates(dates)?);
+                    return Ok(Some(1 + len));
+                }
+                if let Some((days, len)) = self.days_of_month(index + 1) {
+                    found.set_repeat(Repeat::Monthly)?;
+                    found.days_of_month.extend(days);
+                    return Ok(Some(1 + len));
+                }
+                return Ok(None);
+            }
+            _ => {}
+        }
+
+        // Relative dates
+        match key {
+            "today" => return found.set_date(today).map(|()| Some(1)),
+            "tonight" => {
+                found.tonight = true;
+                return found.set_date(today).map(|()| Some(1));
+            }
+            "tomorrow" => {
+                let tomorrow = today.succ_opt().unwrap_or(today);
+                return found.set_date(tomorrow).map(|()| Some(1));
+            }
+            "next" if self.key(index + 1) == "week" => {
+                return found.set_date(today + Duration::days(7)).map(|()| Some(2));
+            }
+            "next" => {
+                if let Some((days, len)) = self.weekdays(index + 1) {
+                    found.days |= days;
+                    found.days_after_today = true;
+                    return Ok(Some(1 + len));
+                }
+                return Ok(None);
+            }
+            "in" => return self.relative(index, now, found),
+            _ => {}
+        }
+
+        // Days of the week, which repeat every week if they are plural like
+        // "mondays"
+        if let Some((days, len)) = self.weekdays(index) {
+            if self.words[index..index + len]
+                .iter()
+                .any(|word| weekday(&word.key).is_some_and(|(_, plural)| plural))
+            {
+                found.set_repeat(Repeat::Weekly)?;
+            }
+            found.days |= days;
+            return Ok(Some(len));
+        }
+
+        // Dates
+        if let Ok(date) = NaiveDate::parse_from_str(key, "%Y-%m-%d") {
+            return found.set_date(date).map(|()| Some(1));
+        }
+        if let Some((dates, len)) = self.month_dates(index) {
+            found.yearly_dates.extend(yearly_dates(dates)?);
+            return Ok(Some(len));
+        }
+        if let Some((days, len)) = self.days_of_month(index) {
+            found.days_of_month.extend(days);
+            return Ok(Some(len));
+        }
+        if key == "the" {
+            if let Some((days, len)) = self.list(index + 1, day_number) {
+                found.days_of_month.extend(days);
+                return Ok(Some(1 + len));
+            }
+        }
+
+        // Times of day
+        if key == "all" && self.key(index + 1) == "day" || key == "all-day" {
+            found.set_time(ReminderTimeOfDay::AllDay)?;
+            return Ok(Some(if key == "all" { 2 } else { 1 }));
+        }
+        if let Some((time, len, bare_hour)) = self.time_of_day(index) {
+            found.set_time(time)?;
+            found.bare_hour = bare_hour;
+            return Ok(Some(len));
+        }
+
+        Ok(None)
+    }
+
+    /// Understands "in 3 days" or "in 2 hours".
+    fn relative(
+        &self,
+        index: usize,
+        now: NaiveDateTime,
+        found: &mut Found,
+    ) -> Result<Option<usize>, QuickAddError> {
+        let count = match self.key(index + 1) {
+            "a" | "an" | "one" => 1,
+            count => match count.parse::<i64>() {
+                Ok(count) if (1..=1000).contains(&count) => count,
+                _ => return Ok(None),
+            },
+        };
+        let unit = self.key(index + 2);
+        let unit = unit.strip_suffix('s').unwrap_or(unit);
+        let at = match unit {
+            "minute" | "min" => now + Duration::minutes(count),
+            "hour" | "hr" => now + Duration::hours(count),
+            "day" => {
+                found.set_date(now.date() + Duration::days(count))?;
+                return Ok(Some(3));
+            }
+            "week" => {
+                found.set_date(now.date() + Duration::weeks(count))?;
+                return Ok(Some(3));
+            }
+            _ => return Ok(None),
+        };
+
+        found.set_date(at.date())?;
+        found.set_time(ReminderTimeOfDay::Time {
+            time: at.time().with_nanosecond(0).unwrap_or(at.time()),
+        })?;
+        Ok(Some(3))
+    }
+
+    /// Understands a list of days of the week, like "monday, wednesday and
+    /// friday".
+    fn weekdays(&self, index: usize) -> Option<(ReminderDaysOfWeek, usize)> {
+        self.list(index, |key| weekday(key).map(|(day, _)| day))
+            .map(|(days, len)| {
+                let days = days
+                    .into_iter()
+                    .fold(ReminderDaysOfWeek::empty(), |all, day| all | day);
+                (days, len)
+            })
+    }
+
+    /// Understands a list of days of the month, like "1st and 15th".
+    fn days_of_month(&self, index: usize) -> Option<(Vec<u8>, usize)> {
+        self.list(index, ordinal_day)
+    }
+
+    /// Understands a list of days of the year, like "march 1" or "1st of
+    /// march and december 25".
+    fn month_dates(&self, index: usize) -> Option<(Vec<(ReminderMonth, u8)>, usize)> {
+        let mut dates = Vec::new();
+        let mut len = 0;
+        while let Some((date, date_len)) = self.month_date(index + len) {
+            dates.push(date);
+            len += date_len;
+
+            let separated = self.ends_list_item(index + len - 1);
+            let next = if matches!(self.key(index + len), "and" | "&") {
+                1
+            } else if separated {
+                0
+            } else {
+                break;
+            };
+            if self.month_date(index + len + next).is_none() {
+                break;
+            }
+            len += next;
+        }
+
+        (!dates.is_empty()).then_some((dates, len))
+    }
+
+    /// Understands a day of the year, like "march 1", "1 march" or "1st of
+    /// march".
+    fn month_date(&self, index: usize) -> Option<((ReminderMonth, u8), usize)> {
+        if let Some(month) = month(self.key(index)) {
+            let date = day_number(self.key(index + 1))?;
+            return Some(((month, date), 2));
+        }
+
+        let date = day_number(self.key(index))?;
+        let (month, len) = match self.key(index + 1) {
+            "of" => (month(self.key(index + 2))?, 3),
+            key => (month(key)?, 2),
+        };
+        Some(((month, date), len))
+    }
+
+    /// Understands a list of items separated by commas or "and".
+    fn list<T>(&self, index: usize, item: impl Fn(&str) -> Option<T>) -> Option<(Vec<T>, usize)> {
+        let mut items = vec![item(self.key(index))?];
+        let mut len = 1;
+        loop {
+            let separated = self.ends_list_item(index + len - 1);
+            let skip = if matches!(self.key(index + len), "and" | "&") {
+                1
+            } else if separated {
+                0
+            } else {
+                break;
+            };
+            let Some(next) = item(self.key(index + len + skip)) else {
+                break;
+            };
+            items.push(next);
+            len += skip + 1;
+        }
+
+        Some((items, len))
+    }
+
+    /// Understands a time or range of time, like "9am", "6:30 pm",
+    /// "9am-5pm" or "from 12:30 to 16:30". Also returns whether the time is
+    /// a bare hour like "at 3", which could be in the morning or afternoon.
+    fn time_of_day(&self, index: usize) -> Option<(ReminderTimeOfDay, usize, bool)> {
+        let key = self.key(index);
+        let after_at = index > 0 && self.key(index - 1) == "at";
+        let from = key == "from";
+        let start_index = if from { index + 1 } else { index };
+
+        // Bare numbers are only times when they come after "at" or "from",
+        // so "buy 2-3 apples" keeps its numbers
+        let is_time = |start: &Clock, end: &Clock| from || after_at || !start.bare() || !end.bare();
+
+        // A range in one word, like "9am-5pm" or "9-11am"
+        if let Some((start, end)) = self.key(start_index).split_once('-') {
+            let (start, end) = (Clock::parse(start)?, Clock::parse(end)?);
+            if !is_time(&start, &end) {
+                return None;
+            }
+            let range = Clock::range(&start, &end)?;
+            return Some((range, start_index - index + 1, false));
+        }
+
+        let (start, start_len) = self.clock(start_index)?;
+        let separator_index = start_index + start_len;
+        let separator = self.key(separator_index);
+        if matches!(separator, "to" | "until" | "till" | "-") {
+            if let Some((end, end_len)) = self.clock(separator_index + 1) {
+                if is_time(&start, &end) {
+                    let range = Clock::range(&start, &end)?;
+                    return Some((range, separator_index + 1 + end_len - index, false));
+                }
+            }
+        }
+
+        // "from" needs a range
+        if from || start.bare() && !after_at {
+            return None;
+        }
+        Some((
+            ReminderTimeOfDay::Time {
+                time: start.time(None)?,
+            },
+            start_len,
+            start.bare() && (1..=12).contains(&start.hour),
+        ))
+    }
+
+    /// Understands a time that might be split over two words, like "6 pm".
+    fn clock(&self, index: usize) -> Option<(Clock, usize)> {
+        let mut clock = Clock::parse(self.key(index))?;
+        if clock.meridiem.is_none() {
+            if let Some(meridiem) = meridiem(self.key(index + 1)) {
+                clock.meridiem = Some(meridiem);
+                return Some((clock, 2));
+            }
+        }
+        Some((clock, 1))
+    }
+}
+
+/// Whether a time is before or after noon.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Meridiem {
+    Am,
+    Pm,
+}
+
+/// A time as typed, like "9", "9:30" or "9:30pm".
+struct Clock {
+    hour: u32,
+    minute: u32,
+    has_minutes: bool,
+    meridiem: Option<Meridiem>,
+}
+
+impl Clock {
+    fn parse(text: &str) -> Option<Self> {
+        match text {
+            "noon" => {
+                return Some(Clock {
+                    hour: 12,
+                    minute: 0,
+                    has_minutes: true,
+                    meridiem: None,
+                })
+            }
+            "midnight" => {
+                return Some(Clock {
+                    hour: 0,
+                    minute: 0,
+                    has_minutes: true,
+                    meridiem: None,
+                })
+            }
+            _ => {}
+        }
+
+        let (digits, meridiem) = match text.find(|c: char| c.is_ascii_alphabetic()) {
+            Some(split) => (&text[..split], Some(meridiem(&text[split..])?)),
+            None => (text, None),
+        };
+        let (hour, minute) = match digits.split_once(':') {
+            Some((hour, minute)) if minute.len() == 2 => (hour, Some(minute)),
+            Some(_) => return None,
+            None => (digits, None),
+        };
+        if hour.is_empty() || hour.len() > 2 || !hour.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+
+        Some(Clock {
+            hour: hour.parse().ok()?,
+            minute: minute.map_or(Some(0), |minute| minute.parse().ok())?,
+            has_minutes: minute.is_some(),
+            meridiem,
+        })
+    }
+
+    /// Checks whether the time is only a number, like "9".
+    fn bare(&self) -> bool {
+        self.meridiem.is_none() && !self.has_minutes
+    }
+
+    /// Gets the range between two times. If the start has no "am" or "pm",
+    /// it is the one that keeps it before the end, like in "9-11am" or
+    /// "11-1pm".
+    fn range(start: &Clock, end: &Clock) -> Option<ReminderTimeOfDay> {
+        let end_time = end.time(None)?;
+        let mut start_time = start.time(end.meridiem)?;
+        if start.meridiem.is_none() && start_time > end_time {
+            let other = match end.meridiem {
+                Some(Meridiem::Am) => Some(Meridiem::Pm),
+                Some(Meridiem::Pm) => Some(Meridiem::Am),
+                None => None,
+            };
+            start_time = start.time(other)?;
+        }
+
+        Some(ReminderTimeOfDay::TimeRange {
+            start: start_time,
+            end: end_time,
+        })
+    }
+
+    /// Gets the time, using `fallback` if the time has no "am" or "pm" of
+    /// its own.
+    fn time(&self, fallback: Option<Meridiem>) -> Option<NaiveTime> {
+        let hour = match self.meridiem.or(fallback) {
+            Some(_) if !(1..=12).contains(&self.hour) => return None,
+            Some(Meridiem::Am) => self.hour % 12,
+            Some(Meridiem::Pm) => self.hour % 12 + 12,
+            None => self.hour,
+        };
+        NaiveTime::from_hms_opt(hour, self.minute, 0)
+    }
+}
+
+/// Understands "am" or "pm".
+fn meridiem(text: &str) -> Option<Meridiem> {
+    match text {
+        "am" | "a.m" | "a.m." => Some(Meridiem::Am),
+        "pm" | "p.m" | "p.m." => Some(Meridiem::Pm),
+        _ => None,
+    }
+}
+
+/// Understands a day of the week, and whether it is plural like "mondays".
+/// "sat", "sun" and "wed" are left alone since they are also words.
+fn weekday(text: &str) -> Option<(ReminderDaysOfWeek, bool)> {
+    let (name, plural) = match text.strip_suffix('s') {
+        Some(name) if name.len() > 3 && name.ends_with("day") => (name, true),
+        _ => (text, false),
+    };
+    let day = match name {
+        "tues" | "tue" => Weekday::Tue,
+        "weds" => Weekday::Wed,
+        "wed" => return None,
+        "thurs" | "thur" | "thu" => Weekday::Thu,
+        "sat" | "sun" => return None,
+        name if name.len() >= 3 => name.parse().ok()?,
+        _ => return None,
+    };
+    Some((day.into(), plural))
+}
+
+/// Checks that the days of the year exist, so "february 30" is not a date.
+fn yearly_dates(dates: Vec<(ReminderMonth, u8)>) -> Result<Vec<ReminderYearlyDate>, QuickAddError> {
+    dates
+        .into_iter()
+        .map(|(month, date)| {
+            ReminderYearlyDate::new(month, date)
+                .ok_or_else(|| QuickAddError::InvalidDate(format!("{month} {date}")))
+        })
+        .collect()
+}
+
+/// Gets the days meant by "weekdays" or "weekends".
+fn group(text: &str) -> ReminderDaysOfWeek {
+    match text {
+        "weekend" | "weekends" => ReminderDaysOfWeek::WEEKENDS,
+        _ => ReminderDaysOfWeek::WEEKDAYS,
+    }
+}
+
+/// Understands a month, like "march" or "mar".
+fn month(text: &str) -> Option<ReminderMonth> {
+    if text.len() < 3 {
+        return None;
+    }
+    let text = if text == "sept" { "sep" } else { text };
+    ReminderMonth::ALL.into_iter().find(|month| {
+        let name = month.to_string().to_lowercase();
+        name == text || text.len() == 3 && name.starts_with(text)
+    })
+}
+
+/// Understands a day of the month written as an ordinal, like "1st".
+fn ordinal_day(text: &str) -> Option<u8> {
+    let digits = ["st", "nd", "rd", "th"]
+        .iter()
+        .find_map(|suffix| text.strip_suffix(suffix))?;
+    digits.parse().ok().filter(|day| (1..=31).contains(day))
+}
+
+/// Understands a day of the month, like "1" or "1st".
+fn day_number(text: &str) -> Option<u8> {
+    ordinal_day(text).or_else(|| text.parse().ok().filter(|day| (1..=31).contains(day)))
+}
+
+/// Writes a day of the month as an ordinal, like "1st".
+fn ordinal(day: u8) -> String {
+    let suffix = match (day % 10, day % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{day}{suffix}")
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, NaiveDateTime};
+
+    use crate::models::reminders::ReminderFrequency;
+
+    use super::{QuickAdd, QuickAddError};
+
+    /// A Monday morning.
+    fn now() -> NaiveDateTime {
+        at(10, 0)
+    }
+
+    fn at(hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2024, 3, 4)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    fn schedule(s: &str) -> ReminderFrequency {
+        s.parse().unwrap()
+    }
+
+    fn check(cases: &[(&str, NaiveDateTime, &str, &str)]) {
+        for &(text, now, title, expected) in cases {
+            let quick_add = QuickAdd::parse(text, now).unwrap_or_else(|error| {
+                panic!("{text}: {error}");
+            });
+            assert_eq!(quick_add.title, title, "{text}");
+            assert_eq!(quick_add.frequency, schedule(expected), "{text}");
+        }
+    }
+
+    #[test]
+    fn parses_examples() {
+        let now = now();
+        check(&[
+            (
+                "pay rent on the 1st every month at 9am",
+                now,
+                "pay rent",
+                "monthly 1 @ 09:00",
+            ),
+            (
+                "call mom next tuesday 6pm",
+                now,
+                "call mom",
+                "once 2024-03-05 @ 18:00",
+            ),
+            (
+                "remind me to stretch every day at 3pm",
+                now,
+                "stretch",
+                "daily @ 15:00",
+            ),
+            (
+                "standup on weekdays at 9:30",
+                now,
+                "standup",
+                "weekly weekdays @ 09:30",
+            ),
+            (
+                "gym every monday and wednesday 6:30 pm",
+                now,
+                "gym",
+                "weekly mon,wed @ 18:30",
+            ),
+            ("trash mondays 7pm", now, "trash", "weekly mon @ 19:00"),
+            (
+                "review on the 1st and 15th every month",
+                now,
+                "review",
+                "monthly 1,15 @ all-day",
+            ),
+            (
+                "anniversary every year on march 1",
+                now,
+                "anniversary",
+                "yearly 03-01 @ all-day",
+            ),
+            (
+                "water plants tomorrow",
+                now,
+                "water plants",
+                "once 2024-03-05 @ all-day",
+            ),
+            ("dinner tonight", now, "dinner", "once 2024-03-04 @ 20:00"),
+            (
+                "check oven in 2 hours",
+                now,
+                "check oven",
+                "once 2024-03-04 @ 12:00",
+            ),
+            (
+                "renew passport in 3 days",
+                now,
+                "renew passport",
+                "once 2024-03-07 @ all-day",
+            ),
+            (
+                "call dad next week",
+                now,
+                "call dad",
+                "once 2024-03-11 @ all-day",
+            ),
+            ("party march 1", now, "party", "once 2025-03-01 @ all-day"),
+            (
+                "pay bills the 15th",
+                now,
+                "pay bills",
+                "once 2024-03-15 @ all-day",
+            ),
+            (
+                "taxes 2024-04-15 from 12:30 to 16:30",
+                now,
+                "taxes",
+                "once 2024-04-15 @ 12:30-16:30",
+            ),
+            (
+                "conference friday 9am-5pm",
+                now,
+                "conference",
+                "once 2024-03-08 @ 09:00-17:00",
+            ),
+            (
+                "report today 11-1pm",
+                now,
+                "report",
+                "once 2024-03-04 @ 11:00-13:00",
+            ),
+            ("lunch at noon", now, "lunch", "once 2024-03-04 @ 12:00"),
+            (
+                "backup at midnight",
+                now,
+                "backup",
+                "once 2024-03-05 @ 00:00",
+            ),
+            ("meds at 18:00 daily", now, "meds", "daily @ 18:00"),
+        ]);
+    }
+
+    #[test]
+    fn keeps_numbers_that_are_not_times() {
+        let now = now();
+        check(&[
+            (
+                "buy 2-3 apples tomorrow",
+                now,
+                "buy 2-3 apples",
+                "once 2024-03-05 @ all-day",
+            ),
+            (
+                "buy 2 to 3 apples tomorrow",
+                now,
+                "buy 2 to 3 apples",
+                "once 2024-03-05 @ all-day",
+            ),
+            (
+                "read 10 pages daily",
+                now,
+                "read 10 pages",
+                "daily @ all-day",
+            ),
+            (
+                "standup daily at 9-10",
+                now,
+                "standup",
+                "daily @ 09:00-10:00",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn bare_hours_are_the_next_time_they_come_around() {
+        check(&[
+            ("call at 3", at(10, 0), "call", "once 2024-03-04 @ 15:00"),
+            ("call at 3", at(2, 0), "call", "once 2024-03-04 @ 03:00"),
+            ("call at 3", at(16, 0), "call", "once 2024-03-05 @ 03:00"),
+            ("call at 15", at(10, 0), "call", "once 2024-03-04 @ 15:00"),
+            (
+                "call today at 9",
+                at(10, 0),
+                "call",
+                "once 2024-03-04 @ 21:00",
+            ),
+            (
+                "movie tonight at 9",
+                at(8, 0),
+                "movie",
+                "once 2024-03-04 @ 21:00",
+            ),
+            (
+                "call tomorrow at 3",
+                at(10, 0),
+                "call",
+                "once 2024-03-05 @ 15:00",
+            ),
+            (
+                "call tomorrow at 9",
+                at(10, 0),
+                "call",
+                "once 2024-03-05 @ 09:00",
+            ),
+            ("walk daily at 7", at(10, 0), "walk", "daily @ 07:00"),
+            ("tea daily at 4", at(10, 0), "tea", "daily @ 16:00"),
+        ]);
+    }
+
+    #[test]
+    fn skips_times_that_already_passed_today() {
+        check(&[
+            (
+                "gym monday at 9",
+                at(10, 0),
+                "gym",
+                "once 2024-03-11 @ 09:00",
+            ),
+            (
+                "gym monday at 9",
+                at(8, 0),
+                "gym",
+                "once 2024-03-04 @ 09:00",
+            ),
+            ("gym monday", at(10, 0), "gym", "once 2024-03-04 @ all-day"),
+            (
+                "rent the 4th at 9am",
+                at(10, 0),
+                "rent",
+                "once 2024-04-04 @ 09:00",
+            ),
+            (
+                "rent the 4th at 11am",
+                at(10, 0),
+                "rent",
+                "once 2024-03-04 @ 11:00",
+            ),
+            (
+                "party march 4 at 9am",
+                at(10, 0),
+                "party",
+                "once 2025-03-04 @ 09:00",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn rejects_unclear_text() {
+        let now = now();
+        assert_eq!(QuickAdd::parse("  ", now), Err(QuickAddError::Empty));
+        assert_eq!(
+            QuickAdd::parse("tomorrow at 9am", now),
+            Err(QuickAddError::MissingTitle)
+        );
+        assert_eq!(
+            QuickAdd::parse("buy milk", now),
+            Err(QuickAddError::MissingSchedule)
+        );
+
+        let conflicts = [
+            "gym daily every monday",
+            "call today tomorrow",
+            "call at 9am at 10am",
+            "call monday and friday",
+            "pay the 1st and 15th",
+        ];
+        for text in conflicts {
+            assert!(
+                matches!(QuickAdd::parse(text, now), Err(QuickAddError::Conflict(_))),
+                "{text}"
+            );
+        }
+        let invalid = [
+            ("party february 30", "February 30"),
+            ("rent every year on feb 30", "February 30"),
+            ("review every year on march 1 and april 31", "April 31"),
+        ];
+        for (text, date) in invalid {
+            assert_eq!(
+                QuickAdd::parse(text, now),
+                Err(QuickAddError::InvalidDate(date.into())),
+                "{text}"
+            );
+        }
+    }
+}
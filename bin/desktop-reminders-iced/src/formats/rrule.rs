@@ -0,0 +1,910 @@
+use std::{
+    collections::BTreeMap,
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+use chrono::{Datelike, NaiveDate, NaiveTime, Timelike};
+use thiserror::Error;
+
+use crate::models::reminders::{
+    ReminderDaysOfWeek, ReminderFrequency, ReminderFrequencyDaily, ReminderFrequencyMonthly,
+    ReminderFrequencyWeekly, ReminderFrequencyYearly, ReminderMonth, ReminderTimeOfDay,
+    ReminderYearlyDate,
+};
+
+/// The codes RRULEs use for the days of the week, in order.
+pub const RRULE_DAYS: [(ReminderDaysOfWeek, &str); 7] = [
+    (ReminderDaysOfWeek::MONDAY, "MO"),
+    (ReminderDaysOfWeek::TUESDAY, "TU"),
+    (ReminderDaysOfWeek::WEDNESDAY, "WE"),
+    (ReminderDaysOfWeek::THURSDAY, "TH"),
+    (ReminderDaysOfWeek::FRIDAY, "FR"),
+    (ReminderDaysOfWeek::SATURDAY, "SA"),
+    (ReminderDaysOfWeek::SUNDAY, "SU"),
+];
+
+/// An error converting between an RFC 5545 RRULE and a
+/// [`ReminderFrequency`].
+#[derive(Clone, PartialEq, Eq, Debug, Error)]
+pub enum RruleError {
+    /// A part of the rule is not `NAME=VALUE`.
+    #[error("expected NAME=VALUE, found {0:?}")]
+    MalformedPart(String),
+    /// A part of the rule is given more than once.
+    #[error("{0} is given more than once")]
+    DuplicatePart(String),
+    /// The rule has no `FREQ`.
+    #[error("the rule has no FREQ")]
+    MissingFrequency,
+    /// The rule repeats more often than daily.
+    #[error("FREQ={0} is not supported: reminders repeat at most daily")]
+    UnsupportedFrequency(String),
+    /// The rule has a part that reminders cannot represent.
+    #[error("{part} is not supported: {reason}")]
+    UnsupportedPart {
+        /// The name of the part.
+        part: String,
+        /// Why the part is not supported.
+        reason: &'static str,
+    },
+    /// The rule has a value that reminders cannot represent.
+    #[error("{part}={value} is not supported: {reason}")]
+    UnsupportedValue {
+        /// The name of the part.
+        part: String,
+        /// The unsupported value.
+        value: String,
+        /// Why the value is not supported.
+        reason: &'static str,
+    },
+    /// The rule combines a part with a frequency that it cannot be used with.
+    #[error("{part} cannot be used with FREQ={frequency}")]
+    UnsupportedCombination {
+        /// The name of the part.
+        part: String,
+        /// The frequency of the rule.
+        frequency: String,
+    },
+    /// A value in the rule is not valid.
+    #[error("invalid {part} value {value:?}")]
+    InvalidValue {
+        /// The name of the part.
+        part: String,
+        /// The invalid value.
+        value: String,
+    },
+    /// The days of the month are not in any of the months of the rule, like
+    /// `BYMONTH=2;BYMONTHDAY=30`.
+    #[error("BYMONTHDAY={month_days} is never in BYMONTH={months}")]
+    ImpossibleDates {
+        /// The days of the month.
+        month_days: String,
+        /// The months.
+        months: String,
+    },
+    /// The rule leaves out a part that is needed to know when it repeats.
+    #[error("FREQ={frequency} needs {part} to say when the reminder repeats")]
+    MissingPart {
+        /// The name of the part.
+        part: String,
+        /// The frequency of the rule.
+        frequency: String,
+    },
+    /// The reminder occurs once, so it has no rule.
+    #[error("the reminder occurs once, so it has no rule")]
+    OccursOnce,
+    /// The reminder needs more than one rule.
+    #[error(
+        "the reminder needs {0} rules because its dates do not share the same days and months"
+    )]
+    SeveralRules(usize),
+    /// The reminder never occurs, so it has no equivalent rule.
+    #[error("the reminder never occurs")]
+    NeverOccurs,
+}
+
+/// A recurrence rule that a reminder can follow, like
+/// `FREQ=WEEKLY;BYDAY=MO,WE,FR`. Rules can also set the time of day with a
+/// single `BYHOUR` and `BYMINUTE`, like `FREQ=DAILY;BYHOUR=12;BYMINUTE=30`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Rrule {
+    frequency: ReminderFrequency,
+    time: Option<NaiveTime>,
+    rule: String,
+}
+
+impl Rrule {
+    /// Creates a rule, checking that the frequency can be written as a
+    /// single rule.
+    fn new(frequency: ReminderFrequency, time: Option<NaiveTime>) -> Result<Self, RruleError> {
+        let mut rules = rrules_for_frequency(&frequency)?;
+        let rule = match rules.len() {
+            0 => return Err(RruleError::OccursOnce),
+            1 => rules.remove(0).1,
+            count => return Err(RruleError::SeveralRules(count)),
+        };
+
+        Ok(Self {
+            frequency,
+            time,
+            rule,
+        })
+    }
+
+    /// Gets the frequency the rule describes. The rule's time of day is used
+    /// if it has one, and `time` otherwise.
+    pub fn frequency(&self, time: ReminderTimeOfDay) -> ReminderFrequency {
+        let mut frequency = self.frequency.clone();
+        *frequency.time_of_day_mut() = match self.time {
+            Some(time) => ReminderTimeOfDay::Time { time },
+            None => time,
+        };
+        frequency
+    }
+
+    /// Gets the time of day set by the rule, if it sets one.
+    #[inline]
+    pub fn time(&self) -> Option<NaiveTime> {
+        self.time
+    }
+}
+
+impl FromStr for Rrule {
+    type Err = RruleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts = RruleParts::parse(s)?;
+        let time = parts.time;
+        let frequency = parts.into_frequency(None, ReminderTimeOfDay::AllDay)?;
+        Self::new(frequency, time)
+    }
+}
+
+impl TryFrom<&ReminderFrequency> for Rrule {
+    type Error = RruleError;
+
+    /// Creates the rule for a frequency. The time of day is only part of the
+    /// rule for reminders that occur at a single time.
+    fn try_from(frequency: &ReminderFrequency) -> Result<Self, Self::Error> {
+        let time = match *frequency.time_of_day() {
+            ReminderTimeOfDay::Time { time } => Some(time),
+            _ => None,
+        };
+        Self::new(frequency.clone(), time)
+    }
+}
+
+impl Display for Rrule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.rule)?;
+        if let Some(time) = self.time {
+            write!(f, ";BYHOUR={};BYMINUTE={}", time.hour(), time.minute())?;
+            if time.second() != 0 {
+                write!(f, ";BYSECOND={}", time.second())?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Converts an RRULE like `FREQ=WEEKLY;BYDAY=MO,WE,FR` into a frequency. The
+/// date of the first occurrence fills in the parts the rule leaves out, like
+/// the day of a monthly rule without `BYMONTHDAY`. A time of day set by the
+/// rule replaces `time`.
+pub fn frequency_from_rrule(
+    rule: &str,
+    start: NaiveDate,
+    time: ReminderTimeOfDay,
+) -> Result<ReminderFrequency, RruleError> {
+    let parts = RruleParts::parse(rule)?;
+    let time = match parts.time {
+        Some(time) => ReminderTimeOfDay::Time { time },
+        None => time,
+    };
+    parts.into_frequency(Some(start), time)
+}
+
+/// The parts of an RRULE that reminders support.
+#[derive(Clone, Debug)]
+struct RruleParts {
+    frequency: String,
+    days: Option<ReminderDaysOfWeek>,
+    month_days: Option<Vec<u8>>,
+    months: Option<Vec<ReminderMonth>>,
+    time: Option<NaiveTime>,
+}
+
+impl RruleParts {
+    /// Reads the parts of a rule, with or without the `RRULE:` prefix.
+    fn parse(rule: &str) -> Result<Self, RruleError> {
+        let rule = rule.trim();
+        let rule = rule
+            .strip_prefix("RRULE:")
+            .or_else(|| rule.strip_prefix("rrule:"))
+            .unwrap_or(rule);
+
+        // Collect the parts of the rule
+        let mut parts = BTreeMap::new();
+        for part in rule.split(';').filter(|part| !part.is_empty()) {
+            let (name, value) = part
+                .split_once('=')
+                .ok_or_else(|| RruleError::MalformedPart(part.to_string()))?;
+            let name = name.trim().to_ascii_uppercase();
+            let value = value.trim().to_ascii_uppercase();
+            if parts.insert(name.clone(), value).is_some() {
+                return Err(RruleError::DuplicatePart(name));
+            }
+        }
+
+        let mut frequency = None;
+        let mut days = None;
+        let mut month_days = None;
+        let mut months = None;
+        let (mut hour, mut minute, mut second) = (None, None, None);
+        for (name, value) in &parts {
+            match name.as_str() {
+                "FREQ" => frequency = Some(value.clone()),
+                "INTERVAL" => match value.parse::<u32>() {
+                    Ok(1) => {}
+                    Ok(_) => {
+                        return Err(RruleError::UnsupportedValue {
+                            part: name.clone(),
+                            value: value.clone(),
+                            reason: "reminders cannot skip periods",
+                        })
+                    }
+                    Err(_) => return Err(invalid(name, value)),
+                },
+                "BYDAY" => days = Some(parse_days(name, value)?),
+                "BYMONTHDAY" => month_days = Some(parse_month_days(name, value)?),
+                "BYMONTH" => months = Some(parse_months(name, value)?),
+                "BYHOUR" => hour = Some(parse_time_part(name, value, 23)?),
+                "BYMINUTE" => minute = Some(parse_time_part(name, value, 59)?),
+                "BYSECOND" => second = Some(parse_time_part(name, value, 59)?),
+                // The start of the week only matters for rules that skip weeks
+                "WKST" => {}
+                _ => {
+                    return Err(RruleError::UnsupportedPart {
+                        part: name.clone(),
+                        reason: unsupported_reason(name),
+                    })
+                }
+            }
+        }
+
+        // Without an hour, the minutes and seconds would repeat every hour
+        let time = match (hour, minute, second) {
+            (None, None, None) => None,
+            (Some(hour), minute, second) => {
+                NaiveTime::from_hms_opt(hour, minute.unwrap_or(0), second.unwrap_or(0))
+            }
+            (None, Some(_), _) => {
+                return Err(RruleError::UnsupportedPart {
+                    part: "BYMINUTE".to_string(),
+                    reason: "reminders cannot repeat every hour, so BYHOUR is needed",
+                })
+            }
+            (None, None, Some(_)) => {
+                return Err(RruleError::UnsupportedPart {
+                    part: "BYSECOND".to_string(),
+                    reason: "reminders cannot repeat every minute, so BYHOUR is needed",
+                })
+            }
+        };
+
+        Ok(Self {
+            frequency: frequency.ok_or(RruleError::MissingFrequency)?,
+            days,
+            month_days,
+            months,
+            time,
+        })
+    }
+
+    /// Converts the parts into a frequency. The date of the first occurrence
+    /// fills in the parts the rule leaves out, if it is known.
+    fn into_frequency(
+        self,
+        start: Option<NaiveDate>,
+        time: ReminderTimeOfDay,
+    ) -> Result<ReminderFrequency, RruleError> {
+        let RruleParts {
+            frequency,
+            days,
+            month_days,
+            months,
+            time: _,
+        } = self;
+        let combination = |part: &str| RruleError::UnsupportedCombination {
+            part: part.to_string(),
+            frequency: frequency.clone(),
+        };
+        let missing = |part: &str| RruleError::MissingPart {
+            part: part.to_string(),
+            frequency: frequency.clone(),
+        };
+
+        match frequency.as_str() {
+            "DAILY" => {
+                if month_days.is_some() {
+                    return Err(combination("BYMONTHDAY"));
+                }
+                if months.is_some() {
+                    return Err(combination("BYMONTH"));
+                }
+
+                Ok(match days {
+                    Some(days) => ReminderFrequency::Weekly(ReminderFrequencyWeekly { days, time }),
+                    None => ReminderFrequency::Daily(ReminderFrequencyDaily { time }),
+                })
+            }
+            "WEEKLY" => {
+                if month_days.is_some() {
+                    return Err(combination("BYMONTHDAY"));
+                }
+                if months.is_some() {
+                    return Err(combination("BYMONTH"));
+                }
+
+                let days = days
+                    .or_else(|| start.map(|start| ReminderDaysOfWeek::from(start.weekday())))
+                    .ok_or_else(|| missing("BYDAY"))?;
+                Ok(ReminderFrequency::Weekly(ReminderFrequencyWeekly {
+                    days,
+                    time,
+                }))
+            }
+            "MONTHLY" => {
+                if days.is_some() {
+                    return Err(combination("BYDAY"));
+                }
+                if months.is_some() {
+                    return Err(combination("BYMONTH"));
+                }
+
+                let dates = month_days
+                    .or_else(|| start.map(|start| vec![start.day() as u8]))
+                    .ok_or_else(|| missing("BYMONTHDAY"))?;
+                Ok(ReminderFrequency::Monthly(ReminderFrequencyMonthly {
+                    dates,
+                    time,
+                }))
+            }
+            "YEARLY" => {
+                if days.is_some() {
+                    return Err(combination("BYDAY"));
+                }
+
+                let months = months
+                    .or_else(|| {
+                        start.map(|start| Vec::from_iter(ReminderMonth::from_number(start.month())))
+                    })
+                    .ok_or_else(|| missing("BYMONTH"))?;
+                let month_days = month_days
+                    .or_else(|| start.map(|start| vec![start.day() as u8]))
+                    .ok_or_else(|| missing("BYMONTHDAY"))?;
+                // Days that are not in a month, like February 30, never occur
+                let dates: Vec<_> = months
+                    .iter()
+                    .flat_map(|&month| {
+                        month_days
+                            .iter()
+                            .filter_map(move |&date| ReminderYearlyDate::new(month, date))
+                    })
+                    .collect();
+                if dates.is_empty() {
+                    let months: Vec<_> = months.iter().map(|month| month.number()).collect();
+                    return Err(RruleError::ImpossibleDates {
+                        month_days: join(&month_days),
+                        months: join(&months),
+                    });
+                }
+                Ok(ReminderFrequency::Yearly(ReminderFrequencyYearly {
+                    dates,
+                    time,
+                }))
+            }
+            "SECONDLY" | "MINUTELY" | "HOURLY" => Err(RruleError::UnsupportedFrequency(frequency)),
+            _ => Err(invalid("FREQ", &frequency)),
+        }
+    }
+}
+
+/// Converts a frequency into RRULEs, without the `RRULE:` prefix. Reminders
+/// that occur once have no rules.
+///
+/// Most frequencies need a single rule. Yearly dates that do not share a day
+/// of the month or a set of months need one rule for each group of dates, so
+/// each rule is returned along with the part of the frequency it covers.
+pub fn rrules_for_frequency(
+    frequency: &ReminderFrequency,
+) -> Result<Vec<(ReminderFrequency, String)>, RruleError> {
+    let rule = match frequency {
+        ReminderFrequency::Once(_) => return Ok(Vec::new()),
+        ReminderFrequency::Daily(_) => "FREQ=DAILY".to_string(),
+        ReminderFrequency::Weekly(weekly) => {
+            if weekly.days.is_empty() {
+                return Err(RruleError::NeverOccurs);
+            }
+
+            let days: Vec<_> = RRULE_DAYS
+                .iter()
+                .filter(|&&(day, _)| weekly.days.contains(day))
+                .map(|&(_, code)| code)
+                .collect();
+            format!("FREQ=WEEKLY;BYDAY={}", days.join(","))
+        }
+        ReminderFrequency::Monthly(monthly) => {
+            let mut dates = monthly.dates.clone();
+            dates.sort_unstable();
+            dates.dedup();
+            if dates.is_empty() {
+                return Err(RruleError::NeverOccurs);
+            }
+
+            format!("FREQ=MONTHLY;BYMONTHDAY={}", join(&dates))
+        }
+        ReminderFrequency::Yearly(yearly) => return yearly_rrules(yearly),
+    };
+
+    Ok(vec![(frequency.clone(), rule)])
+}
+
+/// Converts yearly dates into as few rules as possible. Each rule repeats on
+/// every combination of its months and days.
+fn yearly_rrules(
+    yearly: &ReminderFrequencyYearly,
+) -> Result<Vec<(ReminderFrequency, String)>, RruleError> {
+    // Find the months of each day, then the days that share the same months
+    let mut months_by_day = BTreeMap::<u8, Vec<u32>>::new();
+    for date in &yearly.dates {
+        let months = months_by_day.entry(date.date).or_default();
+        months.push(date.month.number());
+        months.sort_unstable();
+        months.dedup();
+    }
+    let mut days_by_months = BTreeMap::<Vec<u32>, Vec<u8>>::new();
+    for (day, months) in months_by_day {
+        days_by_months.entry(months).or_default().push(day);
+    }
+    if days_by_months.is_empty() {
+        return Err(RruleError::NeverOccurs);
+    }
+
+    Ok(days_by_months
+        .into_iter()
+        .map(|(months, days)| {
+            let dates = months
+                .iter()
+                .filter_map(|&month| ReminderMonth::from_number(month))
+                .flat_map(|month| {
+                    days.iter()
+                        .map(move |&date| ReminderYearlyDate { month, date })
+                })
+                .collect();
+            let part = ReminderFrequency::Yearly(ReminderFrequencyYearly {
+                dates,
+                time: yearly.time,
+            });
+            let rule = format!(
+                "FREQ=YEARLY;BYMONTH={};BYMONTHDAY={}",
+                join(&months),
+                join(&days)
+            );
+            (part, rule)
+        })
+        .collect())
+}
+
+/// Parses the value of `BYDAY`.
+fn parse_days(part: &str, value: &str) -> Result<ReminderDaysOfWeek, RruleError> {
+    let mut days = ReminderDaysOfWeek::empty();
+    for code in value.split(',') {
+        let code = code.trim();
+        let day = RRULE_DAYS.iter().find(|&&(_, name)| name == code);
+        match day {
+            Some(&(day, _)) => days |= day,
+            // Days like "1MO" or "-1FR" pick a specific weekday of the month
+            None if code.len() > 2 && RRULE_DAYS.iter().any(|(_, name)| code.ends_with(name)) => {
+                return Err(RruleError::UnsupportedValue {
+                    part: part.to_string(),
+                    value: code.to_string(),
+                    reason: "reminders cannot repeat on the nth weekday of a period",
+                })
+            }
+            None => return Err(invalid(part, code)),
+        }
+    }
+
+    Ok(days)
+}
+
+/// Parses the value of `BYMONTHDAY`.
+fn parse_month_days(part: &str, value: &str) -> Result<Vec<u8>, RruleError> {
+    value
+        .split(',')
+        .map(|day| {
+            let day = day.trim();
+            match day.parse::<i8>() {
+                Ok(date @ 1..=31) => Ok(date as u8),
+                Ok(-31..=-1) => Err(RruleError::UnsupportedValue {
+                    part: part.to_string(),
+                    value: day.to_string(),
+                    reason: "reminders cannot count days from the end of the month",
+                }),
+                _ => Err(invalid(part, day)),
+            }
+        })
+        .collect()
+}
+
+/// Parses the value of `BYMONTH`.
+fn parse_months(part: &str, value: &str) -> Result<Vec<ReminderMonth>, RruleError> {
+    value
+        .split(',')
+        .map(|month| {
+            let month = month.trim();
+            month
+                .parse()
+                .ok()
+                .and_then(ReminderMonth::from_number)
+                .ok_or_else(|| invalid(part, month))
+        })
+        .collect()
+}
+
+/// Parses the value of `BYHOUR`, `BYMINUTE` or `BYSECOND`, which can only
+/// have a single value since reminders occur at most once a day.
+fn parse_time_part(part: &str, value: &str, max: u32) -> Result<u32, RruleError> {
+    if value.contains(',') {
+        return Err(RruleError::UnsupportedValue {
+            part: part.to_string(),
+            value: value.to_string(),
+            reason: "reminders occur at most once a day, so only one value can be given",
+        });
+    }
+
+    value
+        .parse()
+        .ok()
+        .filter(|&value| value <= max)
+        .ok_or_else(|| invalid(part, value))
+}
+
+/// Explains why a part of a rule is not supported.
+fn unsupported_reason(part: &str) -> &'static str {
+    match part {
+        "COUNT" | "UNTIL" => "reminders repeat forever",
+        "BYSETPOS" => "reminders cannot pick the nth matching day of a period",
+        "BYWEEKNO" => "reminders cannot repeat on weeks of the year",
+        "BYYEARDAY" => "reminders cannot repeat on days of the year",
+        _ => "unknown part",
+    }
+}
+
+/// Creates an error for an invalid value.
+fn invalid(part: &str, value: &str) -> RruleError {
+    RruleError::InvalidValue {
+        part: part.to_string(),
+        value: value.to_string(),
+    }
+}
+
+/// Joins numbers with commas.
+fn join<T: ToString>(values: &[T]) -> String {
+    let values: Vec<_> = values.iter().map(ToString::to_string).collect();
+    values.join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use crate::models::reminders::{ReminderFrequency, ReminderTimeOfDay};
+
+    use super::{frequency_from_rrule, Rrule, RruleError};
+
+    fn schedule(schedule: &str) -> ReminderFrequency {
+        schedule.parse().unwrap()
+    }
+
+    fn parse(rule: &str) -> Result<ReminderFrequency, RruleError> {
+        rule.parse::<Rrule>()
+            .map(|rule| rule.frequency(ReminderTimeOfDay::AllDay))
+    }
+
+    #[test]
+    fn parses_rules() {
+        let cases = [
+            ("FREQ=DAILY", "daily @ all-day"),
+            ("RRULE:FREQ=DAILY;INTERVAL=1", "daily @ all-day"),
+            ("rrule:freq=daily;wkst=su", "daily @ all-day"),
+            (
+                "FREQ=DAILY;BYDAY=MO,TU,WE,TH,FR",
+                "weekly weekdays @ all-day",
+            ),
+            ("FREQ=WEEKLY;BYDAY=MO,WE,FR", "weekly mon,wed,fri @ all-day"),
+            ("FREQ=WEEKLY;BYDAY=SU", "weekly sun @ all-day"),
+            (
+                "FREQ=MONTHLY;BYMONTHDAY=1,15,31",
+                "monthly 1,15,31 @ all-day",
+            ),
+            (
+                "FREQ=YEARLY;BYMONTH=3;BYMONTHDAY=1",
+                "yearly 03-01 @ all-day",
+            ),
+            (
+                "FREQ=YEARLY;BYMONTH=6,12;BYMONTHDAY=1,15",
+                "yearly 06-01,06-15,12-01,12-15 @ all-day",
+            ),
+            // Days that are not in a month never occur
+            (
+                "FREQ=YEARLY;BYMONTH=1,2;BYMONTHDAY=30",
+                "yearly 01-30 @ all-day",
+            ),
+            ("FREQ=DAILY;BYHOUR=9", "daily @ 09:00"),
+            (
+                "FREQ=WEEKLY;BYDAY=SA;BYHOUR=12;BYMINUTE=30",
+                "weekly sat @ 12:30",
+            ),
+        ];
+        for (rule, expected) in cases {
+            assert_eq!(parse(rule), Ok(schedule(expected)), "{rule}");
+        }
+    }
+
+    #[test]
+    fn start_fills_in_missing_parts() {
+        // 2024-03-05 is a Tuesday
+        let start = NaiveDate::from_ymd_opt(2024, 3, 5).unwrap();
+        let time = ReminderTimeOfDay::AllDay;
+        let cases = [
+            ("FREQ=WEEKLY", "weekly tue @ all-day"),
+            ("FREQ=MONTHLY", "monthly 5 @ all-day"),
+            ("FREQ=YEARLY", "yearly 03-05 @ all-day"),
+            ("FREQ=YEARLY;BYMONTH=9", "yearly 09-05 @ all-day"),
+            ("FREQ=YEARLY;BYMONTHDAY=20", "yearly 03-20 @ all-day"),
+            ("FREQ=DAILY;BYHOUR=8;BYMINUTE=15", "daily @ 08:15"),
+        ];
+        for (rule, expected) in cases {
+            assert_eq!(
+                frequency_from_rrule(rule, start, time),
+                Ok(schedule(expected)),
+                "{rule}"
+            );
+        }
+    }
+
+    #[test]
+    fn writes_rules() {
+        let cases = [
+            ("daily @ all-day", "FREQ=DAILY"),
+            ("daily @ 09:00-10:00", "FREQ=DAILY"),
+            ("daily @ 09:05", "FREQ=DAILY;BYHOUR=9;BYMINUTE=5"),
+            (
+                "daily @ 09:05:30",
+                "FREQ=DAILY;BYHOUR=9;BYMINUTE=5;BYSECOND=30",
+            ),
+            ("weekly mon,fri @ all-day", "FREQ=WEEKLY;BYDAY=MO,FR"),
+            ("monthly 1,15 @ all-day", "FREQ=MONTHLY;BYMONTHDAY=1,15"),
+            (
+                "yearly 06-01,12-01 @ all-day",
+                "FREQ=YEARLY;BYMONTH=6,12;BYMONTHDAY=1",
+            ),
+        ];
+        for (frequency, expected) in cases {
+            let frequency = schedule(frequency);
+            let rule = Rrule::try_from(&frequency).unwrap();
+            assert_eq!(rule.to_string(), expected, "{frequency}");
+
+            // Reading the rule back gives the same frequency
+            let parsed: Rrule = expected.parse().unwrap();
+            assert_eq!(
+                parsed.frequency(*frequency.time_of_day()),
+                frequency,
+                "{expected}"
+            );
+        }
+
+        // Dates are sorted and duplicates are removed
+        let rule = Rrule::try_from(&schedule("monthly 15,1,15 @ all-day")).unwrap();
+        assert_eq!(rule.to_string(), "FREQ=MONTHLY;BYMONTHDAY=1,15");
+    }
+
+    #[test]
+    fn rejects_unsupported_rules() {
+        let unsupported_part = |part: &str, reason| RruleError::UnsupportedPart {
+            part: part.to_string(),
+            reason,
+        };
+        let unsupported_value = |part: &str, value: &str, reason| RruleError::UnsupportedValue {
+            part: part.to_string(),
+            value: value.to_string(),
+            reason,
+        };
+        let combination = |part: &str, frequency: &str| RruleError::UnsupportedCombination {
+            part: part.to_string(),
+            frequency: frequency.to_string(),
+        };
+        let invalid = |part: &str, value: &str| RruleError::InvalidValue {
+            part: part.to_string(),
+            value: value.to_string(),
+        };
+
+        let cases = [
+            ("FREQ", RruleError::MalformedPart("FREQ".into())),
+            (
+                "FREQ=DAILY;FREQ=WEEKLY",
+                RruleError::DuplicatePart("FREQ".into()),
+            ),
+            ("BYDAY=MO", RruleError::MissingFrequency),
+            (
+                "FREQ=HOURLY",
+                RruleError::UnsupportedFrequency("HOURLY".into()),
+            ),
+            (
+                "FREQ=MINUTELY",
+                RruleError::UnsupportedFrequency("MINUTELY".into()),
+            ),
+            (
+                "FREQ=SECONDLY",
+                RruleError::UnsupportedFrequency("SECONDLY".into()),
+            ),
+            ("FREQ=FORTNIGHTLY", invalid("FREQ", "FORTNIGHTLY")),
+            (
+                "FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=30",
+                RruleError::ImpossibleDates {
+                    month_days: "30".into(),
+                    months: "2".into(),
+                },
+            ),
+            (
+                "FREQ=YEARLY;BYMONTH=4,6;BYMONTHDAY=31",
+                RruleError::ImpossibleDates {
+                    month_days: "31".into(),
+                    months: "4,6".into(),
+                },
+            ),
+            (
+                "FREQ=DAILY;COUNT=3",
+                unsupported_part("COUNT", "reminders repeat forever"),
+            ),
+            (
+                "FREQ=DAILY;UNTIL=20240301",
+                unsupported_part("UNTIL", "reminders repeat forever"),
+            ),
+            (
+                "FREQ=MONTHLY;BYSETPOS=-1",
+                unsupported_part(
+                    "BYSETPOS",
+                    "reminders cannot pick the nth matching day of a period",
+                ),
+            ),
+            (
+                "FREQ=YEARLY;BYWEEKNO=20",
+                unsupported_part("BYWEEKNO", "reminders cannot repeat on weeks of the year"),
+            ),
+            (
+                "FREQ=YEARLY;BYYEARDAY=100",
+                unsupported_part("BYYEARDAY", "reminders cannot repeat on days of the year"),
+            ),
+            (
+                "FREQ=DAILY;X-NAME=1",
+                unsupported_part("X-NAME", "unknown part"),
+            ),
+            (
+                "FREQ=DAILY;BYMINUTE=30",
+                unsupported_part(
+                    "BYMINUTE",
+                    "reminders cannot repeat every hour, so BYHOUR is needed",
+                ),
+            ),
+            (
+                "FREQ=DAILY;BYSECOND=30",
+                unsupported_part(
+                    "BYSECOND",
+                    "reminders cannot repeat every minute, so BYHOUR is needed",
+                ),
+            ),
+            (
+                "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO",
+                unsupported_value("INTERVAL", "2", "reminders cannot skip periods"),
+            ),
+            ("FREQ=DAILY;INTERVAL=often", invalid("INTERVAL", "OFTEN")),
+            (
+                "FREQ=MONTHLY;BYDAY=1MO",
+                unsupported_value(
+                    "BYDAY",
+                    "1MO",
+                    "reminders cannot repeat on the nth weekday of a period",
+                ),
+            ),
+            ("FREQ=WEEKLY;BYDAY=XX", invalid("BYDAY", "XX")),
+            (
+                "FREQ=MONTHLY;BYMONTHDAY=-1",
+                unsupported_value(
+                    "BYMONTHDAY",
+                    "-1",
+                    "reminders cannot count days from the end of the month",
+                ),
+            ),
+            ("FREQ=MONTHLY;BYMONTHDAY=32", invalid("BYMONTHDAY", "32")),
+            (
+                "FREQ=YEARLY;BYMONTH=13;BYMONTHDAY=1",
+                invalid("BYMONTH", "13"),
+            ),
+            (
+                "FREQ=DAILY;BYHOUR=9,17",
+                unsupported_value(
+                    "BYHOUR",
+                    "9,17",
+                    "reminders occur at most once a day, so only one value can be given",
+                ),
+            ),
+            ("FREQ=DAILY;BYHOUR=24", invalid("BYHOUR", "24")),
+            (
+                "FREQ=DAILY;BYMONTHDAY=1",
+                combination("BYMONTHDAY", "DAILY"),
+            ),
+            ("FREQ=DAILY;BYMONTH=1", combination("BYMONTH", "DAILY")),
+            (
+                "FREQ=WEEKLY;BYDAY=MO;BYMONTHDAY=1",
+                combination("BYMONTHDAY", "WEEKLY"),
+            ),
+            (
+                "FREQ=WEEKLY;BYDAY=MO;BYMONTH=1",
+                combination("BYMONTH", "WEEKLY"),
+            ),
+            ("FREQ=MONTHLY;BYDAY=MO", combination("BYDAY", "MONTHLY")),
+            (
+                "FREQ=MONTHLY;BYMONTHDAY=1;BYMONTH=1",
+                combination("BYMONTH", "MONTHLY"),
+            ),
+            ("FREQ=YEARLY;BYDAY=MO", combination("BYDAY", "YEARLY")),
+            (
+                "FREQ=WEEKLY",
+                RruleError::MissingPart {
+                    part: "BYDAY".into(),
+                    frequency: "WEEKLY".into(),
+                },
+            ),
+            (
+                "FREQ=MONTHLY",
+                RruleError::MissingPart {
+                    part: "BYMONTHDAY".into(),
+                    frequency: "MONTHLY".into(),
+                },
+            ),
+            (
+                "FREQ=YEARLY;BYMONTHDAY=1",
+                RruleError::MissingPart {
+                    part: "BYMONTH".into(),
+                    frequency: "YEARLY".into(),
+                },
+            ),
+        ];
+        for (rule, expected) in cases {
+            assert_eq!(parse(rule), Err(expected), "{rule}");
+        }
+    }
+
+    #[test]
+    fn rejects_frequencies_without_a_single_rule() {
+        let cases = [
+            ("once 2024-03-01 @ 09:00", RruleError::OccursOnce),
+            ("weekly none @ 09:00", RruleError::NeverOccurs),
+            ("monthly none @ 09:00", RruleError::NeverOccurs),
+            ("yearly none @ 09:00", RruleError::NeverOccurs),
+            ("yearly 03-01,12-25 @ 09:00", RruleError::SeveralRules(2)),
+        ];
+        for (frequency, expected) in cases {
+            assert_eq!(
+                Rrule::try_from(&schedule(frequency)),
+                Err(expected),
+                "{frequency}"
+            );
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use chrono::{Duration, NaiveDate, Weekday};
+
+use crate::models::reminders::{
+    ReminderDaysOfWeek, ReminderFrequency, ReminderFrequencyMonthly, ReminderFrequencyWeekly,
+    ReminderFrequencyYearly,
+};
+
+/// The days of the week, in the order split frequencies are listed in.
+const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+/// Splits a frequency into frequencies that each occur on a single day of the
+/// week, month or year, for formats that can only repeat on one day.
+pub fn split_frequency(frequency: &ReminderFrequency) -> Vec<ReminderFrequency> {
+    match frequency {
+        ReminderFrequency::Once(_) | ReminderFrequency::Daily(_) => vec![frequency.clone()],
+        ReminderFrequency::Weekly(weekly) => WEEKDAYS
+            .into_iter()
+            .map(ReminderDaysOfWeek::from)
+            .filter(|&day| weekly.days.contains(day))
+            .map(|days| {
+                ReminderFrequency::Weekly(ReminderFrequencyWeekly {
+                    days,
+                    time: weekly.time,
+                })
+            })
+            .collect(),
+        ReminderFrequency::Monthly(monthly) => {
+            let mut dates = monthly.dates.clone();
+            dates.sort_unstable();
+            dates.dedup();
+            dates
+                .into_iter()
+                .map(|date| {
+                    ReminderFrequency::Monthly(ReminderFrequencyMonthly {
+                        dates: vec![date],
+                        time: monthly.time,
+                    })
+                })
+                .collect()
+        }
+        ReminderFrequency::Yearly(yearly) => {
+            let mut dates = yearly.dates.clone();
+            dates.sort_unstable_by_key(|date| (date.month.number(), date.date));
+            dates.dedup();
+            dates
+                .into_iter()
+                .map(|date| {
+                    ReminderFrequency::Yearly(ReminderFrequencyYearly {
+                        dates: vec![date],
+                        time: yearly.time,
+                    })
+                })
+                .collect()
+        }
+    }
+}
+
+/// Gets the date a frequency first occurs on, on or after `today`. Reminders
+/// that only occur once keep their date even if it has passed.
+pub fn first_date_from(frequency: &ReminderFrequency, today: NaiveDate) -> Option<NaiveDate> {
+    match frequency {
+        ReminderFrequency::Once(once) => Some(once.date),
+        frequency => {
+            let before_today =
+                today.and_hms_opt(0, 0, 0).unwrap_or_default() - Duration::nanoseconds(1);
+            frequency
+                .next_occurrence_after(before_today)
+                .map(|at| at.date())
+        }
+    }
+}
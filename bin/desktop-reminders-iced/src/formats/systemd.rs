@@ -0,0 +1,396 @@
+use std::{fmt::Write, path::PathBuf};
+
+use chrono::{NaiveDateTime, NaiveTime};
+
+use crate::{
+    formats::Converted,
+    models::reminders::{
+        Reminder, ReminderDaysOfWeek, ReminderFrequency, ReminderId, ReminderTimeOfDay,
+    },
+};
+
+/// The prefix of the names of generated units.
+pub const SYSTEMD_UNIT_PREFIX: &str = "desktop-reminders-";
+
+/// The first line of generated units, used to tell them apart from units
+/// written by hand.
+pub const SYSTEMD_UNIT_HEADER: &str =
+    "# Generated by desktop-reminders. Changes are overwritten when units are synced.";
+
+/// The names systemd uses for the days of the week, in order.
+const SYSTEMD_DAYS: [(ReminderDaysOfWeek, &str); 7] = [
+    (ReminderDaysOfWeek::MONDAY, "Mon"),
+    (ReminderDaysOfWeek::TUESDAY, "Tue"),
+    (ReminderDaysOfWeek::WEDNESDAY, "Wed"),
+    (ReminderDaysOfWeek::THURSDAY, "Thu"),
+    (ReminderDaysOfWeek::FRIDAY, "Fri"),
+    (ReminderDaysOfWeek::SATURDAY, "Sat"),
+    (ReminderDaysOfWeek::SUNDAY, "Sun"),
+];
+
+/// The command a generated service runs to show a reminder.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SystemdCommand {
+    /// The program to run. The ID of the reminder is passed after `args`.
+    pub program: PathBuf,
+    /// The arguments passed before the ID of the reminder.
+    pub args: Vec<String>,
+    /// The environment variables to set for the program.
+    pub environment: Vec<(String, String)>,
+}
+
+/// A `.timer` and `.service` pair for a reminder.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SystemdUnits {
+    /// The reminder the units are for.
+    pub id: ReminderId,
+    /// The name of the units, without an extension.
+    pub name: String,
+    /// The contents of the `.timer` unit.
+    pub timer: String,
+    /// The contents of the `.service` unit.
+    pub service: String,
+}
+
+impl SystemdUnits {
+    /// Gets the file name of the `.timer` unit.
+    pub fn timer_name(&self) -> String {
+        format!("{}.timer", self.name)
+    }
+
+    /// Gets the file name of the `.service` unit.
+    pub fn service_name(&self) -> String {
+        format!("{}.service", self.name)
+    }
+}
+
+/// Creates the `OnCalendar=` expressions for a frequency. Reminders that last
+/// all day start at midnight, and reminders that last a range of time start
+/// at the start of the range, like they do in the app.
+pub fn on_calendar(frequency: &ReminderFrequency) -> Vec<String> {
+    let time = start_time(frequency).format("%H:%M:%S");
+
+    match frequency {
+        ReminderFrequency::Once(once) => vec![format!("{} {time}", once.date.format("%Y-%m-%d"))],
+        ReminderFrequency::Daily(_) => vec![format!("*-*-* {time}")],
+        ReminderFrequency::Weekly(weekly) if weekly.days.is_empty() => Vec::new(),
+        ReminderFrequency::Weekly(weekly) => {
+            let days: Vec<_> = SYSTEMD_DAYS
+                .iter()
+                .filter(|&&(day, _)| weekly.days.contains(day))
+                .map(|&(_, name)| name)
+                .collect();
+            vec![format!("{} *-*-* {time}", days.join(","))]
+        }
+        ReminderFrequency::Monthly(monthly) if monthly.dates.is_empty() => Vec::new(),
+        ReminderFrequency::Monthly(monthly) => {
+            let mut dates = monthly.dates.clone();
+            dates.sort_unstable();
+            dates.dedup();
+            let dates: Vec<_> = dates.iter().map(|date| format!("{date:02}")).collect();
+            vec![format!("*-*-{} {time}", dates.join(","))]
+        }
+        ReminderFrequency::Yearly(yearly) => {
+            let mut dates: Vec<_> = yearly
+                .dates
+                .iter()
+                .map(|date| (date.month.number(), date.date))
+                .collect();
+            dates.sort_unstable();
+            dates.dedup();
+            dates
+                .into_iter()
+                .map(|(month, date)| format!("*-{month:02}-{date:02} {time}"))
+                .collect()
+        }
+    }
+}
+
+/// Gets the time of day a timer elapses for a frequency.
+fn start_time(frequency: &ReminderFrequency) -> NaiveTime {
+    match *frequency.time_of_day() {
+        ReminderTimeOfDay::AllDay => NaiveTime::default(),
+        ReminderTimeOfDay::Time { time } => time,
+        ReminderTimeOfDay::TimeRange { start, .. } => start,
+    }
+}
+
+/// Creates a timer and service for each reminder. Reminders that never occur
+/// are skipped and reported as warnings, and one-time reminders that are
+/// already past at `now` are reported since their timers never elapse.
+pub fn systemd_units(
+    reminders: &[Reminder],
+    command: &SystemdCommand,
+    now: NaiveDateTime,
+) -> Converted<Vec<SystemdUnits>> {
+    let mut converted = Converted::new(Vec::new());
+    for reminder in reminders {
+        let calendars = on_calendar(&reminder.frequency);
+        if calendars.is_empty() {
+            converted.warnings.push(format!(
+                "reminder {} ({:?}): skipped: it never occurs",
+                reminder.id, reminder.message
+            ));
+            continue;
+        }
+        if let ReminderFrequency::Once(once) = &reminder.frequency {
+            let at = once.date.and_time(start_time(&reminder.frequency));
+            if at <= now {
+                converted.warnings.push(format!(
+                    "reminder {} ({:?}): {} is already past, so its timer never elapses",
+                    reminder.id,
+                    reminder.message,
+                    at.format("%Y-%m-%d %H:%M"),
+                ));
+            }
+        }
+
+        let description = escape_specifiers(&single_line(&reminder.message));
+        let mut timer = String::new();
+        let _ = writeln!(timer, "{SYSTEMD_UNIT_HEADER}");
+        let _ = writeln!(timer, "[Unit]");
+        let _ = writeln!(timer, "Description=Reminder timer: {description}");
+        let _ = writeln!(timer);
+        let _ = writeln!(timer, "[Timer]");
+        for calendar in calendars {
+            let _ = writeln!(timer, "OnCalendar={calendar}");
+        }
+        let _ = writeln!(timer, "Persistent=true");
+        let _ = writeln!(timer, "AccuracySec=1s");
+        let _ = writeln!(timer);
+        let _ = writeln!(timer, "[Install]");
+        let _ = writeln!(timer, "WantedBy=timers.target");
+
+        let mut service = String::new();
+        let _ = writeln!(service, "{SYSTEMD_UNIT_HEADER}");
+        let _ = writeln!(service, "[Unit]");
+        let _ = writeln!(service, "Description=Reminder: {description}");
+        let _ = writeln!(service);
+        let _ = writeln!(service, "[Service]");
+        let _ = writeln!(service, "Type=oneshot");
+        for (name, value) in &command.environment {
+            let _ = writeln!(service, "Environment={}", quote(&format!("{name}={value}")));
+        }
+        // Commands also expand variables, unlike environment assignments
+        let mut exec = quote(&command.program.to_string_lossy()).replace('$', "$$");
+        for arg in &command.args {
+            exec.push(' ');
+            exec.push_str(&quote(arg).replace('$', "$$"));
+        }
+        let _ = writeln!(service, "ExecStart={exec} {}", reminder.id);
+
+        converted.value.push(SystemdUnits {
+            id: reminder.id,
+            name: format!("{SYSTEMD_UNIT_PREFIX}{}", reminder.id),
+            timer,
+            service,
+        });
+    }
+
+    converted
+}
+
+/// Joins the lines of a message, since unit settings are a single line.
+fn single_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Escapes the `%` specifiers systemd expands in most settings.
+fn escape_specifiers(text: &str) -> String {
+    text.replace('%', "%%")
+}
+
+/// Quotes a word of a command line or an environment assignment, if needed.
+fn quote(word: &str) -> String {
+    let word = escape_specifiers(word);
+    let plain = !word.is_empty()
+        && !word
+            .chars()
+            .any(|c| c.is_whitespace() || matches!(c, '"' | '\'' | '\\' | ';'));
+    if plain {
+        return word;
+    }
+
+    let mut quoted = String::with_capacity(word.len() + 2);
+    quoted.push('"');
+    for c in word.chars() {
+        match c {
+            '"' | '\\' => {
+                quoted.push('\\');
+                quoted.push(c);
+            }
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use chrono::NaiveDateTime;
+
+    use crate::models::reminders::{Reminder, ReminderFrequency, ReminderId};
+
+    use super::{on_calendar, systemd_units, SystemdCommand, SYSTEMD_UNIT_HEADER};
+
+    fn schedule(schedule: &str) -> ReminderFrequency {
+        schedule.parse().unwrap()
+    }
+
+    fn reminder(id: u64, schedule: &str, message: &str) -> Reminder {
+        Reminder {
+            id: ReminderId(id),
+            frequency: schedule.parse().unwrap(),
+            message: message.to_string(),
+            completed: false,
+            snoozed_until: None,
+            actions: Vec::new(),
+            contact: None,
+        }
+    }
+
+    fn command() -> SystemdCommand {
+        SystemdCommand {
+            program: PathBuf::from("/usr/bin/desktop-reminders-iced"),
+            args: vec!["notify".to_string()],
+            environment: Vec::new(),
+        }
+    }
+
+    fn now() -> NaiveDateTime {
+        "2024-03-01T12:00:00".parse().unwrap()
+    }
+
+    #[test]
+    fn writes_calendar_for_each_frequency() {
+        let cases: [(&str, &[&str]); 10] = [
+            ("once 2024-03-01 @ 12:30", &["2024-03-01 12:30:00"]),
+            ("once 2024-03-01 @ all-day", &["2024-03-01 00:00:00"]),
+            ("daily @ 09:00", &["*-*-* 09:00:00"]),
+            ("daily @ 12:30-16:30", &["*-*-* 12:30:00"]),
+            (
+                "weekly sun,wed,mon @ 08:15",
+                &["Mon,Wed,Sun *-*-* 08:15:00"],
+            ),
+            ("weekly none @ 09:00", &[]),
+            ("monthly 15,1,15 @ all-day", &["*-*-01,15 00:00:00"]),
+            ("monthly none @ 09:00", &[]),
+            (
+                "yearly 12-25,03-01 @ 18:00-20:00",
+                &["*-03-01 18:00:00", "*-12-25 18:00:00"],
+            ),
+            ("yearly 02-29 @ all-day", &["*-02-29 00:00:00"]),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(on_calendar(&schedule(input)), expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn writes_units() {
+        let converted = systemd_units(
+            &[reminder(7, "weekly mon,fri @ 09:00", "Stand\nup")],
+            &command(),
+            now(),
+        );
+        assert!(converted.warnings.is_empty(), "{:?}", converted.warnings);
+        let [units] = converted.value.as_slice() else {
+            panic!("{:?}", converted.value);
+        };
+        assert_eq!(units.timer_name(), "desktop-reminders-7.timer");
+        assert_eq!(units.service_name(), "desktop-reminders-7.service");
+        assert_eq!(
+            units.timer,
+            format!(
+                "{SYSTEMD_UNIT_HEADER}\n\
+                 [Unit]\n\
+                 Description=Reminder timer: Stand up\n\
+                 \n\
+                 [Timer]\n\
+                 OnCalendar=Mon,Fri *-*-* 09:00:00\n\
+                 Persistent=true\n\
+                 AccuracySec=1s\n\
+                 \n\
+                 [Install]\n\
+                 WantedBy=timers.target\n"
+            )
+        );
+        assert_eq!(
+            units.service,
+            format!(
+                "{SYSTEMD_UNIT_HEADER}\n\
+                 [Unit]\n\
+                 Description=Reminder: Stand up\n\
+                 \n\
+                 [Service]\n\
+                 Type=oneshot\n\
+                 ExecStart=/usr/bin/desktop-reminders-iced notify 7\n"
+            )
+        );
+    }
+
+    #[test]
+    fn escapes_commands_and_environment() {
+        let command = SystemdCommand {
+            program: PathBuf::from("/opt/My Apps/100%/remind$er"),
+            args: vec![
+                "notify".to_string(),
+                "say \"hi\"".to_string(),
+                "$HOME".to_string(),
+            ],
+            environment: vec![
+                (
+                    "REMINDERS_STORE".to_string(),
+                    "/home/a b/100%.json".to_string(),
+                ),
+                ("REMINDERS_SETTINGS".to_string(), "/home/$USER".to_string()),
+            ],
+        };
+        let converted = systemd_units(
+            &[reminder(3, "daily @ 09:00", "Pay 50% of \"rent\"")],
+            &command,
+            now(),
+        );
+        let service = &converted.value[0].service;
+        let lines: Vec<_> = service.lines().skip(2).collect();
+        assert_eq!(
+            lines,
+            [
+                "Description=Reminder: Pay 50%% of \"rent\"",
+                "",
+                "[Service]",
+                "Type=oneshot",
+                "Environment=\"REMINDERS_STORE=/home/a b/100%%.json\"",
+                "Environment=REMINDERS_SETTINGS=/home/$USER",
+                "ExecStart=\"/opt/My Apps/100%%/remind$$er\" notify \"say \\\"hi\\\"\" $$HOME 3",
+            ]
+        );
+    }
+
+    #[test]
+    fn warns_about_skipped_and_past_reminders() {
+        let converted = systemd_units(
+            &[
+                reminder(1, "weekly none @ 09:00", "Never"),
+                reminder(2, "once 2024-03-01 @ 09:00", "Earlier today"),
+                reminder(3, "once 2024-02-29 @ all-day", "Yesterday"),
+                reminder(4, "once 2024-03-01 @ 12:30", "Later today"),
+            ],
+            &command(),
+            now(),
+        );
+        let ids: Vec<_> = converted.value.iter().map(|units| units.id).collect();
+        assert_eq!(ids, [ReminderId(2), ReminderId(3), ReminderId(4)]);
+        assert_eq!(
+            converted.warnings,
+            [
+                "reminder 1 (\"Never\"): skipped: it never occurs",
+                "reminder 2 (\"Earlier today\"): 2024-03-01 09:00 is already past, so its timer never elapses",
+                "reminder 3 (\"Yesterday\"): 2024-02-29 00:00 is already past, so its timer never elapses",
+            ]
+        );
+    }
+}
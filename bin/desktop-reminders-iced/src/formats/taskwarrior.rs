@@ -0,0 +1,463 @@
+use chrono::{Datelike, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    formats::{first_date_from, split_frequency, Converted},
+    models::reminders::{
+        Reminder, ReminderDaysOfWeek, ReminderFrequency, ReminderFrequencyDaily,
+        ReminderFrequencyMonthly, ReminderFrequencyOnce, ReminderFrequencyWeekly,
+        ReminderFrequencyYearly, ReminderMonth, ReminderTimeOfDay, ReminderYearlyDate,
+    },
+};
+
+/// A task from a Taskwarrior export. Only the attributes reminders can use are
+/// kept.
+#[derive(Clone, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub struct TaskwarriorTask {
+    /// The unique ID of the task.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uuid: Option<String>,
+    /// The description of the task.
+    #[serde(default)]
+    pub description: String,
+    /// The status of the task, like `pending`, `completed` or `recurring`.
+    #[serde(default)]
+    pub status: String,
+    /// When the task is due, like `20240301T090000Z`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due: Option<String>,
+    /// How often the task recurs, like `weekly`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recur: Option<String>,
+    /// When a recurring task stops recurring.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until: Option<String>,
+    /// The recurring task this task is an instance of.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+}
+
+/// Reads reminders from a Taskwarrior export, either as a JSON array or as one
+/// task per line. Tasks that cannot be represented as reminders are skipped
+/// and reported as warnings.
+pub fn import_taskwarrior(source: &str) -> Result<Converted<Vec<Reminder>>, serde_json::Error> {
+    let tasks: Vec<TaskwarriorTask> = match source.trim_start().starts_with('[') {
+        true => serde_json::from_str(source)?,
+        false => serde_json::Deserializer::from_str(source)
+            .into_iter()
+            .collect::<Result<_, _>>()?,
+    };
+
+    let mut converted = Converted::new(Vec::new());
+    for (index, task) in tasks.iter().enumerate() {
+        let mut warn = |message: String| {
+            converted.warnings.push(format!(
+                "task {} ({:?}): {message}",
+                index + 1,
+                task.description
+            ));
+        };
+        match import_task(task, &mut warn) {
+            Ok(reminder) => converted.value.push(reminder),
+            Err(error) => warn(format!("skipped: {error}")),
+        }
+    }
+
+    Ok(converted)
+}
+
+/// Writes reminders as a Taskwarrior export that `task import` accepts.
+/// Recurring reminders are due on their first occurrence on or after `today`.
+/// Reminders that cannot be represented are skipped and reported as warnings.
+pub fn export_taskwarrior(
+    reminders: &[Reminder],
+    today: NaiveDate,
+) -> Result<Converted<String>, serde_json::Error> {
+    let mut tasks = Vec::new();
+    let mut warnings = Vec::new();
+    for reminder in reminders {
+        let name = format!("reminder {} ({:?})", reminder.id, reminder.message);
+        let parts = match &reminder.frequency {
+            ReminderFrequency::Weekly(weekly) if weekly.days == ReminderDaysOfWeek::WEEKDAYS => {
+                vec![reminder.frequency.clone()]
+            }
+            frequency => split_frequency(frequency),
+        };
+        if parts.len() > 1 {
+            warnings.push(format!(
+                "{name}: split into {} tasks because Taskwarrior only recurs on one day",
+                parts.len()
+            ));
+        }
+
+        let time = match *reminder.frequency.time_of_day() {
+            ReminderTimeOfDay::AllDay => NaiveTime::default(),
+            ReminderTimeOfDay::Time { time } => time,
+            ReminderTimeOfDay::TimeRange { start, .. } => {
+                warnings.push(format!("{name}: only the start of its time range is kept"));
+                start
+            }
+        };
+
+        let mut written = false;
+        for part in parts {
+            let Some(date) = first_date_from(&part, today) else {
+                continue;
+            };
+
+            let recur = match &part {
+                ReminderFrequency::Once(_) => None,
+                ReminderFrequency::Daily(_) => Some("daily"),
+                ReminderFrequency::Weekly(weekly) => {
+                    match weekly.days == ReminderDaysOfWeek::WEEKDAYS {
+                        true => Some("weekdays"),
+                        false => Some("weekly"),
+                    }
+                }
+                ReminderFrequency::Monthly(_) => Some("monthly"),
+                ReminderFrequency::Yearly(_) => Some("yearly"),
+            };
+            let status = match (reminder.completed, recur) {
+                (true, _) => "completed",
+                (false, Some(_)) => "recurring",
+                (false, None) => "pending",
+            };
+            tasks.push(TaskwarriorTask {
+                description: reminder.message.clone(),
+                status: status.to_string(),
+                due: Some(format_date_time(date.and_time(time))),
+                recur: recur.map(ToString::to_string),
+                ..Default::default()
+            });
+            written = true;
+        }
+        if !written {
+            warnings.push(format!("{name}: skipped: it never occurs"));
+        }
+    }
+
+    Ok(Converted {
+        value: serde_json::to_string_pretty(&tasks)? + "\n",
+        warnings,
+    })
+}
+
+/// Converts a Taskwarrior task into a reminder.
+fn import_task(task: &TaskwarriorTask, warn: &mut impl FnMut(String)) -> Result<Reminder, String> {
+    if task.parent.is_some() {
+        return Err("it is an instance of a recurring task".to_string());
+    }
+    let completed = match task.status.as_str() {
+        "completed" => true,
+        "deleted" => return Err("it was deleted".to_string()),
+        _ => false,
+    };
+    if task.description.is_empty() {
+        return Err("it has no description".to_string());
+    }
+
+    let due = task.due.as_deref().ok_or("it has no due date")?;
+    let due = parse_date_time(due).ok_or_else(|| format!("invalid due date {due:?}"))?;
+    let date = due.date();
+    let time = match due.time() == NaiveTime::default() {
+        true => ReminderTimeOfDay::AllDay,
+        false => ReminderTimeOfDay::Time { time: due.time() },
+    };
+
+    let frequency = match task.recur.as_deref() {
+        Some(recur) => {
+            if task.until.is_some() {
+                warn("its until date is ignored".to_string());
+            }
+            frequency_from_recur(recur, date, time).unwrap_or_else(|| {
+                warn(format!(
+                    "recur {recur:?} is not supported, so it only occurs once"
+                ));
+                ReminderFrequency::Once(ReminderFrequencyOnce { date, time })
+            })
+        }
+        None => ReminderFrequency::Once(ReminderFrequencyOnce { date, time }),
+    };
+
+    Ok(Reminder {
+        id: Default::default(),
+        frequency,
+        message: task.description.clone(),
+        completed,
+        snoozed_until: None,
+        actions: Vec::new(),
+        contact: None,
+    })
+}
+
+/// Converts a `recur` value like `weekly` into a frequency starting on `date`.
+fn frequency_from_recur(
+    recur: &str,
+    date: NaiveDate,
+    time: ReminderTimeOfDay,
+) -> Option<ReminderFrequency> {
+    let frequency = match recur.to_ascii_lowercase().as_str() {
+        "daily" | "day" | "1d" | "p1d" => ReminderFrequency::Daily(ReminderFrequencyDaily { time }),
+        "weekly" | "week" | "1w" | "1wk" | "7d" | "p1w" | "p7d" => {
+            ReminderFrequency::Weekly(ReminderFrequencyWeekly {
+                days: ReminderDaysOfWeek::from(date.weekday()),
+                time,
+            })
+        }
+        "weekdays" => ReminderFrequency::Weekly(ReminderFrequencyWeekly {
+            days: ReminderDaysOfWeek::WEEKDAYS,
+            time,
+        }),
+        "monthly" | "month" | "1mo" | "p1m" => {
+            ReminderFrequency::Monthly(ReminderFrequencyMonthly {
+                dates: vec![date.day() as u8],
+                time,
+            })
+        }
+        "yearly" | "annual" | "year" | "1y" | "1yr" | "p1y" | "12mo" => {
+            ReminderFrequency::Yearly(ReminderFrequencyYearly {
+                dates: vec![ReminderYearlyDate {
+                    month: ReminderMonth::from_number(date.month())?,
+                    date: date.day() as u8,
+                }],
+                time,
+            })
+        }
+        _ => return None,
+    };
+
+    Some(frequency)
+}
+
+/// Parses a UTC date and time like `20240301T090000Z` into local time.
+fn parse_date_time(value: &str) -> Option<NaiveDateTime> {
+    let utc = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%SZ")
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%SZ"))
+        .ok()?;
+    Some(
+        Utc.from_utc_datetime(&utc)
+            .with_timezone(&Local)
+            .naive_local(),
+    )
+}
+
+/// Formats a local date and time like `20240301T090000Z`.
+fn format_date_time(local: NaiveDateTime) -> String {
+    let utc = match Local.from_local_datetime(&local).earliest() {
+        Some(local) => local.with_timezone(&Utc).naive_utc(),
+        None => local,
+    };
+    utc.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use crate::{formats::test_helpers::reminder, models::reminders::ReminderFrequency};
+
+    use super::{export_taskwarrior, format_date_time, import_taskwarrior, TaskwarriorTask};
+
+    /// Gets a due date as Taskwarrior writes it, in UTC.
+    fn due(date: &str, time: &str) -> String {
+        let local = format!("{date}T{time}").parse().unwrap();
+        format_date_time(local)
+    }
+
+    #[test]
+    fn imports_export() {
+        let source = serde_json::json!([
+            {
+                "id": 1,
+                "uuid": "a",
+                "description": "Dentist",
+                "status": "pending",
+                "entry": "20240301T120000Z",
+                "due": due("2024-03-05", "14:00:00"),
+                "urgency": 8.9
+            },
+            {
+                "uuid": "b",
+                "description": "Water plants",
+                "status": "recurring",
+                "due": due("2024-03-04", "00:00:00"),
+                "recur": "weekly",
+                "mask": "--"
+            },
+            {
+                "uuid": "c",
+                "description": "Water plants",
+                "status": "pending",
+                "due": due("2024-03-04", "00:00:00"),
+                "recur": "weekly",
+                "parent": "b",
+                "imask": 0
+            },
+            {
+                "uuid": "d",
+                "description": "File taxes",
+                "status": "completed",
+                "due": due("2024-04-15", "17:00:00"),
+                "end": "20240410T120000Z"
+            },
+            {
+                "uuid": "e",
+                "description": "Standup",
+                "status": "recurring",
+                "due": due("2024-03-04", "09:30:00"),
+                "recur": "weekdays",
+                "until": "20241231T000000Z"
+            },
+            {
+                "uuid": "f",
+                "description": "Sync",
+                "status": "recurring",
+                "due": due("2024-03-04", "10:00:00"),
+                "recur": "biweekly"
+            },
+            { "uuid": "g", "description": "Old", "status": "deleted", "due": due("2024-03-04", "10:00:00") },
+            { "uuid": "h", "description": "Someday", "status": "pending" },
+            { "uuid": "i", "description": "Broken", "status": "pending", "due": "tomorrow" },
+            {
+                "uuid": "j",
+                "description": "Rent",
+                "status": "recurring",
+                "due": "2024-03-01T09:00:00Z",
+                "recur": "P1M"
+            }
+        ]);
+        let imported = import_taskwarrior(&source.to_string()).unwrap();
+        let reminders: Vec<_> = imported
+            .value
+            .iter()
+            .map(|reminder| {
+                (
+                    reminder.message.as_str(),
+                    reminder.frequency.clone(),
+                    reminder.completed,
+                )
+            })
+            .collect();
+        let schedule = |schedule: &str| schedule.parse::<ReminderFrequency>().unwrap();
+        assert_eq!(reminders.len(), 6);
+        assert_eq!(
+            reminders[..5],
+            [
+                ("Dentist", schedule("once 2024-03-05 @ 14:00"), false),
+                ("Water plants", schedule("weekly mon @ all-day"), false),
+                ("File taxes", schedule("once 2024-04-15 @ 17:00"), true),
+                ("Standup", schedule("weekly weekdays @ 09:30"), false),
+                ("Sync", schedule("once 2024-03-04 @ 10:00"), false),
+            ]
+        );
+        // The dashed form is also read, though the day depends on the time zone
+        assert!(matches!(
+            reminders[5],
+            ("Rent", ReminderFrequency::Monthly(_), false)
+        ));
+
+        assert_eq!(
+            imported.warnings,
+            [
+                "task 3 (\"Water plants\"): skipped: it is an instance of a recurring task",
+                "task 5 (\"Standup\"): its until date is ignored",
+                "task 6 (\"Sync\"): recur \"biweekly\" is not supported, so it only occurs once",
+                "task 7 (\"Old\"): skipped: it was deleted",
+                "task 8 (\"Someday\"): skipped: it has no due date",
+                "task 9 (\"Broken\"): skipped: invalid due date \"tomorrow\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn imports_one_task_per_line() {
+        let source = format!(
+            "{{\"description\":\"A\",\"status\":\"pending\",\"due\":\"{}\"}}\n\
+             {{\"description\":\"B\",\"status\":\"completed\",\"due\":\"{}\"}}\n",
+            due("2024-03-05", "09:00:00"),
+            due("2024-03-06", "00:00:00"),
+        );
+        let imported = import_taskwarrior(&source).unwrap();
+        let reminders: Vec<_> = imported
+            .value
+            .iter()
+            .map(|reminder| (reminder.message.as_str(), reminder.completed))
+            .collect();
+        assert_eq!(reminders, [("A", false), ("B", true)]);
+        assert!(import_taskwarrior("[{\"description\": 1}]").is_err());
+    }
+
+    #[test]
+    fn writes_statuses() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let reminders = [
+            reminder(1, "once 2024-03-05 @ 14:00", false),
+            reminder(2, "weekly weekdays @ all-day", false),
+            reminder(3, "monthly 15 @ 08:00-09:00", true),
+        ];
+        let exported = export_taskwarrior(&reminders, today).unwrap();
+        assert_eq!(
+            exported.warnings,
+            ["reminder 3 (\"Reminder 3\"): only the start of its time range is kept"]
+        );
+
+        let tasks: Vec<TaskwarriorTask> = serde_json::from_str(&exported.value).unwrap();
+        let task =
+            |description: &str, status: &str, due: String, recur: Option<&str>| TaskwarriorTask {
+                description: description.into(),
+                status: status.into(),
+                due: Some(due),
+                recur: recur.map(Into::into),
+                ..Default::default()
+            };
+        assert_eq!(
+            tasks,
+            [
+                task("Reminder 1", "pending", due("2024-03-05", "14:00:00"), None),
+                task(
+                    "Reminder 2",
+                    "recurring",
+                    due("2024-03-01", "00:00:00"),
+                    Some("weekdays")
+                ),
+                task(
+                    "Reminder 3",
+                    "completed",
+                    due("2024-03-15", "08:00:00"),
+                    Some("monthly")
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let schedules = [
+            "once 2024-03-05 @ 12:30",
+            "once 2024-03-05 @ all-day",
+            "daily @ 09:00",
+            "weekly sat @ all-day",
+            "weekly weekdays @ 07:45",
+            "monthly 31 @ 08:00",
+            "yearly 02-29 @ all-day",
+            "yearly 12-25 @ 09:00",
+        ];
+        for completed in [false, true] {
+            for schedule in schedules {
+                let original = reminder(1, schedule, completed);
+                let exported = export_taskwarrior(std::slice::from_ref(&original), today).unwrap();
+                assert_eq!(exported.warnings, Vec::<String>::new(), "{schedule}");
+
+                let imported = import_taskwarrior(&exported.value).unwrap();
+                assert_eq!(imported.warnings, Vec::<String>::new(), "{schedule}");
+                let [reminder] = imported.value.as_slice() else {
+                    panic!("{schedule}: expected one reminder: {:?}", imported.value);
+                };
+                assert_eq!(reminder.frequency, original.frequency, "{schedule}");
+                assert_eq!(reminder.message, original.message);
+                assert_eq!(reminder.completed, completed, "{schedule}");
+            }
+        }
+    }
+}
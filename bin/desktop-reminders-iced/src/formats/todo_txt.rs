@@ -0,0 +1,443 @@
+use chrono::{Datelike, NaiveDate};
+
+use crate::{
+    formats::{first_date_from, split_frequency, Converted},
+    models::reminders::{
+        Reminder, ReminderDaysOfWeek, ReminderFrequency, ReminderFrequencyDaily,
+        ReminderFrequencyMonthly, ReminderFrequencyOnce, ReminderFrequencyWeekly,
+        ReminderFrequencyYearly, ReminderMonth, ReminderTimeOfDay, ReminderYearlyDate,
+    },
+};
+
+/// Reads reminders from the lines of a todo.txt file, using their `due:` and
+/// `rec:` tags. Lines that cannot be represented as reminders are skipped and
+/// reported as warnings.
+pub fn import_todo_txt(source: &str) -> Converted<Vec<Reminder>> {
+    let mut converted = Converted::new(Vec::new());
+    for (index, line) in source.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let task = TodoTxtTask::parse(line);
+        let mut warn = |message: String| {
+            converted.warnings.push(format!(
+                "line {}: {:?}: {message}",
+                index + 1,
+                task.description
+            ));
+        };
+        match import_task(&task, &mut warn) {
+            Ok(reminder) => converted.value.push(reminder),
+            Err(error) => warn(format!("skipped: {error}")),
+        }
+    }
+
+    converted
+}
+
+/// Writes reminders as todo.txt lines with `due:` and `rec:` tags. Recurring
+/// reminders are due on their first occurrence on or after `today`. Reminders
+/// that cannot be represented are skipped and reported as warnings.
+pub fn export_todo_txt(reminders: &[Reminder], today: NaiveDate) -> Converted<String> {
+    let mut output = String::new();
+    let mut warnings = Vec::new();
+    for reminder in reminders {
+        let name = format!("reminder {} ({:?})", reminder.id, reminder.message);
+        let parts = match &reminder.frequency {
+            ReminderFrequency::Weekly(weekly) if weekly.days == ReminderDaysOfWeek::WEEKDAYS => {
+                vec![reminder.frequency.clone()]
+            }
+            frequency => split_frequency(frequency),
+        };
+        if parts.len() > 1 {
+            warnings.push(format!(
+                "{name}: split into {} tasks because rec: only repeats on one day",
+                parts.len()
+            ));
+        }
+        if *reminder.frequency.time_of_day() != ReminderTimeOfDay::AllDay {
+            warnings.push(format!("{name}: its time of day is not kept"));
+        }
+
+        let description = reminder.message.lines().collect::<Vec<_>>().join(" ");
+        if description != reminder.message {
+            warnings.push(format!("{name}: its lines were joined into one task"));
+        }
+
+        let mut written = false;
+        for part in parts {
+            let Some(due) = first_date_from(&part, today) else {
+                continue;
+            };
+
+            if reminder.completed {
+                output.push_str("x ");
+            }
+            output.push_str(&format!("{description} due:{}", due.format("%Y-%m-%d")));
+            let rec = match &part {
+                ReminderFrequency::Once(_) => None,
+                ReminderFrequency::Daily(_) => Some('d'),
+                ReminderFrequency::Weekly(weekly) => {
+                    match weekly.days == ReminderDaysOfWeek::WEEKDAYS {
+                        true => Some('b'),
+                        false => Some('w'),
+                    }
+                }
+                ReminderFrequency::Monthly(_) => Some('m'),
+                ReminderFrequency::Yearly(_) => Some('y'),
+            };
+            if let Some(unit) = rec {
+                output.push_str(&format!(" rec:+1{unit}"));
+            }
+            output.push('\n');
+            written = true;
+        }
+        if !written {
+            warnings.push(format!("{name}: skipped: it never occurs"));
+        }
+    }
+
+    Converted {
+        value: output,
+        warnings,
+    }
+}
+
+/// A task from a line of a todo.txt file, like
+/// `(A) Pay rent +home due:2024-03-01 rec:+1m`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TodoTxtTask {
+    /// Whether the task starts with `x`, meaning it is done.
+    pub completed: bool,
+    /// The description of the task, without its priority, dates, `due:` or
+    /// `rec:`. Projects, contexts and other tags are kept.
+    pub description: String,
+    /// The value of the `due:` tag, if any.
+    pub due: Option<String>,
+    /// The value of the `rec:` tag, if any.
+    pub rec: Option<String>,
+}
+
+impl TodoTxtTask {
+    /// Parses a line of a todo.txt file.
+    pub fn parse(line: &str) -> Self {
+        let mut words = line.split_whitespace().peekable();
+        let completed = words.next_if_eq(&"x").is_some();
+        if !completed {
+            words.next_if(|word| is_priority(word));
+        }
+
+        // Completed tasks can have a completion date and a creation date,
+        // while others can only have a creation date
+        let dates = if completed { 2 } else { 1 };
+        for _ in 0..dates {
+            words.next_if(|word| NaiveDate::parse_from_str(word, "%Y-%m-%d").is_ok());
+        }
+
+        let mut task = TodoTxtTask {
+            completed,
+            description: String::new(),
+            due: None,
+            rec: None,
+        };
+        let mut description = Vec::new();
+        for word in words {
+            if let Some(due) = word.strip_prefix("due:") {
+                task.due = Some(due.to_string());
+            } else if let Some(rec) = word.strip_prefix("rec:") {
+                task.rec = Some(rec.to_string());
+            } else {
+                description.push(word);
+            }
+        }
+        task.description = description.join(" ");
+
+        task
+    }
+}
+
+/// Checks whether a word is a priority, like `(A)`.
+fn is_priority(word: &str) -> bool {
+    let bytes = word.as_bytes();
+    bytes.len() == 3 && bytes[0] == b'(' && bytes[1].is_ascii_uppercase() && bytes[2] == b')'
+}
+
+/// Converts a todo.txt task into a reminder.
+fn import_task(task: &TodoTxtTask, warn: &mut impl FnMut(String)) -> Result<Reminder, String> {
+    if task.description.is_empty() {
+        return Err("it has no description".to_string());
+    }
+
+    let due = task.due.as_deref().ok_or("it has no due: date")?;
+    let date = NaiveDate::parse_from_str(due, "%Y-%m-%d")
+        .map_err(|_| format!("invalid due: date {due:?}"))?;
+    let time = ReminderTimeOfDay::AllDay;
+    let once = ReminderFrequency::Once(ReminderFrequencyOnce { date, time });
+
+    let frequency = match task.rec.as_deref() {
+        Some(rec) => match frequency_from_rec(rec, date) {
+            Some((frequency, strict)) => {
+                if !strict {
+                    warn(format!(
+                        "rec:{rec} repeats from when it is done, but it is imported as a fixed \
+                         schedule"
+                    ));
+                }
+                frequency
+            }
+            None => {
+                warn(format!(
+                    "rec:{rec} is not supported, so it only occurs once"
+                ));
+                once
+            }
+        },
+        None => once,
+    };
+
+    Ok(Reminder {
+        id: Default::default(),
+        frequency,
+        message: task.description.clone(),
+        completed: task.completed,
+        snoozed_until: None,
+        actions: Vec::new(),
+        contact: None,
+    })
+}
+
+/// Converts a `rec:` value like `+1w` into an all-day frequency starting on
+/// `date`, along with whether it repeats from the due date rather than from
+/// when it is done.
+fn frequency_from_rec(rec: &str, date: NaiveDate) -> Option<(ReminderFrequency, bool)> {
+    let (strict, rest) = match rec.strip_prefix('+') {
+        Some(rest) => (true, rest),
+        None => (false, rec),
+    };
+    let (amount, unit) = rest.split_at(rest.char_indices().last()?.0);
+    let amount: u32 = match amount {
+        "" => 1,
+        amount => amount.parse().ok()?,
+    };
+
+    let time = ReminderTimeOfDay::AllDay;
+    let frequency = match (unit, amount) {
+        ("d", 1) => ReminderFrequency::Daily(ReminderFrequencyDaily { time }),
+        ("w", 1) | ("d", 7) => ReminderFrequency::Weekly(ReminderFrequencyWeekly {
+            days: ReminderDaysOfWeek::from(date.weekday()),
+            time,
+        }),
+        ("b", 1) => ReminderFrequency::Weekly(ReminderFrequencyWeekly {
+            days: ReminderDaysOfWeek::WEEKDAYS,
+            time,
+        }),
+        ("m", 1) => ReminderFrequency::Monthly(ReminderFrequencyMonthly {
+            dates: vec![date.day() as u8],
+            time,
+        }),
+        ("y", 1) | ("m", 12) => ReminderFrequency::Yearly(ReminderFrequencyYearly {
+            dates: vec![ReminderYearlyDate {
+                month: ReminderMonth::from_number(date.month())?,
+                date: date.day() as u8,
+            }],
+            time,
+        }),
+        _ => return None,
+    };
+
+    Some((frequency, strict))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use crate::{
+        formats::test_helpers::reminder,
+        models::reminders::{Reminder, ReminderFrequency},
+    };
+
+    use super::{export_todo_txt, import_todo_txt, TodoTxtTask};
+
+    /// A todo.txt file using the `due:` and `rec:` extensions.
+    const TODO_TXT: &str = "\
+(A) 2024-03-01 Pay rent +home due:2024-03-01 rec:+1m
+Water plants @home due:2024-03-04 rec:1w
+x 2024-03-02 2024-03-01 File taxes due:2024-04-15
+
+Stretch due:2024-03-04 rec:+d
+Standup +work due:2024-03-04 rec:+b
+Birthday due:2024-06-15 rec:+1y
+Review due:2024-03-04 rec:+2w
+No due date
+Broken due:2024-13-01
+due:2024-03-01
+Typo due:2024-03-01 rec:é
+";
+
+    /// Adds a project and a context to a reminder's message.
+    fn tagged(reminder: Reminder) -> Reminder {
+        Reminder {
+            message: format!("{} +project @context", reminder.message),
+            ..reminder
+        }
+    }
+
+    #[test]
+    fn parses_tasks() {
+        let task = TodoTxtTask::parse(
+            "x 2024-03-02 2024-03-01 Call plumber +home @phone due:2024-03-05 pri:A",
+        );
+        assert_eq!(
+            task,
+            TodoTxtTask {
+                completed: true,
+                description: "Call plumber +home @phone pri:A".into(),
+                due: Some("2024-03-05".into()),
+                rec: None,
+            }
+        );
+
+        let task = TodoTxtTask::parse("(B) 2024-03-01 Pay rent rec:+1m due:2024-03-01");
+        assert_eq!(
+            task,
+            TodoTxtTask {
+                completed: false,
+                description: "Pay rent".into(),
+                due: Some("2024-03-01".into()),
+                rec: Some("+1m".into()),
+            }
+        );
+
+        // Only a lone "x" marks a task as done
+        let task = TodoTxtTask::parse("xylophone lesson due:2024-03-05");
+        assert!(!task.completed);
+        assert_eq!(task.description, "xylophone lesson");
+    }
+
+    #[test]
+    fn imports_tasks() {
+        let imported = import_todo_txt(TODO_TXT);
+        let reminders: Vec<_> = imported
+            .value
+            .iter()
+            .map(|reminder| {
+                (
+                    reminder.message.as_str(),
+                    reminder.frequency.clone(),
+                    reminder.completed,
+                )
+            })
+            .collect();
+        let schedule = |schedule: &str| schedule.parse::<ReminderFrequency>().unwrap();
+        assert_eq!(
+            reminders,
+            [
+                ("Pay rent +home", schedule("monthly 1 @ all-day"), false),
+                (
+                    "Water plants @home",
+                    schedule("weekly mon @ all-day"),
+                    false
+                ),
+                ("File taxes", schedule("once 2024-04-15 @ all-day"), true),
+                ("Stretch", schedule("daily @ all-day"), false),
+                (
+                    "Standup +work",
+                    schedule("weekly weekdays @ all-day"),
+                    false
+                ),
+                ("Birthday", schedule("yearly 06-15 @ all-day"), false),
+                ("Review", schedule("once 2024-03-04 @ all-day"), false),
+                ("Typo", schedule("once 2024-03-01 @ all-day"), false),
+            ]
+        );
+
+        assert_eq!(
+            imported.warnings,
+            [
+                "line 2: \"Water plants @home\": rec:1w repeats from when it is done, but it is \
+                 imported as a fixed schedule",
+                "line 8: \"Review\": rec:+2w is not supported, so it only occurs once",
+                "line 9: \"No due date\": skipped: it has no due: date",
+                "line 10: \"Broken\": skipped: invalid due: date \"2024-13-01\"",
+                "line 11: \"\": skipped: it has no description",
+                "line 12: \"Typo\": rec:é is not supported, so it only occurs once",
+            ]
+        );
+    }
+
+    #[test]
+    fn writes_tasks() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let reminders = [
+            tagged(reminder(1, "once 2024-03-05 @ all-day", true)),
+            tagged(reminder(2, "weekly weekdays @ all-day", false)),
+            tagged(reminder(3, "monthly 15 @ all-day", false)),
+        ];
+        let exported = export_todo_txt(&reminders, today);
+        assert_eq!(exported.warnings, Vec::<String>::new());
+        assert_eq!(
+            exported.value,
+            "\
+x Reminder 1 +project @context due:2024-03-05
+Reminder 2 +project @context due:2024-03-01 rec:+1b
+Reminder 3 +project @context due:2024-03-15 rec:+1m
+"
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let schedules = [
+            "once 2024-03-05 @ all-day",
+            "daily @ all-day",
+            "weekly sat @ all-day",
+            "weekly weekdays @ all-day",
+            "monthly 31 @ all-day",
+            "yearly 02-29 @ all-day",
+        ];
+        for completed in [false, true] {
+            for schedule in schedules {
+                let original = tagged(reminder(1, schedule, completed));
+                let exported = export_todo_txt(std::slice::from_ref(&original), today);
+                assert_eq!(exported.warnings, Vec::<String>::new(), "{schedule}");
+
+                let imported = import_todo_txt(&exported.value);
+                assert_eq!(imported.warnings, Vec::<String>::new(), "{schedule}");
+                let [reminder] = imported.value.as_slice() else {
+                    panic!("{schedule}: expected one reminder: {:?}", imported.value);
+                };
+                assert_eq!(reminder.frequency, original.frequency, "{schedule}");
+                assert_eq!(reminder.message, original.message);
+                assert_eq!(reminder.completed, completed, "{schedule}");
+            }
+        }
+    }
+
+    #[test]
+    fn warns_about_what_is_lost() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let exported = export_todo_txt(
+            &[tagged(reminder(1, "weekly mon,wed @ 09:00", false))],
+            today,
+        );
+        assert_eq!(
+            exported.value,
+            "\
+Reminder 1 +project @context due:2024-03-04 rec:+1w
+Reminder 1 +project @context due:2024-03-06 rec:+1w
+"
+        );
+        assert_eq!(
+            exported.warnings,
+            [
+                "reminder 1 (\"Reminder 1 +project @context\"): split into 2 tasks because rec: \
+                 only repeats on one day",
+                "reminder 1 (\"Reminder 1 +project @context\"): its time of day is not kept",
+            ]
+        );
+    }
+}
@@ -0,0 +1,755 @@
+use std::{
+    collections::HashSet,
+    fmt::{Display, Formatter},
+    ops::Range,
+};
+
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+use serde::{Deserialize, Serialize};
+use toml::{value::Datetime, Spanned};
+
+use crate::models::{
+    reminders::{
+        Reminder, ReminderAction, ReminderAlert, ReminderCompletion, ReminderContact,
+        ReminderDaysOfWeek, ReminderFrequency, ReminderFrequencyDaily, ReminderFrequencyMonthly,
+        ReminderFrequencyOnce, ReminderFrequencyWeekly, ReminderFrequencyYearly, ReminderId,
+        ReminderMonth, ReminderTimeOfDay, ReminderYearlyDate,
+    },
+    store::ReminderStore,
+};
+
+/// The short names of the days of the week, in order.
+const DAY_NAMES: [(ReminderDaysOfWeek, &str); 7] = [
+    (ReminderDaysOfWeek::MONDAY, "mon"),
+    (ReminderDaysOfWeek::TUESDAY, "tue"),
+    (ReminderDaysOfWeek::WEDNESDAY, "wed"),
+    (ReminderDaysOfWeek::THURSDAY, "thu"),
+    (ReminderDaysOfWeek::FRIDAY, "fri"),
+    (ReminderDaysOfWeek::SATURDAY, "sat"),
+    (ReminderDaysOfWeek::SUNDAY, "sun"),
+];
+
+/// An error reading a TOML file, with the position it happened at if known.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TomlError {
+    /// What went wrong.
+    pub message: String,
+    /// The bytes of the file the error is about.
+    pub span: Option<Range<usize>>,
+}
+
+impl TomlError {
+    /// Gets the line and column the error starts at, both starting from 1.
+    pub fn position(&self, source: &str) -> Option<(usize, usize)> {
+        let start = self.span.as_ref()?.start.min(source.len());
+        let before = source.get(..start)?;
+        let line = before.matches('\n').count() + 1;
+        let column = before
+            .rsplit('\n')
+            .next()
+            .map_or(0, |line| line.chars().count())
+            + 1;
+        Some((line, column))
+    }
+}
+
+impl Display for TomlError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for TomlError {}
+
+impl From<toml::de::Error> for TomlError {
+    fn from(error: toml::de::Error) -> Self {
+        Self {
+            message: error.message().trim().to_string(),
+            span: error.span(),
+        }
+    }
+}
+
+/// Reads a store from TOML.
+pub fn store_from_toml(source: &str) -> Result<ReminderStore, TomlError> {
+    let store: TomlStore = toml::from_str(source)?;
+    let mut reminders = Vec::with_capacity(store.reminders.len());
+    let mut ids = HashSet::new();
+    for reminder in store.reminders {
+        let span = reminder.span();
+        let reminder = reminder
+            .into_inner()
+            .into_reminder()
+            .map_err(|message| TomlError {
+                message,
+                span: Some(span.clone()),
+            })?;
+        if reminder.id != ReminderId(0) && !ids.insert(reminder.id) {
+            return Err(TomlError {
+                message: format!(
+                    "reminder {}: another reminder has the same `id`",
+                    reminder.id
+                ),
+                span: Some(span),
+            });
+        }
+        reminders.push(reminder);
+    }
+
+    let mut store = ReminderStore {
+        last_seen: store.last_seen,
+        reminders,
+        queued_alerts: store.queued_alerts,
+        completions: store.completions,
+        last_digest: store.last_digest,
+    };
+
+    // Reminders written without an `id` are given unused ones
+    for index in 0..store.reminders.len() {
+        if store.reminders[index].id == ReminderId(0) {
+            store.reminders[index].id = store.next_id();
+        }
+    }
+
+    Ok(store)
+}
+
+/// Writes a store as TOML.
+pub fn store_to_toml(store: &ReminderStore) -> Result<String, toml::ser::Error> {
+    let store = TomlStore {
+        last_seen: store.last_seen,
+        last_digest: store.last_digest,
+        reminders: store
+            .reminders
+            .iter()
+            .map(|reminder| Spanned::new(0..0, TomlReminder::from(reminder)))
+            .collect(),
+        queued_alerts: store.queued_alerts.clone(),
+        completions: store.completions.clone(),
+    };
+    toml::to_string(&store)
+}
+
+/// A [`ReminderStore`] as written in TOML.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TomlStore {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_seen: Option<DateTime<Local>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_digest: Option<NaiveDate>,
+    #[serde(default)]
+    reminders: Vec<Spanned<TomlReminder>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    queued_alerts: Vec<ReminderAlert>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    completions: Vec<ReminderCompletion>,
+}
+
+/// A [`Reminder`] as written in TOML, like:
+///
+/// ```toml
+/// [[reminders]]
+/// id = 1
+/// message = "Standup"
+/// repeat = "weekly"
+/// days = ["mon", "wed"]
+/// time = "12:30"
+/// ```
+///
+/// Reminders that last a range of time use `from` and `until` instead of
+/// `time`, and reminders without either last all day. Instead of `repeat` and
+/// the fields that go with it, a reminder can have a `schedule`, like
+/// `schedule = "weekly mon,wed @ 12:30"`. Reminders without an `id` are
+/// given an unused one when the file is read.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TomlReminder {
+    #[serde(default)]
+    id: ReminderId,
+    message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    schedule: Option<TomlSchedule>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    repeat: Option<TomlRepeat>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    date: Option<Datetime>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    days: Option<Vec<TomlDay>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    days_of_month: Option<Vec<u8>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    dates: Option<Vec<TomlYearlyDate>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    time: Option<TomlTime>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    from: Option<TomlTime>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    until: Option<TomlTime>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    completed: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    snoozed_until: Option<NaiveDateTime>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    actions: Vec<ReminderAction>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    contact: Option<ReminderContact>,
+}
+
+impl TomlReminder {
+    /// Converts the reminder, which either has a `schedule` or uses `repeat`
+    /// and the fields that go with it.
+    fn into_reminder(mut self) -> Result<Reminder, String> {
+        let name = match self.id {
+            ReminderId(0) => format!("reminder {:?}", self.message),
+            id => format!("reminder {id}"),
+        };
+        let frequency = match (self.schedule.take(), self.repeat) {
+            (Some(schedule), None) => {
+                let fields = [
+                    ("date", self.date.is_some()),
+                    ("days", self.days.is_some()),
+                    ("days_of_month", self.days_of_month.is_some()),
+                    ("dates", self.dates.is_some()),
+                    ("time", self.time.is_some()),
+                    ("from", self.from.is_some()),
+                    ("until", self.until.is_some()),
+                ];
+                if let Some((field, _)) = fields.iter().find(|&&(_, present)| present) {
+                    return Err(format!(
+                        "{name}: `{field}` cannot be used with `schedule`, which already sets it"
+                    ));
+                }
+                schedule.0
+            }
+            (None, Some(repeat)) => self.repeat_frequency(repeat, &name)?,
+            (Some(_), Some(_)) => {
+                return Err(format!("{name}: `repeat` cannot be used with `schedule`"))
+            }
+            (None, None) => return Err(format!("{name}: reminders need `repeat` or `schedule`")),
+        };
+
+        Ok(Reminder {
+            id: self.id,
+            frequency,
+            message: self.message,
+            completed: self.completed,
+            snoozed_until: self.snoozed_until,
+            actions: self.actions,
+            contact: self.contact,
+        })
+    }
+
+    /// Gets the frequency of a reminder that uses `repeat`, checking that it
+    /// has the fields its kind of repetition needs and no others.
+    fn repeat_frequency(
+        &mut self,
+        repeat: TomlRepeat,
+        name: &str,
+    ) -> Result<ReminderFrequency, String> {
+        let unused = |field: &str, used_by: &str| {
+            format!("{name}: `{field}` is only used by {used_by} reminders, not {repeat} ones")
+        };
+        let needs = |field: &str| format!("{name}: {repeat} reminders need `{field}`");
+
+        let fields = [
+            ("date", self.date.is_some(), TomlRepeat::Once),
+            ("days", self.days.is_some(), TomlRepeat::Weekly),
+            (
+                "days_of_month",
+                self.days_of_month.is_some(),
+                TomlRepeat::Monthly,
+            ),
+            ("dates", self.dates.is_some(), TomlRepeat::Yearly),
+        ];
+        for (field, present, used_by) in fields {
+            if present && used_by != repeat {
+                return Err(unused(field, &used_by.to_string()));
+            }
+        }
+
+        let time = match (self.time.take(), self.from.take(), self.until.take()) {
+            (None, None, None) => ReminderTimeOfDay::AllDay,
+            (Some(time), None, None) => ReminderTimeOfDay::Time { time: time.0 },
+            (None, Some(start), Some(end)) => ReminderTimeOfDay::TimeRange {
+                start: start.0,
+                end: end.0,
+            },
+            (Some(_), _, _) => {
+                return Err(format!(
+                    "{name}: `time` cannot be used with `from` and `until`"
+                ))
+            }
+            (None, Some(_), None) => return Err(format!("{name}: `from` needs `until`")),
+            (None, None, Some(_)) => return Err(format!("{name}: `until` needs `from`")),
+        };
+
+        let frequency = match repeat {
+            TomlRepeat::Once => {
+                let date = self.date.take().ok_or_else(|| needs("date"))?;
+                let date = match (date.date, date.time, date.offset) {
+                    (Some(date), None, None) => NaiveDate::from_ymd_opt(
+                        date.year.into(),
+                        date.month.into(),
+                        date.day.into(),
+                    ),
+                    _ => None,
+                }
+                .ok_or_else(|| format!("{name}: `date` must be a date like 2024-03-01"))?;
+                ReminderFrequency::Once(ReminderFrequencyOnce { date, time })
+            }
+            TomlRepeat::Daily => ReminderFrequency::Daily(ReminderFrequencyDaily { time }),
+            TomlRepeat::Weekly => {
+                let days = self
+                    .days
+                    .take()
+                    .ok_or_else(|| needs("days"))?
+                    .into_iter()
+                    .fold(ReminderDaysOfWeek::empty(), |days, day| days | day.0);
+                ReminderFrequency::Weekly(ReminderFrequencyWeekly { days, time })
+            }
+            TomlRepeat::Monthly => {
+                let dates = self
+                    .days_of_month
+                    .take()
+                    .ok_or_else(|| needs("days_of_month"))?;
+                if let Some(date) = dates.iter().find(|date| !(1..=31).contains(*date)) {
+                    return Err(format!(
+                        "{name}: {date} is not a day of the month; expected 1 to 31"
+                    ));
+                }
+                ReminderFrequency::Monthly(ReminderFrequencyMonthly { dates, time })
+            }
+            TomlRepeat::Yearly => {
+                let dates = self.dates.take().ok_or_else(|| needs("dates"))?;
+                let dates = dates
+                    .into_iter()
+                    .map(|date| {
+                        ReminderYearlyDate::new(date.month.0, date.day).ok_or_else(|| {
+                            format!("{name}: {} has no day {}", date.month.0, date.day)
+                        })
+                    })
+                    .collect::<Result<_, _>>()?;
+                ReminderFrequency::Yearly(ReminderFrequencyYearly { dates, time })
+            }
+        };
+
+        Ok(frequency)
+    }
+}
+
+impl From<&Reminder> for TomlReminder {
+    fn from(reminder: &Reminder) -> Self {
+        let mut toml = TomlReminder {
+            id: reminder.id,
+            message: reminder.message.clone(),
+            schedule: None,
+            repeat: Some(TomlRepeat::Once),
+            date: None,
+            days: None,
+            days_of_month: None,
+            dates: None,
+            time: None,
+            from: None,
+            until: None,
+            completed: reminder.completed,
+            snoozed_until: reminder.snoozed_until,
+            actions: reminder.actions.clone(),
+            contact: reminder.contact.clone(),
+        };
+
+        match *reminder.frequency.time_of_day() {
+            ReminderTimeOfDay::AllDay => {}
+            ReminderTimeOfDay::Time { time } => toml.time = Some(TomlTime(time)),
+            ReminderTimeOfDay::TimeRange { start, end } => {
+                toml.from = Some(TomlTime(start));
+                toml.until = Some(TomlTime(end));
+            }
+        }
+
+        match &reminder.frequency {
+            ReminderFrequency::Once(once) => {
+                toml.date = once.date.to_string().parse().ok();
+            }
+            ReminderFrequency::Daily(_) => toml.repeat = Some(TomlRepeat::Daily),
+            ReminderFrequency::Weekly(weekly) => {
+                toml.repeat = Some(TomlRepeat::Weekly);
+                toml.days = Some(
+                    DAY_NAMES
+                        .iter()
+                        .filter(|&&(day, _)| weekly.days.contains(day))
+                        .map(|&(day, _)| TomlDay(day))
+                        .collect(),
+                );
+            }
+            ReminderFrequency::Monthly(monthly) => {
+                toml.repeat = Some(TomlRepeat::Monthly);
+                toml.days_of_month = Some(monthly.dates.clone());
+            }
+            ReminderFrequency::Yearly(yearly) => {
+                toml.repeat = Some(TomlRepeat::Yearly);
+                toml.dates = Some(
+                    yearly
+                        .dates
+                        .iter()
+                        .map(|date| TomlYearlyDate {
+                            month: TomlMonth(date.month),
+                            day: date.date,
+                        })
+                        .collect(),
+                );
+            }
+        }
+
+        toml
+    }
+}
+
+/// How often a reminder repeats.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum TomlRepeat {
+    Once,
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl Display for TomlRepeat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            TomlRepeat::Once => "once",
+            TomlRepeat::Daily => "daily",
+            TomlRepeat::Weekly => "weekly",
+            TomlRepeat::Monthly => "monthly",
+            TomlRepeat::Yearly => "yearly",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// A whole frequency written as a schedule, like
+/// `"weekly mon,wed @ 12:30-16:30"`.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+struct TomlSchedule(ReminderFrequency);
+
+impl TryFrom<String> for TomlSchedule {
+    type Error = String;
+
+    fn try_from(schedule: String) -> Result<Self, Self::Error> {
+        schedule
+            .parse()
+            .map(TomlSchedule)
+            .map_err(|error| format!("invalid schedule {schedule:?}: {error}"))
+    }
+}
+
+impl From<TomlSchedule> for String {
+    fn from(schedule: TomlSchedule) -> Self {
+        schedule.0.to_string()
+    }
+}
+
+/// A day of the year, like `{ month = "march", day = 1 }`.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TomlYearlyDate {
+    month: TomlMonth,
+    day: u8,
+}
+
+/// A day of the week, written like `"mon"`. Full names are also accepted, as
+/// are `"weekdays"` and `"weekends"`.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+struct TomlDay(ReminderDaysOfWeek);
+
+impl TryFrom<String> for TomlDay {
+    type Error = String;
+
+    fn try_from(name: String) -> Result<Self, Self::Error> {
+        match name.parse::<ReminderDaysOfWeek>() {
+            Ok(days) if !name.contains(',') => Ok(TomlDay(days)),
+            _ => Err(format!("unknown day {name:?}, expected a day like \"mon\"")),
+        }
+    }
+}
+
+impl From<TomlDay> for String {
+    fn from(day: TomlDay) -> Self {
+        DAY_NAMES
+            .iter()
+            .find(|&&(flag, _)| flag == day.0)
+            .map_or_else(|| day.0.to_string(), |&(_, short)| short.to_string())
+    }
+}
+
+/// A month, written like `"march"`. Short names like `"mar"` are also
+/// accepted.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+struct TomlMonth(ReminderMonth);
+
+impl TryFrom<String> for TomlMonth {
+    type Error = String;
+
+    fn try_from(name: String) -> Result<Self, Self::Error> {
+        let lower = name.trim().to_ascii_lowercase();
+        ReminderMonth::ALL
+            .into_iter()
+            .find(|month| {
+                let full = month.to_string().to_ascii_lowercase();
+                lower == full || lower.len() == 3 && full.starts_with(&lower)
+            })
+            .map(TomlMonth)
+            .ok_or_else(|| format!("unknown month {name:?}, expected a month like \"march\""))
+    }
+}
+
+impl From<TomlMonth> for String {
+    fn from(month: TomlMonth) -> Self {
+        month.0.to_string().to_ascii_lowercase()
+    }
+}
+
+/// A time of day, written like `"12:30"`.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+struct TomlTime(NaiveTime);
+
+impl TryFrom<String> for TomlTime {
+    type Error = String;
+
+    fn try_from(time: String) -> Result<Self, Self::Error> {
+        NaiveTime::parse_from_str(time.trim(), "%H:%M")
+            .or_else(|_| NaiveTime::parse_from_str(time.trim(), "%H:%M:%S"))
+            .map(TomlTime)
+            .map_err(|_| format!("invalid time {time:?}, expected a time like \"12:30\""))
+    }
+}
+
+impl From<TomlTime> for String {
+    fn from(time: TomlTime) -> Self {
+        match time.0.second() {
+            0 => time.0.format("%H:%M").to_string(),
+            _ => time.0.format("%H:%M:%S").to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Local, NaiveDate, TimeZone};
+
+    use crate::models::{
+        reminders::{
+            Reminder, ReminderAction, ReminderAlert, ReminderCompletion, ReminderContact,
+            ReminderContactDate, ReminderId, ReminderOccurrence,
+        },
+        store::ReminderStore,
+    };
+
+    use super::{store_from_toml, store_to_toml};
+
+    fn reminder(id: u64, schedule: &str) -> Reminder {
+        Reminder {
+            id: ReminderId(id),
+            frequency: schedule.parse().unwrap(),
+            message: format!("Reminder \"{id}\""),
+            completed: false,
+            snoozed_until: None,
+            actions: Vec::new(),
+            contact: None,
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let at = NaiveDate::from_ymd_opt(2024, 3, 1)
+            .unwrap()
+            .and_hms_opt(9, 30, 0)
+            .unwrap();
+        let mut reminders: Vec<_> = [
+            "once 2024-03-01 @ all-day",
+            "once 2024-03-01 @ 12:30",
+            "daily @ 09:00:30",
+            "daily @ 22:00-01:00",
+            "weekly mon,wed,sun @ 12:30-16:30",
+            "weekly none @ all-day",
+            "monthly 1,15,31 @ all-day",
+            "yearly 03-01,12-25 @ 09:00",
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(index, schedule)| reminder(index as u64 + 1, schedule))
+        .collect();
+        reminders[0].completed = true;
+        reminders[1].snoozed_until = Some(at);
+        reminders[2].actions = vec![
+            ReminderAction::Command {
+                command: "echo hi".into(),
+                timeout_secs: Some(5),
+            },
+            ReminderAction::Webhook {
+                url: "https://example.com/hook".into(),
+                timeout_secs: None,
+            },
+        ];
+        reminders[7].contact = Some(ReminderContact {
+            uid: "jane@example.com".into(),
+            date: ReminderContactDate::Birthday,
+        });
+
+        let store = ReminderStore {
+            last_seen: Some(Local.with_ymd_and_hms(2024, 3, 1, 8, 0, 0).unwrap()),
+            reminders,
+            queued_alerts: vec![ReminderAlert {
+                occurrence: ReminderOccurrence {
+                    id: ReminderId(3),
+                    at,
+                },
+                missed: true,
+            }],
+            completions: vec![ReminderCompletion {
+                id: ReminderId(1),
+                at,
+            }],
+            last_digest: NaiveDate::from_ymd_opt(2024, 3, 1),
+        };
+
+        let toml = store_to_toml(&store).unwrap();
+        assert_eq!(store_from_toml(&toml).unwrap(), store, "{toml}");
+    }
+
+    #[test]
+    fn reads_handwritten_files() {
+        let source = r#"
+            [[reminders]]
+            id = 1
+            message = "Standup"
+            repeat = "weekly"
+            days = ["monday", "wed"]
+            time = "12:30"
+
+            [[reminders]]
+            id = 2
+            message = "Taxes"
+            repeat = "yearly"
+            dates = [{ month = "apr", day = 15 }]
+            from = "09:00"
+            until = "17:00"
+
+            [[reminders]]
+            id = 3
+            message = "Dentist"
+            repeat = "once"
+            date = 2024-03-01
+
+            [[reminders]]
+            message = "Stretch"
+            schedule = "daily @ 10:00"
+        "#;
+        let store = store_from_toml(source).unwrap();
+        let schedules: Vec<_> = store
+            .reminders
+            .iter()
+            .map(|reminder| (reminder.message.as_str(), reminder.frequency.to_string()))
+            .collect();
+        assert_eq!(
+            schedules,
+            [
+                ("Standup", "weekly mon,wed @ 12:30".to_string()),
+                ("Taxes", "yearly 04-15 @ 09:00-17:00".to_string()),
+                ("Dentist", "once 2024-03-01 @ all-day".to_string()),
+                ("Stretch", "daily @ 10:00".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn gives_reminders_without_ids_unused_ones() {
+        let source = r#"
+            [[reminders]]
+            message = "Stretch"
+            schedule = "daily @ 10:00"
+
+            [[reminders]]
+            id = 2
+            message = "Standup"
+            schedule = "weekly mon @ 09:00"
+
+            [[reminders]]
+            message = "Water plants"
+            schedule = "weekly sat @ all-day"
+        "#;
+        let store = store_from_toml(source).unwrap();
+        let ids: Vec<_> = store
+            .reminders
+            .iter()
+            .map(|reminder| (reminder.message.as_str(), reminder.id))
+            .collect();
+        assert_eq!(
+            ids,
+            [
+                ("Stretch", ReminderId(3)),
+                ("Standup", ReminderId(2)),
+                ("Water plants", ReminderId(4)),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_errors_with_positions() {
+        let cases = [
+            (
+                "[[reminders]]\nmessage = \"a\"\n",
+                "reminder \"a\": reminders need `repeat` or `schedule`",
+                (1, 1),
+            ),
+            (
+                "[[reminders]]\nid = 4\nmessage = \"a\"\nrepeat = \"weekly\"\n",
+                "reminder 4: weekly reminders need `days`",
+                (1, 1),
+            ),
+            (
+                "\n[[reminders]]\nid = 4\nmessage = \"a\"\nrepeat = \"daily\"\ndays = [\"mon\"]\n",
+                "reminder 4: `days` is only used by weekly reminders, not daily ones",
+                (2, 1),
+            ),
+            (
+                "[[reminders]]\nid = 4\nmessage = \"a\"\nschedule = \"daily @ 9:00\"\ntime = \"09:00\"\n",
+                "reminder 4: `time` cannot be used with `schedule`, which already sets it",
+                (1, 1),
+            ),
+            (
+                "[[reminders]]\nid = 4\nmessage = \"a\"\nrepeat = \"monthly\"\ndays_of_month = [32]\n",
+                "reminder 4: 32 is not a day of the month; expected 1 to 31",
+                (1, 1),
+            ),
+            (
+                "[[reminders]]\nid = 4\nmessage = \"a\"\nrepeat = \"yearly\"\n\
+                 dates = [{ month = \"feb\", day = 31 }]\n",
+                "reminder 4: February has no day 31",
+                (1, 1),
+            ),
+            (
+                "[[reminders]]\nid = 4\nmessage = \"a\"\nschedule = \"daily @ 09:00\"\n\n\
+                 [[reminders]]\nid = 4\nmessage = \"b\"\nschedule = \"daily @ 10:00\"\n",
+                "reminder 4: another reminder has the same `id`",
+                (6, 1),
+            ),
+        ];
+        for (source, message, position) in cases {
+            let error = store_from_toml(source).unwrap_err();
+            assert_eq!(error.message, message, "{source}");
+            assert_eq!(error.position(source), Some(position), "{source}");
+        }
+
+        let source = "[[reminders]]\nmessage = \"a\"\nrepeat = \"daily\"\ntime = \"noon\"\n";
+        let error = store_from_toml(source).unwrap_err();
+        assert!(error.message.contains("invalid time \"noon\""), "{error}");
+        assert_eq!(error.position(source), Some((4, 8)));
+    }
+}
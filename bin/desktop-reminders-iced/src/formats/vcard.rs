@@ -0,0 +1,331 @@
+use chrono::NaiveDate;
+
+use crate::{
+    formats::{unescape, Converted, IcsComponent, IcsError, IcsProperty},
+    models::reminders::{
+        Reminder, ReminderContact, ReminderContactDate, ReminderFrequency, ReminderFrequencyYearly,
+        ReminderMonth, ReminderTimeOfDay, ReminderYearlyDate,
+    },
+};
+
+/// The properties each date of a contact can be read from, in order of
+/// preference.
+const DATE_PROPERTIES: &[(ReminderContactDate, &[&str])] = &[
+    (ReminderContactDate::Birthday, &["BDAY"]),
+    (
+        ReminderContactDate::Anniversary,
+        &["ANNIVERSARY", "X-ANNIVERSARY"],
+    ),
+];
+
+/// Reads yearly reminders from the birthdays and anniversaries of the contacts
+/// in a vCard file. Each reminder is linked to its contact's UID. Dates that
+/// cannot be represented as reminders are skipped and reported as warnings.
+pub fn import_vcard(source: &str) -> Result<Converted<Vec<Reminder>>, IcsError> {
+    let cards: Vec<_> = IcsComponent::parse_all(source)?
+        .into_iter()
+        .filter(|component| component.name == "VCARD")
+        .collect();
+    if cards.is_empty() {
+        return Err(IcsError::NoContact);
+    }
+
+    let mut converted = Converted::new(Vec::new());
+    for card in &cards {
+        let name = contact_name(card);
+        let mut warn = |line: usize, message: String| {
+            converted
+                .warnings
+                .push(format!("line {line}: {name:?}: {message}"));
+        };
+
+        let uid = card.text("UID");
+        let mut found = false;
+        for &(date, names) in DATE_PROPERTIES {
+            let Some(property) = names.iter().find_map(|&name| card.property(name)) else {
+                continue;
+            };
+            found = true;
+
+            let yearly = match yearly_date(property) {
+                Ok(yearly) => yearly,
+                Err(error) => {
+                    warn(property.line, format!("skipped {date}: {error}"));
+                    continue;
+                }
+            };
+            if yearly.month == ReminderMonth::February && yearly.date == 29 {
+                warn(
+                    property.line,
+                    format!("its {date} is on February 29, so it only occurs in leap years"),
+                );
+            }
+            if uid.is_none() {
+                warn(
+                    property.line,
+                    format!("it has no UID, so importing its {date} again adds it again"),
+                );
+            }
+
+            converted.value.push(Reminder {
+                id: Default::default(),
+                frequency: ReminderFrequency::Yearly(ReminderFrequencyYearly {
+                    dates: vec![yearly],
+                    time: ReminderTimeOfDay::AllDay,
+                }),
+                message: format!("{name}'s {date}"),
+                completed: false,
+                snoozed_until: None,
+                actions: Vec::new(),
+                contact: uid.clone().map(|uid| ReminderContact { uid, date }),
+            });
+        }
+        if !found {
+            warn(
+                card.line,
+                "skipped: it has no BDAY or ANNIVERSARY".to_string(),
+            );
+        }
+    }
+
+    Ok(converted)
+}
+
+/// Gets the name of a contact from its `FN`, `N` or `ORG`.
+fn contact_name(card: &IcsComponent) -> String {
+    if let Some(name) = card.text("FN").filter(|name| !name.trim().is_empty()) {
+        return name.trim().to_string();
+    }
+
+    // Structured names are "family;given;additional;prefixes;suffixes"
+    if let Some(property) = card.property("N") {
+        let mut parts = property.value.split(';').map(unescape);
+        let family = parts.next().unwrap_or_default();
+        let given = parts.next().unwrap_or_default();
+        let name = format!("{given} {family}");
+        if !name.trim().is_empty() {
+            return name.trim().to_string();
+        }
+    }
+
+    card.text("ORG")
+        .and_then(|org| org.split(';').next().map(|org| org.trim().to_string()))
+        .filter(|org| !org.is_empty())
+        .unwrap_or_else(|| "Contact".to_string())
+}
+
+/// Reads the month and day of a date like `19850412`, `1985-04-12`, `--0412`
+/// or `--04-12`. Any time after the date is ignored.
+fn yearly_date(property: &IcsProperty) -> Result<ReminderYearlyDate, String> {
+    let value = property.value.trim();
+    if property
+        .param("VALUE")
+        .is_some_and(|kind| kind.eq_ignore_ascii_case("text"))
+    {
+        return Err(format!("{value:?} is text, not a date"));
+    }
+
+    let invalid = || format!("invalid date {value:?}");
+    let date = value.split('T').next().unwrap_or(value);
+    let (year, month_day) = match date.strip_prefix("--") {
+        Some(month_day) => (None, month_day.replace('-', "")),
+        None => {
+            let digits = date.replace('-', "");
+            if digits.len() != 8 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(invalid());
+            }
+            let (year, month_day) = digits.split_at(4);
+            (Some(year.to_string()), month_day.to_string())
+        }
+    };
+    if month_day.len() != 4 || !month_day.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid());
+    }
+
+    let month: u32 = month_day[..2].parse().map_err(|_| invalid())?;
+    let day: u32 = month_day[2..].parse().map_err(|_| invalid())?;
+
+    // Check the date against its year if it has one, or a leap year otherwise
+    let year = match year {
+        Some(year) => year.parse().map_err(|_| invalid())?,
+        None => 2000,
+    };
+    NaiveDate::from_ymd_opt(year, month, day).ok_or_else(invalid)?;
+
+    Ok(ReminderYearlyDate {
+        month: ReminderMonth::from_number(month).ok_or_else(invalid)?,
+        date: day as u8,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        formats::IcsError,
+        models::reminders::{ReminderContact, ReminderContactDate, ReminderFrequency},
+    };
+
+    use super::import_vcard;
+
+    /// Contacts like the ones address books export, in vCard 3 and 4.
+    const CONTACTS: &str = "\
+BEGIN:VCARD\r
+VERSION:4.0\r
+UID:urn:uuid:jane\r
+FN:Jane\r
+  Doe\r
+BDAY:19850412\r
+ANNIVERSARY:2010-06-\r
+ 19\r
+END:VCARD\r
+BEGIN:VCARD\r
+VERSION:3.0\r
+UID:john\r
+N:Smith;John;;;\r
+BDAY:--0229\r
+item1.X-ANNIVERSARY:--12-25\r
+END:VCARD\r
+BEGIN:VCARD\r
+VERSION:4.0\r
+FN:No UID\r
+BDAY;VALUE=date:--0704\r
+END:VCARD\r
+BEGIN:VCARD\r
+VERSION:4.0\r
+UID:text\r
+FN:Text Date\r
+BDAY;VALUE=text:circa 1800\r
+END:VCARD\r
+BEGIN:VCARD\r
+VERSION:4.0\r
+UID:acme\r
+ORG:Acme Inc;Sales\r
+ANNIVERSARY:19990230\r
+BDAY:19900101T120000Z\r
+END:VCARD\r
+BEGIN:VCARD\r
+VERSION:4.0\r
+UID:nobody\r
+FN:Nobody\r
+EMAIL:nobody@example.com\r
+END:VCARD\r
+";
+
+    #[test]
+    fn imports_contacts() {
+        let imported = import_vcard(CONTACTS).unwrap();
+        let reminders: Vec<_> = imported
+            .value
+            .iter()
+            .map(|reminder| {
+                (
+                    reminder.message.as_str(),
+                    reminder.frequency.clone(),
+                    reminder.contact.clone(),
+                )
+            })
+            .collect();
+        let schedule = |schedule: &str| schedule.parse::<ReminderFrequency>().unwrap();
+        let contact = |uid: &str, date| {
+            Some(ReminderContact {
+                uid: uid.into(),
+                date,
+            })
+        };
+        assert_eq!(
+            reminders,
+            [
+                (
+                    "Jane Doe's birthday",
+                    schedule("yearly 04-12 @ all-day"),
+                    contact("urn:uuid:jane", ReminderContactDate::Birthday),
+                ),
+                (
+                    "Jane Doe's anniversary",
+                    schedule("yearly 06-19 @ all-day"),
+                    contact("urn:uuid:jane", ReminderContactDate::Anniversary),
+                ),
+                (
+                    "John Smith's birthday",
+                    schedule("yearly 02-29 @ all-day"),
+                    contact("john", ReminderContactDate::Birthday),
+                ),
+                (
+                    "John Smith's anniversary",
+                    schedule("yearly 12-25 @ all-day"),
+                    contact("john", ReminderContactDate::Anniversary),
+                ),
+                (
+                    "No UID's birthday",
+                    schedule("yearly 07-04 @ all-day"),
+                    None
+                ),
+                (
+                    "Acme Inc's birthday",
+                    schedule("yearly 01-01 @ all-day"),
+                    contact("acme", ReminderContactDate::Birthday),
+                ),
+            ]
+        );
+
+        assert_eq!(
+            imported.warnings,
+            [
+                "line 14: \"John Smith\": its birthday is on February 29, so it only occurs in \
+                 leap years",
+                "line 20: \"No UID\": it has no UID, so importing its birthday again adds it again",
+                "line 26: \"Text Date\": skipped birthday: \"circa 1800\" is text, not a date",
+                "line 32: \"Acme Inc\": skipped anniversary: invalid date \"19990230\"",
+                "line 35: \"Nobody\": skipped: it has no BDAY or ANNIVERSARY",
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_every_date_form() {
+        let cases = [
+            ("19850412", "yearly 04-12 @ all-day"),
+            ("1985-04-12", "yearly 04-12 @ all-day"),
+            ("--0412", "yearly 04-12 @ all-day"),
+            ("--04-12", "yearly 04-12 @ all-day"),
+            ("1985-04-12T09:30:00", "yearly 04-12 @ all-day"),
+            ("20000229", "yearly 02-29 @ all-day"),
+        ];
+        for (value, expected) in cases {
+            let source = format!("BEGIN:VCARD\r\nUID:a\r\nFN:A\r\nBDAY:{value}\r\nEND:VCARD\r\n");
+            let imported = import_vcard(&source).unwrap();
+            let [reminder] = imported.value.as_slice() else {
+                panic!("{value}: expected one reminder: {:?}", imported.warnings);
+            };
+            let expected: ReminderFrequency = expected.parse().unwrap();
+            assert_eq!(reminder.frequency, expected, "{value}");
+        }
+
+        for value in [
+            "19990229",
+            "--1301",
+            "--0230",
+            "0412",
+            "1985-4-12",
+            "--04",
+            "198é412",
+            "--0é1",
+        ] {
+            let source = format!("BEGIN:VCARD\r\nUID:a\r\nFN:A\r\nBDAY:{value}\r\nEND:VCARD\r\n");
+            let imported = import_vcard(&source).unwrap();
+            assert!(imported.value.is_empty(), "{value}");
+            assert_eq!(
+                imported.warnings,
+                [format!(
+                    "line 4: \"A\": skipped birthday: invalid date {value:?}"
+                )]
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_files_without_contacts() {
+        let source = "BEGIN:VCALENDAR\r\nEND:VCALENDAR\r\n";
+        assert_eq!(import_vcard(source), Err(IcsError::NoContact));
+    }
+}
@@ -0,0 +1,138 @@
+#[cfg(unix)]
+mod client;
+mod lock;
+// The JSON-RPC envelopes are only sent over the socket
+#[cfg_attr(not(unix), allow(dead_code))]
+mod protocol;
+mod request;
+#[cfg(unix)]
+mod server;
+#[cfg(not(unix))]
+mod unsupported;
+
+#[cfg(unix)]
+pub use client::*;
+pub use lock::*;
+pub use protocol::*;
+pub use request::*;
+#[cfg(unix)]
+pub use server::*;
+#[cfg(not(unix))]
+pub use unsupported::*;
+
+use std::path::{Path, PathBuf};
+
+#[cfg(unix)]
+use color_eyre::eyre::{bail, Context};
+
+/// The environment variable that overrides the location of the IPC socket.
+pub const SOCKET_PATH_VAR: &str = "DESKTOP_REMINDERS_SOCKET";
+
+/// Gets the path of the per-user socket that the running instance listens on.
+pub fn socket_path() -> PathBuf {
+    if let Some(path) = std::env::var_os(SOCKET_PATH_VAR) {
+        return PathBuf::from(path);
+    }
+
+    let file_name = concat!(env!("CARGO_PKG_NAME"), ".sock");
+    dirs::runtime_dir()
+        .unwrap_or_else(fallback_dir)
+        .join(file_name)
+}
+
+/// Gets the directory the socket is placed in if there is no runtime
+/// directory. The temporary directory is shared with other users, so the
+/// socket is kept in a directory only the current user can access.
+fn fallback_dir() -> PathBuf {
+    #[cfg(unix)]
+    // SAFETY: Getting the user ID has no memory safety requirements
+    let user = unsafe { libc::getuid() }.to_string();
+    #[cfg(not(unix))]
+    let user = std::env::var("USER").unwrap_or_default();
+    std::env::temp_dir().join(format!("{}-{user}", env!("CARGO_PKG_NAME")))
+}
+
+/// Creates the directory containing `path` if it is the fallback directory
+/// for the socket, and checks that no other user can access it. This should
+/// be done before using the socket or the lock next to it.
+pub fn prepare_socket_dir(path: &Path) -> color_eyre::Result<()> {
+    #[cfg(unix)]
+    {
+        let dir = fallback_dir();
+        if path.parent() == Some(dir.as_path()) {
+            create_private_dir(&dir)?;
+        }
+    }
+
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(())
+}
+
+/// Creates a directory that only the current user can access, failing if it
+/// already exists and another user could access it.
+#[cfg(unix)]
+fn create_private_dir(dir: &Path) -> color_eyre::Result<()> {
+    use std::os::unix::fs::{DirBuilderExt, MetadataExt};
+
+    if let Err(error) = std::fs::DirBuilder::new().mode(0o700).create(dir) {
+        if error.kind() != std::io::ErrorKind::AlreadyExists {
+            return Err(error).wrap_err_with(|| format!("failed to create {}", dir.display()));
+        }
+    }
+
+    // The directory could have been created by someone else, or be a link
+    let metadata = std::fs::symlink_metadata(dir)
+        .wrap_err_with(|| format!("failed to read {}", dir.display()))?;
+    // SAFETY: Getting the user ID has no memory safety requirements
+    let user = unsafe { libc::getuid() };
+    if !metadata.is_dir() || metadata.uid() != user || metadata.mode() & 0o077 != 0 {
+        bail!(
+            "{} must be a directory that only the current user can access",
+            dir.display()
+        );
+    }
+
+    Ok(())
+}
+
+/// Gets the path of the lock file held by the running instance.
+pub fn lock_path() -> PathBuf {
+    socket_path().with_extension("lock")
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::os::unix::fs::PermissionsExt;
+
+    use super::create_private_dir;
+
+    #[test]
+    fn private_dir_rejects_shared_dirs() {
+        let root =
+            std::env::temp_dir().join(format!("desktop-reminders-ipc-{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+
+        // Created with the right permissions, and accepted again afterwards
+        let dir = root.join("private");
+        create_private_dir(&dir).unwrap();
+        let mode = std::fs::metadata(&dir).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o700);
+        create_private_dir(&dir).unwrap();
+
+        let shared = root.join("shared");
+        std::fs::create_dir(&shared).unwrap();
+        std::fs::set_permissions(&shared, std::fs::Permissions::from_mode(0o755)).unwrap();
+        assert!(create_private_dir(&shared).is_err());
+
+        let link = root.join("link");
+        std::os::unix::fs::symlink(&dir, &link).unwrap();
+        assert!(create_private_dir(&link).is_err());
+
+        let file = root.join("file");
+        std::fs::write(&file, "").unwrap();
+        assert!(create_private_dir(&file).is_err());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
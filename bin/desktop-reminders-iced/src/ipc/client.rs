@@ -0,0 +1,112 @@
+use std::{
+    io::{BufRead, BufReader, ErrorKind, Write},
+    os::unix::net::UnixStream,
+    path::Path,
+    time::Duration,
+};
+
+use color_eyre::eyre::{bail, Context};
+use serde_json::Value;
+
+use super::{
+    prepare_socket_dir, Request, Response, RpcNotification, RpcRequest, RpcResponse,
+    JSONRPC_VERSION,
+};
+
+/// A connection to the running instance.
+#[derive(Debug)]
+pub struct IpcClient {
+    reader: BufReader<UnixStream>,
+    writer: UnixStream,
+    next_id: u64,
+}
+
+impl IpcClient {
+    /// Connects to the instance listening on `path`. If no instance is
+    /// running, `None` is returned.
+    pub fn connect(path: &Path) -> color_eyre::Result<Option<Self>> {
+        prepare_socket_dir(path)?;
+        let stream = match UnixStream::connect(path) {
+            Ok(stream) => stream,
+            Err(error)
+                if matches!(
+                    error.kind(),
+                    ErrorKind::NotFound | ErrorKind::ConnectionRefused
+                ) =>
+            {
+                return Ok(None);
+            }
+            Err(error) => {
+                return Err(error)
+                    .wrap_err_with(|| format!("failed to connect to {}", path.display()))
+            }
+        };
+
+        Ok(Some(Self {
+            reader: BufReader::new(stream.try_clone()?),
+            writer: stream,
+            next_id: 1,
+        }))
+    }
+
+    /// Sends a request and waits for its response.
+    pub fn call(&mut self, request: Request) -> color_eyre::Result<Response> {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let request = RpcRequest {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            id: id.into(),
+            request,
+        };
+        let mut line = serde_json::to_vec(&request)?;
+        line.push(b'\n');
+        self.writer.write_all(&line)?;
+
+        // Skip notifications until the response arrives
+        loop {
+            let line = self.read_line()?;
+            let value: Value = serde_json::from_str(&line)?;
+            if value.get("id").and_then(Value::as_u64) != Some(id) {
+                continue;
+            }
+
+            let response: RpcResponse = serde_json::from_value(value)?;
+            if let Some(error) = response.error {
+                bail!("{} (code {})", error.message, error.code);
+            }
+            return Ok(response.result.unwrap_or(Response::None));
+        }
+    }
+
+    /// Waits up to `timeout` for a notification. Send [`Request::Subscribe`]
+    /// first to receive notifications.
+    pub fn wait_for_notification(
+        &mut self,
+        timeout: Duration,
+    ) -> color_eyre::Result<Option<RpcNotification>> {
+        self.reader.get_ref().set_read_timeout(Some(timeout))?;
+        let line = self.read_line();
+        self.reader.get_ref().set_read_timeout(None)?;
+        match line {
+            Ok(line) => Ok(serde_json::from_str(&line).ok()),
+            Err(error)
+                if error.downcast_ref::<std::io::Error>().is_some_and(|error| {
+                    matches!(error.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut)
+                }) =>
+            {
+                Ok(None)
+            }
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Reads a line from the connection.
+    fn read_line(&mut self) -> color_eyre::Result<String> {
+        let mut line = String::new();
+        if self.reader.read_line(&mut line)? == 0 {
+            bail!("the instance closed the connection");
+        }
+        Ok(line)
+    }
+}
@@ -0,0 +1,36 @@
+use std::{
+    fs::{File, TryLockError},
+    path::Path,
+};
+
+use color_eyre::eyre::Context;
+
+use super::prepare_socket_dir;
+
+/// A lock held by the running instance for as long as it runs. Only one
+/// process can hold the lock at a time.
+#[derive(Debug)]
+pub struct InstanceLock {
+    _file: File,
+}
+
+impl InstanceLock {
+    /// Acquires the lock at `path`. If another instance holds the lock, `None`
+    /// is returned.
+    pub fn acquire(path: &Path) -> color_eyre::Result<Option<Self>> {
+        prepare_socket_dir(path)?;
+        let file = File::options()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(path)
+            .wrap_err_with(|| format!("failed to open {}", path.display()))?;
+        match file.try_lock() {
+            Ok(()) => Ok(Some(Self { _file: file })),
+            Err(TryLockError::WouldBlock) => Ok(None),
+            Err(TryLockError::Error(error)) => {
+                Err(error).wrap_err_with(|| format!("failed to lock {}", path.display()))
+            }
+        }
+    }
+}
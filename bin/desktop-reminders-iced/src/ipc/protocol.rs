@@ -0,0 +1,311 @@
+use chrono::{Duration, Local};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::models::{
+    reminders::{Reminder, ReminderId, SNOOZE_MINUTES},
+    store::ReminderStore,
+};
+
+/// The JSON-RPC version spoken over the socket.
+pub const JSONRPC_VERSION: &str = "2.0";
+
+/// A request to the running instance. Each request is sent as a single line
+/// of JSON, like `{"jsonrpc":"2.0","id":1,"method":"complete","params":{"id":3}}`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "method", content = "params", rename_all = "snake_case")]
+pub enum Request {
+    /// Adds a reminder, responding with the added reminder.
+    Add {
+        /// The reminder to add. Its ID is ignored.
+        reminder: Reminder,
+    },
+    /// Lists the reminders.
+    List,
+    /// Marks a reminder as done, responding with the changed reminder. A
+    /// recurring reminder only has its current occurrence acknowledged.
+    Complete {
+        /// The ID of the reminder.
+        id: ReminderId,
+        /// Mark the reminder as not done instead.
+        #[serde(default)]
+        undo: bool,
+    },
+    /// Snoozes a reminder, responding with the changed reminder.
+    Snooze {
+        /// The ID of the reminder.
+        id: ReminderId,
+        /// How long to snooze for, in minutes.
+        #[serde(default = "default_snooze_minutes")]
+        minutes: i64,
+    },
+    /// Replaces a reminder, responding with the changed reminder.
+    Update {
+        /// The new reminder. Its ID chooses the reminder to replace.
+        reminder: Reminder,
+    },
+    /// Removes a reminder, responding with the removed reminder.
+    Remove {
+        /// The ID of the reminder.
+        id: ReminderId,
+    },
+    /// Brings the running instance's window to the front.
+    Activate {
+        /// Open the "New" tab with this title entered.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        new_reminder: Option<String>,
+    },
+    /// Subscribes to changes. After responding, the instance sends a
+    /// [`Notification`] whenever the reminders change.
+    Subscribe,
+}
+
+fn default_snooze_minutes() -> i64 {
+    SNOOZE_MINUTES
+}
+
+impl Request {
+    /// Checks whether the request changes the reminders.
+    #[must_use]
+    pub fn is_change(&self) -> bool {
+        !matches!(
+            self,
+            Request::List | Request::Activate { .. } | Request::Subscribe
+        )
+    }
+
+    /// Applies the request to a store.
+    pub fn apply(self, store: &mut ReminderStore) -> Result<Response, RpcError> {
+        match self {
+            Request::Add { reminder } => {
+                let id = store.add(reminder);
+                store
+                    .get(id)
+                    .cloned()
+                    .map(Response::Reminder)
+                    .ok_or(RpcError::not_found(id))
+            }
+            Request::List => Ok(Response::Reminders(store.reminders.clone())),
+            Request::Complete { id, undo } => {
+                let reminder = match undo {
+                    true => store.uncomplete(id),
+                    false => store.complete(id, Local::now().naive_local()),
+                };
+                reminder
+                    .cloned()
+                    .map(Response::Reminder)
+                    .ok_or(RpcError::not_found(id))
+            }
+            Request::Snooze { id, minutes } => {
+                let reminder = store.get_mut(id).ok_or(RpcError::not_found(id))?;
+                let until = Local::now().naive_local() + Duration::minutes(minutes);
+                reminder.snoozed_until = Some(until);
+                Ok(Response::Reminder(reminder.clone()))
+            }
+            Request::Update { reminder } => {
+                let existing = store
+                    .get_mut(reminder.id)
+                    .ok_or(RpcError::not_found(reminder.id))?;
+                *existing = reminder;
+                Ok(Response::Reminder(existing.clone()))
+            }
+            Request::Remove { id } => store
+                .remove(id)
+                .map(Response::Reminder)
+                .ok_or(RpcError::not_found(id)),
+            Request::Activate { .. } => Err(RpcError::new(
+                RpcError::NO_WINDOW,
+                "the running instance has no window",
+            )),
+            Request::Subscribe => Ok(Response::None),
+        }
+    }
+}
+
+/// The result of a [`Request`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Response {
+    /// A list of reminders.
+    Reminders(Vec<Reminder>),
+    /// A single reminder.
+    Reminder(Reminder),
+    /// No result.
+    None,
+}
+
+/// A message sent to subscribers, like
+/// `{"jsonrpc":"2.0","method":"changed","params":{"reminders":[]}}`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "method", content = "params", rename_all = "snake_case")]
+pub enum Notification {
+    /// The reminders changed.
+    Changed {
+        /// The reminders after the change.
+        reminders: Vec<Reminder>,
+    },
+}
+
+/// An error responding to a [`Request`].
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct RpcError {
+    /// The error code.
+    pub code: i64,
+    /// A description of the error.
+    pub message: String,
+}
+
+impl RpcError {
+    /// The request was not valid JSON.
+    pub const PARSE_ERROR: i64 = -32700;
+    /// The request was not a valid request object.
+    pub const INVALID_REQUEST: i64 = -32600;
+    /// The request could not be handled.
+    pub const INTERNAL_ERROR: i64 = -32603;
+    /// The requested reminder does not exist.
+    pub const NOT_FOUND: i64 = 1;
+    /// The running instance has no window to activate.
+    pub const NO_WINDOW: i64 = 2;
+
+    /// Creates a new error.
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+
+    /// Creates an error for a reminder that does not exist.
+    pub fn not_found(id: ReminderId) -> Self {
+        Self::new(Self::NOT_FOUND, format!("no reminder with ID {id}"))
+    }
+}
+
+/// The envelope around a [`Request`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RpcRequest {
+    /// The protocol version.
+    pub jsonrpc: String,
+    /// The ID used to match the response to the request.
+    #[serde(default)]
+    pub id: Value,
+    /// The request.
+    #[serde(flatten)]
+    pub request: Request,
+}
+
+/// The envelope around a [`Response`] or an [`RpcError`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RpcResponse {
+    /// The protocol version.
+    pub jsonrpc: String,
+    /// The ID of the request this responds to.
+    pub id: Value,
+    /// The response, if the request succeeded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<Response>,
+    /// The error, if the request failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<RpcError>,
+}
+
+impl RpcResponse {
+    /// Creates a response to the request with the given ID.
+    pub fn new(id: Value, result: Result<Response, RpcError>) -> Self {
+        let (result, error) = match result {
+            Ok(response) => (Some(response), None),
+            Err(error) => (None, Some(error)),
+        };
+        Self {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            id,
+            result,
+            error,
+        }
+    }
+}
+
+/// The envelope around a [`Notification`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RpcNotification {
+    /// The protocol version.
+    pub jsonrpc: String,
+    /// The notification.
+    #[serde(flatten)]
+    pub notification: Notification,
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+
+    use crate::models::{
+        reminders::{
+            Reminder, ReminderAlert, ReminderFrequency, ReminderFrequencyDaily,
+            ReminderFrequencyOnce, ReminderId, ReminderOccurrence, ReminderTimeOfDay,
+        },
+        store::ReminderStore,
+    };
+
+    use super::{Request, Response};
+
+    fn at(hour: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2024, 3, 1)
+            .unwrap()
+            .and_time(NaiveTime::from_hms_opt(hour, 0, 0).unwrap())
+    }
+
+    fn reminder(frequency: ReminderFrequency) -> Reminder {
+        Reminder {
+            id: Default::default(),
+            frequency,
+            message: "Stretch".to_string(),
+            completed: false,
+            snoozed_until: Some(at(12)),
+            actions: Vec::new(),
+            contact: None,
+        }
+    }
+
+    fn complete(store: &mut ReminderStore, id: ReminderId, undo: bool) -> Reminder {
+        match (Request::Complete { id, undo }).apply(store).unwrap() {
+            Response::Reminder(reminder) => reminder,
+            response => panic!("unexpected response: {response:?}"),
+        }
+    }
+
+    #[test]
+    fn completing_recurring_reminder_keeps_it_active() {
+        let mut store = ReminderStore::default();
+        let id = store.add(reminder(ReminderFrequency::Daily(ReminderFrequencyDaily {
+            time: ReminderTimeOfDay::AllDay,
+        })));
+        store.queued_alerts.push(ReminderAlert {
+            occurrence: ReminderOccurrence { id, at: at(9) },
+            missed: false,
+        });
+
+        let completed = complete(&mut store, id, false);
+        assert!(!completed.completed);
+        assert_eq!(completed.snoozed_until, None);
+        assert!(store.queued_alerts.is_empty());
+        assert_eq!(store.completions.len(), 1);
+
+        // It still comes due the next day
+        let next = completed.next_occurrence_after(at(10));
+        assert_eq!(next, Some(at(0) + chrono::Duration::days(1)));
+    }
+
+    #[test]
+    fn completing_one_off_reminder_marks_it_completed() {
+        let mut store = ReminderStore::default();
+        let id = store.add(reminder(ReminderFrequency::Once(ReminderFrequencyOnce {
+            date: at(0).date(),
+            time: ReminderTimeOfDay::AllDay,
+        })));
+
+        assert!(complete(&mut store, id, false).completed);
+        assert!(!complete(&mut store, id, true).completed);
+        assert!(store.completions.is_empty());
+    }
+}
@@ -0,0 +1,44 @@
+use futures::{
+    channel::mpsc::{UnboundedReceiver, UnboundedSender},
+    StreamExt,
+};
+
+use super::{Request, Response, RpcError};
+
+/// A request received from outside the app, waiting for a response.
+#[derive(Clone, Debug)]
+pub struct IpcRequest {
+    /// The request.
+    pub request: Request,
+    responder: UnboundedSender<Result<Response, RpcError>>,
+}
+
+impl IpcRequest {
+    /// Creates a new request, along with the response to wait on.
+    pub fn new(request: Request) -> (Self, PendingResponse) {
+        let (responder, response) = futures::channel::mpsc::unbounded();
+        (Self { request, responder }, PendingResponse(response))
+    }
+
+    /// Sends the response back to the client.
+    pub fn respond(self, result: Result<Response, RpcError>) {
+        // The client may have disconnected already
+        let _ = self.responder.unbounded_send(result);
+    }
+}
+
+/// The response to an [`IpcRequest`] that has not been handled yet.
+#[derive(Debug)]
+pub struct PendingResponse(UnboundedReceiver<Result<Response, RpcError>>);
+
+impl PendingResponse {
+    /// Waits for the request to be handled.
+    pub async fn recv(mut self) -> Result<Response, RpcError> {
+        self.0.next().await.unwrap_or_else(|| {
+            Err(RpcError::new(
+                RpcError::INTERNAL_ERROR,
+                "the request was not handled",
+            ))
+        })
+    }
+}
@@ -0,0 +1,279 @@
+use std::{
+    fs,
+    io::{BufRead, BufReader, ErrorKind, Write},
+    net::Shutdown,
+    os::unix::net::{UnixListener, UnixStream},
+    path::PathBuf,
+    sync::{
+        mpsc::{self, SyncSender, TrySendError},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use color_eyre::eyre::{bail, Context};
+use serde::Serialize;
+use serde_json::Value;
+use tracing::{debug, info, info_span, warn};
+
+use crate::models::reminders::Reminder;
+
+use super::{
+    prepare_socket_dir, IpcRequest, Notification, Request, Response, RpcError, RpcNotification,
+    RpcRequest, RpcResponse, JSONRPC_VERSION,
+};
+
+/// How many notifications can wait to be written to a subscriber before it is
+/// dropped for not reading them.
+const SUBSCRIBER_BACKLOG: usize = 16;
+
+/// How long writing to a subscriber may take before the connection is closed.
+const SUBSCRIBER_WRITE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A connection that can be written to from multiple threads.
+type SharedStream = Arc<Mutex<UnixStream>>;
+
+/// Sends notifications to a subscriber's writer thread.
+type Subscriber = SyncSender<Arc<[u8]>>;
+
+/// Listens on a Unix domain socket for requests from the CLI and scripts.
+/// The socket is removed when the server is dropped.
+#[derive(Debug)]
+pub struct IpcServer {
+    path: PathBuf,
+    subscribers: Arc<Mutex<Vec<Subscriber>>>,
+}
+
+impl IpcServer {
+    /// Binds to the socket at `path`, passing each request to `on_request`.
+    /// Fails if another instance is already listening on the socket.
+    pub fn bind<F>(path: impl Into<PathBuf>, on_request: F) -> color_eyre::Result<Self>
+    where
+        F: Fn(IpcRequest) + Send + Sync + 'static,
+    {
+        let path = path.into();
+        prepare_socket_dir(&path)?;
+        let listener = match UnixListener::bind(&path) {
+            Ok(listener) => listener,
+            Err(error) if error.kind() == ErrorKind::AddrInUse => {
+                if UnixStream::connect(&path).is_ok() {
+                    bail!("another instance is listening on {}", path.display());
+                }
+
+                // The socket was left behind by an instance that stopped
+                debug!(path = %path.display(), "removing stale socket");
+                fs::remove_file(&path)
+                    .wrap_err_with(|| format!("failed to remove {}", path.display()))?;
+                UnixListener::bind(&path)
+                    .wrap_err_with(|| format!("failed to bind to {}", path.display()))?
+            }
+            Err(error) => {
+                return Err(error).wrap_err_with(|| format!("failed to bind to {}", path.display()))
+            }
+        };
+        info!(path = %path.display(), "listening for requests");
+
+        let subscribers = Arc::new(Mutex::new(Vec::new()));
+        let on_request = Arc::new(on_request);
+        std::thread::spawn({
+            let subscribers = subscribers.clone();
+            move || {
+                for stream in listener.incoming() {
+                    let stream = match stream {
+                        Ok(stream) => stream,
+                        Err(error) => {
+                            warn!(%error, "failed to accept connection");
+                            continue;
+                        }
+                    };
+
+                    let on_request = on_request.clone();
+                    let subscribers = subscribers.clone();
+                    std::thread::spawn(move || {
+                        let _span = info_span!("ipc connection").entered();
+                        if let Err(error) = serve(stream, on_request.as_ref(), &subscribers) {
+                            debug!(%error, "connection closed");
+                        }
+                    });
+                }
+            }
+        });
+
+        Ok(Self { path, subscribers })
+    }
+
+    /// Tells subscribers that the reminders changed. This never waits for
+    /// subscribers, and ones that stopped reading notifications are dropped.
+    pub fn notify_changed(&self, reminders: &[Reminder]) {
+        let notification = RpcNotification {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            notification: Notification::Changed {
+                reminders: reminders.to_vec(),
+            },
+        };
+        let line: Arc<[u8]> = match to_line(&notification) {
+            Ok(line) => line.into(),
+            Err(error) => {
+                warn!(%error, "failed to serialize notification");
+                return;
+            }
+        };
+
+        let mut subscribers = self.subscribers.lock().unwrap();
+        subscribers.retain(|subscriber| match subscriber.try_send(line.clone()) {
+            Ok(()) => true,
+            Err(TrySendError::Full(_)) => {
+                warn!("dropping a subscriber that is not reading notifications");
+                false
+            }
+            Err(TrySendError::Disconnected(_)) => false,
+        });
+    }
+}
+
+impl Drop for IpcServer {
+    fn drop(&mut self) {
+        if let Err(error) = fs::remove_file(&self.path) {
+            warn!(%error, path = %self.path.display(), "failed to remove socket");
+        }
+    }
+}
+
+/// Serves requests on a connection until it is closed.
+fn serve(
+    stream: UnixStream,
+    on_request: &(dyn Fn(IpcRequest) + Send + Sync),
+    subscribers: &Mutex<Vec<Subscriber>>,
+) -> color_eyre::Result<()> {
+    let reader = BufReader::new(stream.try_clone()?);
+    let writer = Arc::new(Mutex::new(stream));
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let request = match serde_json::from_str::<RpcRequest>(&line) {
+            Ok(request) => request,
+            Err(error) => {
+                let code = match serde_json::from_str::<Value>(&line) {
+                    Ok(_) => RpcError::INVALID_REQUEST,
+                    Err(_) => RpcError::PARSE_ERROR,
+                };
+                let error = RpcError::new(code, error.to_string());
+                write_line(&writer, &RpcResponse::new(Value::Null, Err(error)))?;
+                continue;
+            }
+        };
+
+        debug!(?request.request, "received request");
+        let id = request.id;
+        let result = if let Request::Subscribe = request.request {
+            let subscriber = subscribe(&writer)?;
+            subscribers.lock().unwrap().push(subscriber);
+            Ok(Response::None)
+        } else {
+            let (request, response) = IpcRequest::new(request.request);
+            on_request(request);
+            futures::executor::block_on(response.recv())
+        };
+        write_line(&writer, &RpcResponse::new(id, result))?;
+    }
+
+    Ok(())
+}
+
+/// Starts writing notifications to a connection on its own thread, so a
+/// subscriber that stops reading only holds up its own connection.
+fn subscribe(writer: &SharedStream) -> color_eyre::Result<Subscriber> {
+    writer
+        .lock()
+        .unwrap()
+        .set_write_timeout(Some(SUBSCRIBER_WRITE_TIMEOUT))?;
+
+    let (sender, receiver) = mpsc::sync_channel::<Arc<[u8]>>(SUBSCRIBER_BACKLOG);
+    let writer = writer.clone();
+    std::thread::spawn(move || {
+        for line in receiver {
+            let mut stream = writer.lock().unwrap();
+            if let Err(error) = stream.write_all(&line) {
+                debug!(%error, "subscriber disconnected");
+                let _ = stream.shutdown(Shutdown::Both);
+                break;
+            }
+        }
+    });
+
+    Ok(sender)
+}
+
+/// Writes a value as a single line of JSON.
+fn write_line(stream: &Mutex<UnixStream>, value: &impl Serialize) -> color_eyre::Result<()> {
+    let line = to_line(value)?;
+    stream.lock().unwrap().write_all(&line)?;
+    Ok(())
+}
+
+/// Serializes a value as a single line of JSON.
+fn to_line(value: &impl Serialize) -> serde_json::Result<Vec<u8>> {
+    let mut line = serde_json::to_vec(value)?;
+    line.push(b'\n');
+    Ok(line)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use crate::{
+        ipc::{IpcClient, Notification, Request, Response},
+        models::reminders::{
+            Reminder, ReminderFrequency, ReminderFrequencyDaily, ReminderId, ReminderTimeOfDay,
+        },
+    };
+
+    use super::IpcServer;
+
+    fn reminder(id: u64) -> Reminder {
+        Reminder {
+            id: ReminderId(id),
+            frequency: ReminderFrequency::Daily(ReminderFrequencyDaily {
+                time: ReminderTimeOfDay::AllDay,
+            }),
+            message: "x".repeat(100),
+            completed: false,
+            snoozed_until: None,
+            actions: Vec::new(),
+            contact: None,
+        }
+    }
+
+    #[test]
+    fn stalled_subscriber_does_not_block_notifications() {
+        let path = std::env::temp_dir().join(format!("reminders-ipc-{}.sock", std::process::id()));
+        let server = IpcServer::bind(&path, |request| request.respond(Ok(Response::None))).unwrap();
+
+        // This subscriber never reads its notifications
+        let mut stalled = IpcClient::connect(&path).unwrap().unwrap();
+        stalled.call(Request::Subscribe).unwrap();
+
+        // Enough notifications to fill the socket's buffers many times over
+        let reminders: Vec<_> = (1..=1000).map(reminder).collect();
+        let start = Instant::now();
+        for _ in 0..100 {
+            server.notify_changed(&reminders);
+        }
+        assert!(start.elapsed() < Duration::from_secs(5));
+
+        // Other connections can still subscribe and be notified
+        let mut client = IpcClient::connect(&path).unwrap().unwrap();
+        client.call(Request::Subscribe).unwrap();
+        server.notify_changed(&reminders[..1]);
+        let notification = client
+            .wait_for_notification(Duration::from_secs(5))
+            .unwrap()
+            .unwrap();
+        let Notification::Changed { reminders } = notification.notification;
+        assert_eq!(reminders.len(), 1);
+    }
+}
@@ -0,0 +1,59 @@
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use color_eyre::eyre::bail;
+
+use crate::models::reminders::Reminder;
+
+use super::{IpcRequest, Request, Response, RpcNotification};
+
+/// Stands in for the socket server on platforms without Unix domain sockets.
+/// Binding always fails, so the app runs without serving requests.
+#[derive(Debug)]
+pub enum IpcServer {}
+
+impl IpcServer {
+    /// Fails, since requests cannot be served on this platform.
+    pub fn bind<F>(path: impl Into<PathBuf>, _on_request: F) -> color_eyre::Result<Self>
+    where
+        F: Fn(IpcRequest) + Send + Sync + 'static,
+    {
+        bail!(
+            "cannot listen on {}: sockets are not supported on this platform",
+            path.into().display()
+        )
+    }
+
+    /// Tells subscribers that the reminders changed.
+    pub fn notify_changed(&self, _reminders: &[Reminder]) {
+        match *self {}
+    }
+}
+
+/// Stands in for the socket client on platforms without Unix domain sockets.
+/// No running instance can be reached, so the store is edited directly.
+#[derive(Debug)]
+pub enum IpcClient {}
+
+impl IpcClient {
+    /// Always returns `None`, since the running instance cannot be reached
+    /// on this platform.
+    pub fn connect(_path: &Path) -> color_eyre::Result<Option<Self>> {
+        Ok(None)
+    }
+
+    /// Sends a request and waits for its response.
+    pub fn call(&mut self, _request: Request) -> color_eyre::Result<Response> {
+        match *self {}
+    }
+
+    /// Waits up to `timeout` for a notification.
+    pub fn wait_for_notification(
+        &mut self,
+        _timeout: Duration,
+    ) -> color_eyre::Result<Option<RpcNotification>> {
+        match *self {}
+    }
+}
@@ -1,10 +1,28 @@
+use clap::Parser;
+
+use crate::cli::Args;
+
+mod actions;
 mod backend;
+mod cli;
+mod daemon;
+mod dbus;
+mod digest;
+mod formats;
+mod ipc;
 mod models;
+mod mqtt;
+mod notifications;
+mod scheduler;
 mod startup;
+mod storage;
+mod tray;
 mod ui;
+mod web;
 
 fn main() -> color_eyre::Result<()> {
     color_eyre::install()?;
 
-    crate::startup::start()
+    let args = Args::parse();
+    crate::startup::start(args)
 }
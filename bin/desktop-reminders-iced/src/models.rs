@@ -1,2 +1,4 @@
 pub mod reminders;
+pub mod settings;
+pub mod store;
 pub mod theme;
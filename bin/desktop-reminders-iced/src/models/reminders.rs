@@ -1,5 +1,13 @@
+mod action;
+mod contact;
 mod frequency;
+mod id;
+mod occurrence;
 mod reminder;
 
+pub use action::*;
+pub use contact::*;
 pub use frequency::*;
+pub use id::*;
+pub use occurrence::*;
 pub use reminder::*;
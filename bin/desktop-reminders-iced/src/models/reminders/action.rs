@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+
+/// Something to do when a reminder fires, in addition to alerting the user.
+#[derive(Clone, PartialEq, Eq, Debug, Hash, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+pub enum ReminderAction {
+    /// Runs a shell command. The reminder's details are passed to the command
+    /// through environment variables.
+    Command {
+        /// The command to run.
+        command: String,
+        /// How long (in seconds) the command may run before it is killed.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        timeout_secs: Option<u64>,
+    },
+    /// Sends a POST request with a JSON payload describing the reminder.
+    Webhook {
+        /// The URL to send the request to.
+        url: String,
+        /// How long (in seconds) to wait for a response.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        timeout_secs: Option<u64>,
+    },
+}
@@ -0,0 +1,32 @@
+use std::fmt::{Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+
+/// The contact a reminder was imported from, so importing the contact again
+/// updates the reminder instead of adding another one.
+#[derive(Clone, PartialEq, Eq, Debug, Hash, Serialize, Deserialize)]
+pub struct ReminderContact {
+    /// The UID of the contact.
+    pub uid: String,
+    /// Which of the contact's dates the reminder is for.
+    pub date: ReminderContactDate,
+}
+
+/// A date of a contact that a reminder can be for.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReminderContactDate {
+    /// The contact's birthday, from `BDAY`.
+    Birthday,
+    /// The contact's anniversary, from `ANNIVERSARY`.
+    Anniversary,
+}
+
+impl Display for ReminderContactDate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReminderContactDate::Birthday => write!(f, "birthday"),
+            ReminderContactDate::Anniversary => write!(f, "anniversary"),
+        }
+    }
+}
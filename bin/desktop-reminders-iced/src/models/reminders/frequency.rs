@@ -1,5 +1,6 @@
 mod daily;
 mod days_of_week;
+#[allow(clippy::module_inception)]
 mod frequency;
 mod month;
 mod monthly;
@@ -1,7 +1,12 @@
-use std::fmt::{Display, Formatter};
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
 
 use bitflags::bitflags;
+use chrono::Weekday;
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
 bitflags! {
     /// Days of the week a reminder can be set for.
@@ -73,3 +78,55 @@ impl Display for ReminderDaysOfWeek {
         Ok(())
     }
 }
+
+impl From<Weekday> for ReminderDaysOfWeek {
+    fn from(value: Weekday) -> Self {
+        match value {
+            Weekday::Mon => ReminderDaysOfWeek::MONDAY,
+            Weekday::Tue => ReminderDaysOfWeek::TUESDAY,
+            Weekday::Wed => ReminderDaysOfWeek::WEDNESDAY,
+            Weekday::Thu => ReminderDaysOfWeek::THURSDAY,
+            Weekday::Fri => ReminderDaysOfWeek::FRIDAY,
+            Weekday::Sat => ReminderDaysOfWeek::SATURDAY,
+            Weekday::Sun => ReminderDaysOfWeek::SUNDAY,
+        }
+    }
+}
+
+impl FromStr for ReminderDaysOfWeek {
+    type Err = ParseDaysOfWeekError;
+
+    /// Parses a comma-separated list of days, like `mon,wed,fri`. Full day
+    /// names and the groups `weekdays` and `weekends` are also accepted.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut days = ReminderDaysOfWeek::empty();
+        for name in s.split(',') {
+            let name = name.trim().to_ascii_lowercase();
+            days |= match name.as_str() {
+                "weekdays" => ReminderDaysOfWeek::WEEKDAYS,
+                "weekends" => ReminderDaysOfWeek::WEEKENDS,
+                _ => name
+                    .parse::<Weekday>()
+                    .map(ReminderDaysOfWeek::from)
+                    .map_err(|_| ParseDaysOfWeekError::UnknownDay(name))?,
+            };
+        }
+
+        if days.is_empty() {
+            return Err(ParseDaysOfWeekError::Empty);
+        }
+
+        Ok(days)
+    }
+}
+
+/// An error parsing [`ReminderDaysOfWeek`].
+#[derive(Clone, PartialEq, Debug, Error)]
+pub enum ParseDaysOfWeekError {
+    /// No days were given.
+    #[error("expected at least one day")]
+    Empty,
+    /// A day was not recognized.
+    #[error("unknown day {0:?}")]
+    UnknownDay(String),
+}
@@ -1,10 +1,34 @@
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+use chrono::{Datelike, NaiveDate, NaiveDateTime};
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
 use super::{
+    ParseDaysOfWeekError, ParseTimeOfDayError, ParseYearlyDateError, ReminderDaysOfWeek,
     ReminderFrequencyDaily, ReminderFrequencyMonthly, ReminderFrequencyOnce,
-    ReminderFrequencyWeekly, ReminderFrequencyYearly, ReminderTimeOfDay,
+    ReminderFrequencyWeekly, ReminderFrequencyYearly, ReminderTimeOfDay, ReminderYearlyDate,
 };
 
+/// The short names of the days of the week used by the schedule syntax, in
+/// order.
+const SCHEDULE_DAYS: [(ReminderDaysOfWeek, &str); 7] = [
+    (ReminderDaysOfWeek::MONDAY, "mon"),
+    (ReminderDaysOfWeek::TUESDAY, "tue"),
+    (ReminderDaysOfWeek::WEDNESDAY, "wed"),
+    (ReminderDaysOfWeek::THURSDAY, "thu"),
+    (ReminderDaysOfWeek::FRIDAY, "fri"),
+    (ReminderDaysOfWeek::SATURDAY, "sat"),
+    (ReminderDaysOfWeek::SUNDAY, "sun"),
+];
+
+/// The maximum number of days to search for the next occurrence of a
+/// reminder. This covers the longest gap between leap days.
+const MAX_SEARCH_DAYS: usize = 8 * 366;
+
 /// The frequency of a reminder.
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 #[serde(tag = "kind")]
@@ -34,4 +58,479 @@ impl ReminderFrequency {
             ReminderFrequency::Yearly(yearly) => &yearly.time,
         }
     }
+
+    /// Gets a mutable reference to the time of day that the reminder should
+    /// occur.
+    #[inline]
+    pub fn time_of_day_mut(&mut self) -> &mut ReminderTimeOfDay {
+        match self {
+            ReminderFrequency::Once(once) => &mut once.time,
+            ReminderFrequency::Daily(daily) => &mut daily.time,
+            ReminderFrequency::Weekly(weekly) => &mut weekly.time,
+            ReminderFrequency::Monthly(monthly) => &mut monthly.time,
+            ReminderFrequency::Yearly(yearly) => &mut yearly.time,
+        }
+    }
+
+    /// Checks whether the reminder occurs on the given date.
+    #[must_use]
+    pub fn occurs_on(&self, date: NaiveDate) -> bool {
+        match self {
+            ReminderFrequency::Once(once) => once.date == date,
+            ReminderFrequency::Daily(_) => true,
+            ReminderFrequency::Weekly(weekly) => weekly
+                .days
+                .contains(ReminderDaysOfWeek::from(date.weekday())),
+            ReminderFrequency::Monthly(monthly) => monthly
+                .dates
+                .iter()
+                .any(|&day| u32::from(day) == date.day()),
+            ReminderFrequency::Yearly(yearly) => yearly.dates.iter().any(|yearly_date| {
+                yearly_date.month.number() == date.month()
+                    && u32::from(yearly_date.date) == date.day()
+            }),
+        }
+    }
+
+    /// Gets the start times of the occurrences that start after `after` and no
+    /// later than `until`, in chronological order.
+    pub fn occurrences_between(
+        &self,
+        after: NaiveDateTime,
+        until: NaiveDateTime,
+    ) -> impl Iterator<Item = NaiveDateTime> + '_ {
+        let start = self.time_of_day().start();
+        after
+            .date()
+            .iter_days()
+            .take_while(move |&date| date <= until.date())
+            .filter(|&date| self.occurs_on(date))
+            .map(move |date| date.and_time(start))
+            .filter(move |&at| after < at && at <= until)
+    }
+
+    /// Gets the start time of the first occurrence that starts after `after`,
+    /// if there is one.
+    #[must_use]
+    pub fn next_occurrence_after(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        let start = self.time_of_day().start();
+
+        // Reminders that occur once don't need to be searched for, which
+        // matters for ones in the past since nothing would be found
+        if let ReminderFrequency::Once(once) = self {
+            let at = once.date.and_time(start);
+            return (after < at).then_some(at);
+        }
+
+        after
+            .date()
+            .iter_days()
+            .take(MAX_SEARCH_DAYS)
+            .filter(|&date| self.occurs_on(date))
+            .map(|date| date.and_time(start))
+            .find(|&at| after < at)
+    }
+
+    /// Describes the frequency in a human-readable way, like
+    /// "Weekly on Mon, Wed at 12:30 PM".
+    #[must_use]
+    pub fn describe(&self) -> String {
+        let days = match self {
+            ReminderFrequency::Once(once) => format!("Once on {}", once.date),
+            ReminderFrequency::Daily(_) => "Daily".to_string(),
+            ReminderFrequency::Weekly(weekly) => format!("Weekly on {}", weekly.days),
+            ReminderFrequency::Monthly(monthly) => {
+                let dates: Vec<_> = monthly.dates.iter().map(u8::to_string).collect();
+                format!("Monthly on {}", dates.join(", "))
+            }
+            ReminderFrequency::Yearly(yearly) => {
+                let dates: Vec<_> = yearly
+                    .dates
+                    .iter()
+                    .map(|date| format!("{} {}", date.month, date.date))
+                    .collect();
+                format!("Yearly on {}", dates.join(", "))
+            }
+        };
+
+        match self.time_of_day() {
+            ReminderTimeOfDay::AllDay => format!("{days}, all day"),
+            ReminderTimeOfDay::Time { time } => format!("{days} at {}", time.format("%-I:%M %p")),
+            ReminderTimeOfDay::TimeRange { start, end } => format!(
+                "{days} from {} to {}",
+                start.format("%-I:%M %p"),
+                end.format("%-I:%M %p")
+            ),
+        }
+    }
+}
+
+impl Display for ReminderFrequency {
+    /// Writes the frequency as a schedule, like `weekly mon,wed @ 12:30`. See
+    /// [`ReminderFrequency::from_str`] for the syntax.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        fn write_list<T: Display>(f: &mut Formatter<'_>, items: &[T]) -> std::fmt::Result {
+            if items.is_empty() {
+                return write!(f, " none");
+            }
+
+            for (index, item) in items.iter().enumerate() {
+                let separator = if index == 0 { ' ' } else { ',' };
+                write!(f, "{separator}{item}")?;
+            }
+            Ok(())
+        }
+
+        match self {
+            ReminderFrequency::Once(once) => write!(f, "once {}", once.date.format("%Y-%m-%d"))?,
+            ReminderFrequency::Daily(_) => write!(f, "daily")?,
+            ReminderFrequency::Weekly(weekly) => {
+                let days: Vec<_> = SCHEDULE_DAYS
+                    .iter()
+                    .filter(|&&(day, _)| weekly.days.contains(day))
+                    .map(|&(_, name)| name)
+                    .collect();
+                write!(f, "weekly")?;
+                write_list(f, &days)?;
+            }
+            ReminderFrequency::Monthly(monthly) => {
+                write!(f, "monthly")?;
+                write_list(f, &monthly.dates)?;
+            }
+            ReminderFrequency::Yearly(yearly) => {
+                write!(f, "yearly")?;
+                write_list(f, &yearly.dates)?;
+            }
+        }
+
+        write!(f, " @ {}", self.time_of_day())
+    }
+}
+
+impl FromStr for ReminderFrequency {
+    type Err = ParseFrequencyError;
+
+    /// Parses a schedule, which is how often a reminder repeats followed by
+    /// `@` and a [`ReminderTimeOfDay`]:
+    ///
+    /// - `once 2024-03-01 @ 12:30`
+    /// - `daily @ 09:00`
+    /// - `weekly mon,wed @ 12:30-16:30`
+    /// - `monthly 1,15 @ all-day`
+    /// - `yearly 03-01,12-25 @ 09:00`
+    ///
+    /// Lists are separated by commas without spaces. Weekly schedules also
+    /// accept full day names, `weekdays` and `weekends`. An empty list is
+    /// written as `none`. Names are not case-sensitive, so `Weekly MON @
+    /// All-Day` is the same as `weekly mon @ all-day`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (schedule, time) = s.split_once('@').ok_or(ParseFrequencyError::MissingTime)?;
+        let time = time.parse()?;
+        let mut parts = schedule.split_whitespace();
+        let kind = parts.next().ok_or(ParseFrequencyError::Empty)?;
+        let kind = kind.to_ascii_lowercase();
+        let list = parts.next();
+        if let Some(extra) = parts.next() {
+            return Err(ParseFrequencyError::Unexpected(extra.to_string()));
+        }
+
+        let items = |example: &'static str| {
+            let list = list.ok_or_else(|| ParseFrequencyError::MissingDates {
+                kind: kind.clone(),
+                example,
+            })?;
+            Ok::<_, ParseFrequencyError>(match list {
+                list if list.eq_ignore_ascii_case("none") => Vec::new(),
+                list => list.split(',').collect(),
+            })
+        };
+        let frequency = match kind.as_str() {
+            "once" => {
+                let date = items("2024-03-01")?;
+                let [date] = date.as_slice() else {
+                    return Err(ParseFrequencyError::InvalidDate(date.join(",")));
+                };
+                let date = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                    .map_err(|_| ParseFrequencyError::InvalidDate(date.to_string()))?;
+                ReminderFrequency::Once(ReminderFrequencyOnce { date, time })
+            }
+            "daily" => {
+                if let Some(list) = list {
+                    return Err(ParseFrequencyError::Unexpected(list.to_string()));
+                }
+                ReminderFrequency::Daily(ReminderFrequencyDaily { time })
+            }
+            "weekly" => {
+                let days = items("mon,wed")?
+                    .into_iter()
+                    .map(str::parse)
+                    .try_fold(ReminderDaysOfWeek::empty(), |days, day| {
+                        day.map(|day| days | day)
+                    })?;
+                ReminderFrequency::Weekly(ReminderFrequencyWeekly { days, time })
+            }
+            "monthly" => {
+                let dates = items("1,15")?
+                    .into_iter()
+                    .map(|date| {
+                        date.parse()
+                            .ok()
+                            .filter(|date| (1..=31).contains(date))
+                            .ok_or_else(|| ParseFrequencyError::InvalidDayOfMonth(date.to_string()))
+                    })
+                    .collect::<Result<_, _>>()?;
+                ReminderFrequency::Monthly(ReminderFrequencyMonthly { dates, time })
+            }
+            "yearly" => {
+                let dates: Vec<ReminderYearlyDate> = items("03-01,12-25")?
+                    .into_iter()
+                    .map(str::parse)
+                    .collect::<Result<_, _>>()?;
+                ReminderFrequency::Yearly(ReminderFrequencyYearly { dates, time })
+            }
+            _ => return Err(ParseFrequencyError::UnknownKind(kind)),
+        };
+
+        Ok(frequency)
+    }
+}
+
+/// An error parsing a [`ReminderFrequency`] from a schedule.
+#[derive(Clone, PartialEq, Debug, Error)]
+pub enum ParseFrequencyError {
+    /// Nothing was given before the time of day.
+    #[error("expected a schedule like \"weekly mon,wed @ 12:30\"")]
+    Empty,
+    /// The schedule has no `@` and time of day.
+    #[error("expected \"@\" and a time of day, like \"daily @ 12:30\" or \"daily @ all-day\"")]
+    MissingTime,
+    /// The schedule does not start with a known kind of repetition.
+    #[error("unknown schedule {0:?}, expected once, daily, weekly, monthly or yearly")]
+    UnknownKind(String),
+    /// The schedule is missing the dates it repeats on.
+    #[error("{kind} schedules need dates, like \"{kind} {example} @ 12:30\"")]
+    MissingDates {
+        /// The kind of repetition.
+        kind: String,
+        /// An example of the dates.
+        example: &'static str,
+    },
+    /// The schedule has something it does not use.
+    #[error("unexpected {0:?}")]
+    Unexpected(String),
+    /// The date of a schedule that occurs once is invalid.
+    #[error("expected a date like \"2024-03-01\", got {0:?}")]
+    InvalidDate(String),
+    /// A day of the week is invalid.
+    #[error(transparent)]
+    InvalidDays(#[from] ParseDaysOfWeekError),
+    /// A day of the month is invalid.
+    #[error("expected a day of the month from 1 to 31, got {0:?}")]
+    InvalidDayOfMonth(String),
+    /// A day of the year is invalid.
+    #[error(transparent)]
+    InvalidYearlyDate(#[from] ParseYearlyDateError),
+    /// The time of day is invalid.
+    #[error(transparent)]
+    InvalidTime(#[from] ParseTimeOfDayError),
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, NaiveTime};
+
+    use crate::models::reminders::{
+        ParseDaysOfWeekError, ParseTimeOfDayError, ParseYearlyDateError, ReminderDaysOfWeek,
+        ReminderFrequencyDaily, ReminderFrequencyMonthly, ReminderFrequencyOnce,
+        ReminderFrequencyWeekly, ReminderFrequencyYearly, ReminderMonth, ReminderTimeOfDay,
+        ReminderYearlyDate,
+    };
+
+    use super::{ParseFrequencyError, ReminderFrequency};
+
+    fn time(hour: u32, minute: u32, second: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, second).unwrap()
+    }
+
+    fn yearly_date(month: ReminderMonth, date: u8) -> ReminderYearlyDate {
+        ReminderYearlyDate { month, date }
+    }
+
+    #[test]
+    fn round_trip() {
+        let times = [
+            ReminderTimeOfDay::AllDay,
+            ReminderTimeOfDay::Time {
+                time: time(9, 0, 0),
+            },
+            ReminderTimeOfDay::Time {
+                time: time(12, 30, 15),
+            },
+            ReminderTimeOfDay::TimeRange {
+                start: time(22, 0, 0),
+                end: time(1, 30, 0),
+            },
+        ];
+        for time in times {
+            let frequencies = [
+                ReminderFrequency::Once(ReminderFrequencyOnce {
+                    date: NaiveDate::from_ymd_opt(2024, 2, 29).unwrap(),
+                    time,
+                }),
+                ReminderFrequency::Daily(ReminderFrequencyDaily { time }),
+                ReminderFrequency::Weekly(ReminderFrequencyWeekly {
+                    days: ReminderDaysOfWeek::MONDAY | ReminderDaysOfWeek::SUNDAY,
+                    time,
+                }),
+                ReminderFrequency::Weekly(ReminderFrequencyWeekly {
+                    days: ReminderDaysOfWeek::all(),
+                    time,
+                }),
+                ReminderFrequency::Weekly(ReminderFrequencyWeekly {
+                    days: ReminderDaysOfWeek::empty(),
+                    time,
+                }),
+                ReminderFrequency::Monthly(ReminderFrequencyMonthly {
+                    dates: vec![15, 1, 31],
+                    time,
+                }),
+                ReminderFrequency::Monthly(ReminderFrequencyMonthly {
+                    dates: Vec::new(),
+                    time,
+                }),
+                ReminderFrequency::Yearly(ReminderFrequencyYearly {
+                    dates: vec![
+                        yearly_date(ReminderMonth::December, 25),
+                        yearly_date(ReminderMonth::February, 29),
+                    ],
+                    time,
+                }),
+                ReminderFrequency::Yearly(ReminderFrequencyYearly {
+                    dates: Vec::new(),
+                    time,
+                }),
+            ];
+            for frequency in frequencies {
+                let schedule = frequency.to_string();
+                assert_eq!(schedule.parse(), Ok(frequency), "{schedule}");
+            }
+        }
+    }
+
+    #[test]
+    fn parses_alternate_spellings() {
+        let cases = [
+            (
+                "weekly weekdays @ 09:00",
+                "weekly mon,tue,wed,thu,fri @ 09:00",
+            ),
+            ("weekly Saturday,sunday @ 09:00", "weekly sat,sun @ 09:00"),
+            ("Weekly MON @ All-Day", "weekly mon @ all-day"),
+            ("MONTHLY NONE @ all-day", "monthly none @ all-day"),
+            ("  daily   @   09:00  ", "daily @ 09:00"),
+        ];
+        for (schedule, expected) in cases {
+            let frequency: ReminderFrequency = schedule.parse().unwrap();
+            assert_eq!(frequency.to_string(), expected, "{schedule}");
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_schedules() {
+        let cases = [
+            ("daily", ParseFrequencyError::MissingTime),
+            (" @ 09:00", ParseFrequencyError::Empty),
+            (
+                "fortnightly @ 09:00",
+                ParseFrequencyError::UnknownKind("fortnightly".into()),
+            ),
+            (
+                "weekly @ 09:00",
+                ParseFrequencyError::MissingDates {
+                    kind: "weekly".into(),
+                    example: "mon,wed",
+                },
+            ),
+            (
+                "monthly @ 09:00",
+                ParseFrequencyError::MissingDates {
+                    kind: "monthly".into(),
+                    example: "1,15",
+                },
+            ),
+            (
+                "daily mon @ 09:00",
+                ParseFrequencyError::Unexpected("mon".into()),
+            ),
+            (
+                "weekly mon, wed @ 09:00",
+                ParseFrequencyError::Unexpected("wed".into()),
+            ),
+            (
+                "once 2024-02-30 @ 09:00",
+                ParseFrequencyError::InvalidDate("2024-02-30".into()),
+            ),
+            (
+                "once 2024-03-01,2024-03-02 @ 09:00",
+                ParseFrequencyError::InvalidDate("2024-03-01,2024-03-02".into()),
+            ),
+            (
+                "weekly mon,funday @ 09:00",
+                ParseFrequencyError::InvalidDays(ParseDaysOfWeekError::UnknownDay("funday".into())),
+            ),
+            (
+                "monthly 0 @ 09:00",
+                ParseFrequencyError::InvalidDayOfMonth("0".into()),
+            ),
+            (
+                "monthly 1,32 @ 09:00",
+                ParseFrequencyError::InvalidDayOfMonth("32".into()),
+            ),
+            (
+                "yearly 13-01 @ 09:00",
+                ParseFrequencyError::InvalidYearlyDate(ParseYearlyDateError::Invalid(
+                    "13-01".into(),
+                )),
+            ),
+            (
+                "yearly 0301 @ 09:00",
+                ParseFrequencyError::InvalidYearlyDate(ParseYearlyDateError::Invalid(
+                    "0301".into(),
+                )),
+            ),
+            (
+                "yearly 03-01,02-31 @ 09:00",
+                ParseFrequencyError::InvalidYearlyDate(ParseYearlyDateError::Impossible(
+                    ReminderMonth::February,
+                    31,
+                )),
+            ),
+            (
+                "yearly 04-31 @ 09:00",
+                ParseFrequencyError::InvalidYearlyDate(ParseYearlyDateError::Impossible(
+                    ReminderMonth::April,
+                    31,
+                )),
+            ),
+        ];
+        for (schedule, expected) in cases {
+            assert_eq!(
+                schedule.parse::<ReminderFrequency>(),
+                Err(expected),
+                "{schedule}"
+            );
+        }
+
+        for schedule in ["daily @ 25:00", "daily @ noon", "daily @ 09:00-"] {
+            assert!(
+                matches!(
+                    schedule.parse::<ReminderFrequency>(),
+                    Err(ParseFrequencyError::InvalidTime(
+                        ParseTimeOfDayError::InvalidTime(_)
+                    ))
+                ),
+                "{schedule}"
+            );
+        }
+    }
 }
@@ -3,7 +3,7 @@ use std::fmt::{Display, Formatter};
 use serde::{Deserialize, Serialize};
 
 /// A month of the year a reminder can be set for.
-#[derive(Clone, Copy, PartialEq, Debug, Default, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
 pub enum ReminderMonth {
     /// January.
     #[default]
@@ -32,6 +32,52 @@ pub enum ReminderMonth {
     December,
 }
 
+impl ReminderMonth {
+    /// All months, in order.
+    pub const ALL: [ReminderMonth; 12] = [
+        ReminderMonth::January,
+        ReminderMonth::February,
+        ReminderMonth::March,
+        ReminderMonth::April,
+        ReminderMonth::May,
+        ReminderMonth::June,
+        ReminderMonth::July,
+        ReminderMonth::August,
+        ReminderMonth::September,
+        ReminderMonth::October,
+        ReminderMonth::November,
+        ReminderMonth::December,
+    ];
+
+    /// Gets the number of the month, starting from 1 for January.
+    #[inline]
+    #[must_use]
+    pub const fn number(self) -> u32 {
+        self as u32 + 1
+    }
+
+    /// Gets the most days the month can have, counting February 29.
+    #[inline]
+    #[must_use]
+    pub const fn max_days(self) -> u8 {
+        match self {
+            ReminderMonth::February => 29,
+            ReminderMonth::April
+            | ReminderMonth::June
+            | ReminderMonth::September
+            | ReminderMonth::November => 30,
+            _ => 31,
+        }
+    }
+
+    /// Gets the month with the given number, starting from 1 for January.
+    #[must_use]
+    pub fn from_number(number: u32) -> Option<Self> {
+        let index = usize::try_from(number.checked_sub(1)?).ok()?;
+        Self::ALL.get(index).copied()
+    }
+}
+
 impl Display for ReminderMonth {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
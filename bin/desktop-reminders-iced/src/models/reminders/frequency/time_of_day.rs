@@ -1,6 +1,13 @@
-use chrono::NaiveTime;
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+use chrono::{NaiveTime, Timelike};
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
+/// The time of day a reminder is set for.
 #[derive(Clone, Copy, PartialEq, Debug, Default, Serialize, Deserialize)]
 #[serde(tag = "kind")]
 pub enum ReminderTimeOfDay {
@@ -20,3 +27,81 @@ pub enum ReminderTimeOfDay {
         end: NaiveTime,
     },
 }
+
+impl ReminderTimeOfDay {
+    /// Gets the time the reminder starts at. All-day reminders start at
+    /// midnight.
+    #[inline]
+    #[must_use]
+    pub fn start(&self) -> NaiveTime {
+        match *self {
+            ReminderTimeOfDay::AllDay => NaiveTime::default(),
+            ReminderTimeOfDay::Time { time } => time,
+            ReminderTimeOfDay::TimeRange { start, .. } => start,
+        }
+    }
+}
+
+impl Display for ReminderTimeOfDay {
+    /// Writes the time of day like `12:30`, `12:30-16:30` or `all-day`.
+    /// Seconds are only written if they are not zero.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            ReminderTimeOfDay::AllDay => write!(f, "all-day"),
+            ReminderTimeOfDay::Time { time } => write_time(f, time),
+            ReminderTimeOfDay::TimeRange { start, end } => {
+                write_time(f, start)?;
+                write!(f, "-")?;
+                write_time(f, end)
+            }
+        }
+    }
+}
+
+impl FromStr for ReminderTimeOfDay {
+    type Err = ParseTimeOfDayError;
+
+    /// Parses a time of day written like `12:30`, a range of time written
+    /// like `12:30-16:30`, or `all-day`. Times use a 24-hour clock and can
+    /// have seconds, like `12:30:15`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("all-day") {
+            return Ok(ReminderTimeOfDay::AllDay);
+        }
+
+        match s.split_once('-') {
+            Some((start, end)) => Ok(ReminderTimeOfDay::TimeRange {
+                start: parse_time(start)?,
+                end: parse_time(end)?,
+            }),
+            None => Ok(ReminderTimeOfDay::Time {
+                time: parse_time(s)?,
+            }),
+        }
+    }
+}
+
+/// Writes a time like `12:30`, or `12:30:15` if it has seconds.
+fn write_time(f: &mut Formatter<'_>, time: NaiveTime) -> std::fmt::Result {
+    match time.second() {
+        0 => write!(f, "{}", time.format("%H:%M")),
+        _ => write!(f, "{}", time.format("%H:%M:%S")),
+    }
+}
+
+/// Parses a time like `12:30` or `12:30:15`.
+fn parse_time(s: &str) -> Result<NaiveTime, ParseTimeOfDayError> {
+    let s = s.trim();
+    NaiveTime::parse_from_str(s, "%H:%M")
+        .or_else(|_| NaiveTime::parse_from_str(s, "%H:%M:%S"))
+        .map_err(|_| ParseTimeOfDayError::InvalidTime(s.to_string()))
+}
+
+/// An error parsing a [`ReminderTimeOfDay`].
+#[derive(Clone, PartialEq, Eq, Debug, Error)]
+pub enum ParseTimeOfDayError {
+    /// A time was not written like `12:30`.
+    #[error("expected a time like \"12:30\", \"12:30-16:30\" or \"all-day\", got {0:?}")]
+    InvalidTime(String),
+}
@@ -1,4 +1,10 @@
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
 use crate::models::reminders::{ReminderMonth, ReminderTimeOfDay};
 
@@ -16,10 +22,22 @@ pub struct ReminderFrequencyYearly {
 pub struct ReminderYearlyDate {
     /// The month of the year the reminder is set for.
     pub month: ReminderMonth,
-    /// The day of the month the reminder is set for. This day might not exist.
+    /// The day of the month the reminder is set for. February 29 only exists
+    /// in leap years.
     pub date: u8,
 }
 
+impl ReminderYearlyDate {
+    /// Creates a day of the year, or `None` if the day is not in the month in
+    /// any year, like February 30.
+    #[must_use]
+    pub fn new(month: ReminderMonth, date: u8) -> Option<Self> {
+        (1..=month.max_days())
+            .contains(&date)
+            .then_some(Self { month, date })
+    }
+}
+
 impl Default for ReminderYearlyDate {
     fn default() -> Self {
         Self {
@@ -28,3 +46,38 @@ impl Default for ReminderYearlyDate {
         }
     }
 }
+
+impl Display for ReminderYearlyDate {
+    /// Writes the day of the year like `03-01`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:02}-{:02}", self.month.number(), self.date)
+    }
+}
+
+impl FromStr for ReminderYearlyDate {
+    type Err = ParseYearlyDateError;
+
+    /// Parses a day of the year written as a month and day, like `03-01`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseYearlyDateError::Invalid(s.to_string());
+        let (month, date) = s.trim().split_once('-').ok_or_else(error)?;
+        let month = month.parse().ok().and_then(ReminderMonth::from_number);
+        let date = date.parse().ok().filter(|date| (1..=31).contains(date));
+        match (month, date) {
+            (Some(month), Some(date)) => ReminderYearlyDate::new(month, date)
+                .ok_or(ParseYearlyDateError::Impossible(month, date)),
+            _ => Err(error()),
+        }
+    }
+}
+
+/// An error parsing a [`ReminderYearlyDate`].
+#[derive(Clone, PartialEq, Eq, Debug, Error)]
+pub enum ParseYearlyDateError {
+    /// The text is not a month and day.
+    #[error("expected a day of the year like \"12-25\", got {0:?}")]
+    Invalid(String),
+    /// The day is not in the month in any year, like `02-31`.
+    #[error("{0} has no day {1}")]
+    Impossible(ReminderMonth, u8),
+}
@@ -0,0 +1,31 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use super::ReminderId;
+
+/// A single occurrence of a reminder.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash, Serialize, Deserialize)]
+pub struct ReminderOccurrence {
+    /// The reminder that is occurring.
+    pub id: ReminderId,
+    /// The local time the occurrence starts at.
+    pub at: NaiveDateTime,
+}
+
+/// An occurrence of a reminder that needs the user's attention.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash, Serialize, Deserialize)]
+pub struct ReminderAlert {
+    /// The occurrence that triggered the alert.
+    pub occurrence: ReminderOccurrence,
+    /// Whether the occurrence came due while the app was not running.
+    pub missed: bool,
+}
+
+/// A record of a reminder being completed.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash, Serialize, Deserialize)]
+pub struct ReminderCompletion {
+    /// The reminder that was completed.
+    pub id: ReminderId,
+    /// The local time the reminder was completed at.
+    pub at: NaiveDateTime,
+}
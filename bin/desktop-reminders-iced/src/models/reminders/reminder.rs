@@ -1,14 +1,89 @@
+use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
 
-use super::ReminderFrequency;
+use super::{ReminderAction, ReminderContact, ReminderFrequency, ReminderId, ReminderOccurrence};
+
+/// How long a reminder is snoozed for by default, in minutes.
+pub const SNOOZE_MINUTES: i64 = 10;
 
 /// A potentially recurring reminder.
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub struct Reminder {
+    /// The ID of the reminder.
+    #[serde(default)]
+    pub id: ReminderId,
     /// The frequency of the reminder.
     pub frequency: ReminderFrequency,
     /// The message for the reminder.
     pub message: String,
     /// Whether the reminder has been completed.
     pub completed: bool,
+    /// The local time the reminder has been snoozed until, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snoozed_until: Option<NaiveDateTime>,
+    /// What to do when the reminder fires.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub actions: Vec<ReminderAction>,
+    /// The contact the reminder was imported from, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contact: Option<ReminderContact>,
+}
+
+impl Reminder {
+    /// Gets the occurrences of this reminder (including snoozes) that start
+    /// after `after` and no later than `until`, in chronological order.
+    /// Completed reminders have no occurrences.
+    pub fn occurrences_between(
+        &self,
+        after: NaiveDateTime,
+        until: NaiveDateTime,
+    ) -> Vec<ReminderOccurrence> {
+        if self.completed {
+            return Vec::new();
+        }
+
+        let mut occurrences: Vec<_> = self
+            .frequency
+            .occurrences_between(after, until)
+            .chain(self.snoozed_until.filter(|&at| after < at && at <= until))
+            .map(|at| ReminderOccurrence { id: self.id, at })
+            .collect();
+        occurrences.sort();
+        occurrences
+    }
+
+    /// Gets the start of the next occurrence of this reminder (including
+    /// snoozes) after `after`, if there is one.
+    pub fn next_occurrence_after(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        if self.completed {
+            return None;
+        }
+
+        let snoozed = self.snoozed_until.filter(|&at| after < at);
+        let next = self.frequency.next_occurrence_after(after);
+        match (next, snoozed) {
+            (Some(next), Some(snoozed)) => Some(next.min(snoozed)),
+            (next, snoozed) => next.or(snoozed),
+        }
+    }
+
+    /// Gets the local time this reminder became overdue, if it is overdue.
+    /// Only reminders that occur once can be overdue, since recurring
+    /// reminders move on to their next occurrence.
+    pub fn overdue_since(&self, now: NaiveDateTime) -> Option<NaiveDateTime> {
+        let ReminderFrequency::Once(once) = &self.frequency else {
+            return None;
+        };
+        if self.completed {
+            return None;
+        }
+        if self.snoozed_until.is_some_and(|until| now < until) {
+            return None;
+        }
+
+        let due = self
+            .snoozed_until
+            .unwrap_or_else(|| once.date.and_time(once.time.start()));
+        (due <= now).then_some(due)
+    }
 }
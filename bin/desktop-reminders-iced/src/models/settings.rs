@@ -0,0 +1,11 @@
+mod app;
+mod digest;
+mod mqtt;
+mod quiet_hours;
+mod web;
+
+pub use app::*;
+pub use digest::*;
+pub use mqtt::*;
+pub use quiet_hours::*;
+pub use web::*;
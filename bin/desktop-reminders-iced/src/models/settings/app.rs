@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+
+use super::{DigestSettings, MqttSettings, QuietHoursSettings, WebSettings};
+
+/// The user's settings for the app.
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct AppSettings {
+    /// When alerts should be held back.
+    #[serde(default)]
+    pub quiet_hours: QuietHoursSettings,
+    /// How the web dashboard is served.
+    #[serde(default)]
+    pub web: WebSettings,
+    /// How events are published to an MQTT broker.
+    #[serde(default)]
+    pub mqtt: MqttSettings,
+    /// How the morning digest email is sent.
+    #[serde(default)]
+    pub digest: DigestSettings,
+}
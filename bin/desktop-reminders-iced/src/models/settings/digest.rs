@@ -0,0 +1,97 @@
+use std::path::PathBuf;
+
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+
+/// The port SMTP servers accept submissions on by default.
+pub const DEFAULT_SMTP_PORT: u16 = 587;
+
+/// Settings for the morning digest email.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DigestSettings {
+    /// Whether the digest is sent.
+    pub enabled: bool,
+    /// The local time the digest is sent at each day.
+    pub send_at: NaiveTime,
+    /// The address the digest is sent from, like `Reminders <me@example.com>`.
+    pub from: String,
+    /// The addresses the digest is sent to.
+    pub to: Vec<String>,
+    /// The template for the subject of the digest.
+    pub subject: String,
+    /// A file containing the template for the body of the digest. The
+    /// built-in template is used if this is not set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub template: Option<PathBuf>,
+    /// The server the digest is sent through.
+    pub smtp: SmtpSettings,
+}
+
+impl DigestSettings {
+    /// Checks whether the digest should be sent at the given local time,
+    /// given the date it was last sent on.
+    #[must_use]
+    pub fn is_due(&self, last_sent: Option<NaiveDate>, now: NaiveDateTime) -> bool {
+        self.enabled
+            && self.send_at <= now.time()
+            && last_sent.is_none_or(|last_sent| last_sent < now.date())
+    }
+}
+
+impl Default for DigestSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            send_at: NaiveTime::from_hms_opt(7, 0, 0).unwrap(),
+            from: String::new(),
+            to: Vec::new(),
+            subject: "Reminders for {{date}}".into(),
+            template: None,
+            smtp: SmtpSettings::default(),
+        }
+    }
+}
+
+/// Settings for connecting to an SMTP server.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SmtpSettings {
+    /// The host name of the server.
+    pub host: String,
+    /// The port of the server.
+    pub port: u16,
+    /// How the connection to the server is secured.
+    pub security: SmtpSecurity,
+    /// The user name to log in with, if the server requires one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    /// The password to log in with.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+}
+
+impl Default for SmtpSettings {
+    fn default() -> Self {
+        Self {
+            host: "localhost".into(),
+            port: DEFAULT_SMTP_PORT,
+            security: SmtpSecurity::default(),
+            username: None,
+            password: None,
+        }
+    }
+}
+
+/// How the connection to an SMTP server is secured.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SmtpSecurity {
+    /// The connection is upgraded with `STARTTLS`.
+    #[default]
+    StartTls,
+    /// The connection uses TLS from the start, usually on port 465.
+    Tls,
+    /// The connection is not encrypted. Only use this for local servers.
+    None,
+}
@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+
+/// The port MQTT brokers listen on by default.
+pub const DEFAULT_MQTT_PORT: u16 = 1883;
+
+/// Settings for publishing reminder events to an MQTT broker.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MqttSettings {
+    /// Whether events are published.
+    pub enabled: bool,
+    /// The host name of the broker.
+    pub host: String,
+    /// The port of the broker.
+    pub port: u16,
+    /// The client ID the app connects with.
+    pub client_id: String,
+    /// The user name to connect with, if the broker requires one.
+    pub username: Option<String>,
+    /// The password to connect with.
+    pub password: Option<String>,
+    /// The prefix of every topic. Events are published to
+    /// `{prefix}/due`, `{prefix}/completed` and `{prefix}/snoozed`, and
+    /// commands are received on `{prefix}/command`.
+    pub topic_prefix: String,
+}
+
+impl Default for MqttSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            host: "localhost".into(),
+            port: DEFAULT_MQTT_PORT,
+            client_id: "desktop-reminders".into(),
+            username: None,
+            password: None,
+            topic_prefix: "desktop-reminders".into(),
+        }
+    }
+}
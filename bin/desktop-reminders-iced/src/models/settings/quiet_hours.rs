@@ -0,0 +1,108 @@
+use chrono::{Datelike, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+
+use crate::models::reminders::ReminderDaysOfWeek;
+
+/// Settings for when alerts should be held back. Alerts that come due during
+/// quiet hours are queued and delivered together once quiet hours end.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QuietHoursSettings {
+    /// Whether the daily quiet window is enabled.
+    pub enabled: bool,
+    /// When the daily quiet window starts.
+    pub start: NaiveTime,
+    /// When the daily quiet window ends. If this is before `start`, the window
+    /// continues past midnight.
+    pub end: NaiveTime,
+    /// Days that are quiet for the whole day.
+    pub days: ReminderDaysOfWeek,
+    /// The local time "do not disturb" was manually turned on until, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub do_not_disturb_until: Option<NaiveDateTime>,
+}
+
+impl QuietHoursSettings {
+    /// Checks whether alerts should be held back at the given local time.
+    #[must_use]
+    pub fn is_quiet(&self, at: NaiveDateTime) -> bool {
+        if self.do_not_disturb_until.is_some_and(|until| at < until) {
+            return true;
+        }
+        if self.days.contains(ReminderDaysOfWeek::from(at.weekday())) {
+            return true;
+        }
+        if !self.enabled {
+            return false;
+        }
+
+        let time = at.time();
+        if self.start <= self.end {
+            self.start <= time && time < self.end
+        } else {
+            self.start <= time || time < self.end
+        }
+    }
+}
+
+impl Default for QuietHoursSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            start: NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+            end: NaiveTime::from_hms_opt(7, 0, 0).unwrap(),
+            days: ReminderDaysOfWeek::empty(),
+            do_not_disturb_until: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDateTime, NaiveTime};
+
+    use crate::models::reminders::ReminderDaysOfWeek;
+
+    use super::QuietHoursSettings;
+
+    fn at(at: &str) -> NaiveDateTime {
+        at.parse().unwrap()
+    }
+
+    #[test]
+    fn is_quiet() {
+        let overnight = QuietHoursSettings {
+            enabled: true,
+            start: NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+            end: NaiveTime::from_hms_opt(7, 0, 0).unwrap(),
+            ..Default::default()
+        };
+        let weekends = QuietHoursSettings {
+            enabled: false,
+            days: ReminderDaysOfWeek::SATURDAY | ReminderDaysOfWeek::SUNDAY,
+            ..overnight.clone()
+        };
+        let do_not_disturb = QuietHoursSettings {
+            do_not_disturb_until: Some(at("2024-03-01T15:00:00")),
+            ..Default::default()
+        };
+
+        let cases = [
+            (&overnight, "2024-03-01T21:59:00", false),
+            (&overnight, "2024-03-01T22:00:00", true),
+            (&overnight, "2024-03-02T00:00:00", true),
+            (&overnight, "2024-03-02T06:59:59", true),
+            (&overnight, "2024-03-02T07:00:00", false),
+            (&overnight, "2024-03-02T12:00:00", false),
+            (&weekends, "2024-03-02T12:00:00", true),
+            (&weekends, "2024-03-03T00:00:00", true),
+            (&weekends, "2024-03-01T23:00:00", false),
+            (&weekends, "2024-03-04T06:00:00", false),
+            (&do_not_disturb, "2024-03-01T14:59:59", true),
+            (&do_not_disturb, "2024-03-01T15:00:00", false),
+        ];
+        for (settings, time, expected) in cases {
+            assert_eq!(settings.is_quiet(at(time)), expected, "{time} {settings:?}");
+        }
+    }
+}
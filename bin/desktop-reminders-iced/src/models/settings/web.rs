@@ -0,0 +1,26 @@
+use std::net::{Ipv4Addr, SocketAddr};
+
+use serde::{Deserialize, Serialize};
+
+/// The port the dashboard listens on by default.
+pub const DEFAULT_WEB_PORT: u16 = 8787;
+
+/// Settings for the read-only web dashboard.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebSettings {
+    /// Whether the dashboard is served.
+    pub enabled: bool,
+    /// The address the dashboard listens on. Only this machine can reach the
+    /// dashboard by default; use `0.0.0.0` to serve it to the local network.
+    pub address: SocketAddr,
+}
+
+impl Default for WebSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            address: SocketAddr::from((Ipv4Addr::LOCALHOST, DEFAULT_WEB_PORT)),
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+use crate::models::reminders::{
+    Reminder, ReminderAlert, ReminderCompletion, ReminderFrequency, ReminderId, ReminderOccurrence,
+};
+
+/// How many days of completions are kept.
+const COMPLETION_HISTORY_DAYS: i64 = 7;
+
+/// The persisted state of the app.
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct ReminderStore {
+    /// The last time the app was known to be running.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_seen: Option<DateTime<Local>>,
+    /// The reminders.
+    #[serde(default)]
+    pub reminders: Vec<Reminder>,
+    /// Alerts that came due during quiet hours and have not been delivered
+    /// yet.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub queued_alerts: Vec<ReminderAlert>,
+    /// When reminders were completed over the last few days.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub completions: Vec<ReminderCompletion>,
+    /// The last date the digest email was sent on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_digest: Option<NaiveDate>,
+}
+
+impl ReminderStore {
+    /// Gets an unused reminder ID.
+    #[must_use]
+    pub fn next_id(&self) -> ReminderId {
+        let max = self.reminders.iter().map(|reminder| reminder.id.0).max();
+        ReminderId(max.map_or(1, |max| max + 1))
+    }
+
+    /// Gets a reminder by its ID.
+    #[must_use]
+    pub fn get(&self, id: ReminderId) -> Option<&Reminder> {
+        self.reminders.iter().find(|reminder| reminder.id == id)
+    }
+
+    /// Gets a mutable reference to a reminder by its ID.
+    #[must_use]
+    pub fn get_mut(&mut self, id: ReminderId) -> Option<&mut Reminder> {
+        self.reminders.iter_mut().find(|reminder| reminder.id == id)
+    }
+
+    /// Records that an occurrence came due, clearing the reminder's snooze if
+    /// this occurrence is the snoozed one.
+    pub fn mark_due(&mut self, occurrence: &ReminderOccurrence) -> Option<&Reminder> {
+        let reminder = self.get_mut(occurrence.id)?;
+        if reminder
+            .snoozed_until
+            .is_some_and(|until| until <= occurrence.at)
+        {
+            reminder.snoozed_until = None;
+        }
+
+        Some(reminder)
+    }
+
+    /// Completes a reminder at the given local time. A reminder that occurs
+    /// once is marked as completed, while a recurring one only has its
+    /// current occurrence acknowledged so it keeps coming due.
+    pub fn complete(&mut self, id: ReminderId, at: NaiveDateTime) -> Option<&Reminder> {
+        let reminder = self.get_mut(id)?;
+        if matches!(reminder.frequency, ReminderFrequency::Once(_)) {
+            reminder.completed = true;
+        }
+        reminder.snoozed_until = None;
+        self.queued_alerts.retain(|alert| alert.occurrence.id != id);
+        self.record_completion(id, at);
+        self.get(id)
+    }
+
+    /// Undoes [`ReminderStore::complete`], marking a reminder as not
+    /// completed.
+    pub fn uncomplete(&mut self, id: ReminderId) -> Option<&Reminder> {
+        self.get_mut(id)?.completed = false;
+        self.forget_completion(id);
+        self.get(id)
+    }
+
+    /// Records that a reminder was completed at the given local time,
+    /// forgetting completions that are too old to be interesting.
+    pub fn record_completion(&mut self, id: ReminderId, at: NaiveDateTime) {
+        let cutoff = at - Duration::days(COMPLETION_HISTORY_DAYS);
+        self.completions.retain(|completion| completion.at > cutoff);
+        self.completions.push(ReminderCompletion { id, at });
+    }
+
+    /// Forgets the most recent completion of a reminder, if it was marked as
+    /// not completed again.
+    pub fn forget_completion(&mut self, id: ReminderId) {
+        if let Some(index) = self
+            .completions
+            .iter()
+            .rposition(|completion| completion.id == id)
+        {
+            self.completions.remove(index);
+        }
+    }
+
+    /// Adds a reminder to the store, assigning it a new ID.
+    pub fn add(&mut self, mut reminder: Reminder) -> ReminderId {
+        reminder.id = self.next_id();
+        let id = reminder.id;
+        self.reminders.push(reminder);
+        id
+    }
+
+    /// Removes a reminder from the store by its ID.
+    pub fn remove(&mut self, id: ReminderId) -> Option<Reminder> {
+        let index = self
+            .reminders
+            .iter()
+            .position(|reminder| reminder.id == id)?;
+        Some(self.reminders.remove(index))
+    }
+}
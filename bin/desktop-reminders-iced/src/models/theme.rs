@@ -1,5 +1,6 @@
 mod app;
 mod color;
 
+#[allow(unused_imports)]
 pub use app::*;
 pub use color::*;
@@ -2,6 +2,8 @@ use serde::{Deserialize, Serialize};
 
 use super::ConfigColor;
 
+/// The colors used by the app.
+#[allow(dead_code)]
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct AppTheme {
     background: ConfigColor,
@@ -0,0 +1,485 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+use rumqttc::{Client, Event, MqttOptions, Packet, Publish, QoS};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tracing::{debug, info, warn};
+
+use crate::{
+    actions::ActionPayload,
+    ipc::{IpcRequest, Request, Response, RpcError},
+    models::{
+        reminders::{Reminder, ReminderAlert, ReminderId, SNOOZE_MINUTES},
+        settings::MqttSettings,
+    },
+};
+
+/// How many requests can be waiting to be sent to the broker.
+const REQUEST_CAPACITY: usize = 64;
+
+/// How often the broker is pinged while the connection is idle.
+const KEEP_ALIVE: Duration = Duration::from_secs(30);
+
+/// How long to wait before reconnecting after the connection is lost.
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// Publishes reminder events to an MQTT broker and accepts commands from it,
+/// so home automation setups can react to reminders.
+///
+/// Events are published as JSON to `{prefix}/due`, `{prefix}/completed` and
+/// `{prefix}/snoozed`. Commands like `{"command":"complete","id":3}` or
+/// `{"command":"snooze","id":3,"minutes":15}` are received on
+/// `{prefix}/command`.
+pub struct MqttService {
+    client: Client,
+    prefix: String,
+    stopped: Arc<AtomicBool>,
+}
+
+impl MqttService {
+    /// Connects to the broker in the background, passing each command to
+    /// `on_request`. The connection is retried until the service is dropped.
+    pub fn start<F>(settings: &MqttSettings, on_request: F) -> color_eyre::Result<Self>
+    where
+        F: Fn(IpcRequest) + Send + Sync + 'static,
+    {
+        let mut options = MqttOptions::new(&settings.client_id, &settings.host, settings.port);
+        options.set_keep_alive(KEEP_ALIVE);
+        if let Some(username) = &settings.username {
+            let password = settings.password.clone().unwrap_or_default();
+            options.set_credentials(username, password);
+        }
+
+        let prefix = settings.topic_prefix.trim_end_matches('/').to_string();
+        let command_topic = format!("{prefix}/command");
+        let (client, mut connection) = Client::new(options, REQUEST_CAPACITY);
+        let stopped = Arc::new(AtomicBool::new(false));
+
+        // Commands wait for the app to handle them, so they are handled off
+        // the connection's thread to keep it responsive
+        let (command_tx, command_rx) = mpsc::channel::<Publish>();
+        thread::Builder::new()
+            .name("mqtt-commands".into())
+            .spawn(move || {
+                for publish in command_rx {
+                    handle_command(&publish.payload, &on_request);
+                }
+            })?;
+
+        thread::Builder::new().name("mqtt".into()).spawn({
+            let client = client.clone();
+            let stopped = stopped.clone();
+            move || {
+                for event in connection.iter() {
+                    if stopped.load(Ordering::Relaxed) {
+                        break;
+                    }
+
+                    match event {
+                        Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                            info!(topic = command_topic, "connected to MQTT broker");
+
+                            // Subscriptions do not survive reconnecting
+                            if let Err(error) =
+                                client.try_subscribe(&command_topic, QoS::AtLeastOnce)
+                            {
+                                warn!(%error, "failed to subscribe to commands");
+                            }
+                        }
+                        Ok(Event::Incoming(Packet::Publish(publish)))
+                            if publish.topic == command_topic =>
+                        {
+                            let _ = command_tx.send(publish);
+                        }
+                        Ok(_) => {}
+                        Err(error) => {
+                            warn!(%error, "lost connection to MQTT broker");
+                            thread::sleep(RECONNECT_DELAY);
+                        }
+                    }
+                }
+            }
+        })?;
+
+        Ok(Self {
+            client,
+            prefix,
+            stopped,
+        })
+    }
+
+    /// Publishes that an occurrence of a reminder came due.
+    pub fn publish_due(&self, reminder: &Reminder, alert: &ReminderAlert) {
+        let payload = ActionPayload::new(reminder, alert);
+        self.publish(
+            "due",
+            json!({
+                "id": payload.id,
+                "title": payload.title,
+                "occurrence": payload.occurrence,
+                "missed": payload.missed,
+            }),
+        );
+    }
+
+    /// Publishes that a reminder was completed.
+    pub fn publish_completed(&self, reminder: &Reminder) {
+        self.publish(
+            "completed",
+            json!({
+                "id": reminder.id,
+                "title": reminder.message,
+            }),
+        );
+    }
+
+    /// Publishes that a reminder was snoozed.
+    pub fn publish_snoozed(&self, reminder: &Reminder) {
+        let until = reminder
+            .snoozed_until
+            .map(|until| until.format("%Y-%m-%dT%H:%M:%S").to_string());
+        self.publish(
+            "snoozed",
+            json!({
+                "id": reminder.id,
+                "title": reminder.message,
+                "until": until,
+            }),
+        );
+    }
+
+    /// Publishes the events caused by a request that was applied to the
+    /// store, if any.
+    pub fn publish_applied(&self, request: &Request, result: &Result<Response, RpcError>) {
+        let Ok(Response::Reminder(reminder)) = result else {
+            return;
+        };
+
+        match request {
+            Request::Complete { undo: false, .. } => self.publish_completed(reminder),
+            Request::Snooze { .. } => self.publish_snoozed(reminder),
+            _ => {}
+        }
+    }
+
+    /// Publishes an event to `{prefix}/{name}` without waiting for it to be
+    /// sent.
+    fn publish(&self, name: &str, payload: Value) {
+        let topic = format!("{}/{name}", self.prefix);
+        let result = self
+            .client
+            .try_publish(&topic, QoS::AtLeastOnce, false, payload.to_string());
+        if let Err(error) = result {
+            warn!(topic, %error, "failed to publish event");
+        }
+    }
+}
+
+impl Drop for MqttService {
+    fn drop(&mut self) {
+        // Ends the connection and stops the background thread
+        self.stopped.store(true, Ordering::Relaxed);
+        let _ = self.client.try_disconnect();
+    }
+}
+
+impl std::fmt::Debug for MqttService {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MqttService")
+            .field("prefix", &self.prefix)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A command received on the command topic.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+enum MqttCommand {
+    /// Completes a reminder.
+    Complete { id: ReminderId },
+    /// Snoozes a reminder.
+    Snooze {
+        id: ReminderId,
+        #[serde(default = "default_snooze_minutes")]
+        minutes: i64,
+    },
+}
+
+fn default_snooze_minutes() -> i64 {
+    SNOOZE_MINUTES
+}
+
+impl From<MqttCommand> for Request {
+    fn from(command: MqttCommand) -> Self {
+        match command {
+            MqttCommand::Complete { id } => Request::Complete { id, undo: false },
+            MqttCommand::Snooze { id, minutes } => Request::Snooze { id, minutes },
+        }
+    }
+}
+
+/// Passes a command to the app and logs the result.
+fn handle_command(payload: &[u8], on_request: &impl Fn(IpcRequest)) {
+    let command: MqttCommand = match serde_json::from_slice(payload) {
+        Ok(command) => command,
+        Err(error) => {
+            warn!(%error, "ignoring invalid MQTT command");
+            return;
+        }
+    };
+
+    debug!(?command, "received MQTT command");
+    let (request, response) = IpcRequest::new(command.into());
+    on_request(request);
+    if let Err(error) = futures::executor::block_on(response.recv()) {
+        warn!(
+            code = error.code,
+            message = error.message,
+            "MQTT command failed"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{Read, Write},
+        net::{TcpListener, TcpStream},
+        sync::{mpsc, Arc, Mutex},
+        thread,
+        time::Duration,
+    };
+
+    use bytes::BytesMut;
+    use chrono::NaiveDate;
+    use rumqttc::{
+        mqttbytes, ConnAck, ConnectReturnCode, Packet, PubAck, Publish, QoS, SubAck,
+        SubscribeReasonCode,
+    };
+    use serde_json::{json, Value};
+
+    use crate::{
+        ipc::{Request, Response},
+        models::{
+            reminders::{
+                Reminder, ReminderAlert, ReminderFrequency, ReminderFrequencyDaily, ReminderId,
+                ReminderOccurrence, ReminderTimeOfDay, SNOOZE_MINUTES,
+            },
+            settings::MqttSettings,
+        },
+    };
+
+    use super::MqttService;
+
+    const MAX_PACKET_SIZE: usize = 1 << 16;
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    /// A broker that accepts a single client and acknowledges everything it
+    /// sends.
+    struct FakeBroker {
+        stream: Arc<Mutex<TcpStream>>,
+        packets: mpsc::Receiver<Packet>,
+    }
+
+    impl FakeBroker {
+        fn accept(listener: &TcpListener) -> Self {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = stream.try_clone().unwrap();
+            let stream = Arc::new(Mutex::new(stream));
+            let (tx, packets) = mpsc::channel();
+            thread::spawn({
+                let stream = stream.clone();
+                move || {
+                    let mut buffer = BytesMut::new();
+                    loop {
+                        let packet = match Packet::read(&mut buffer, MAX_PACKET_SIZE) {
+                            Ok(packet) => packet,
+                            Err(mqttbytes::Error::InsufficientBytes(_)) => {
+                                let mut chunk = [0; 1024];
+                                match reader.read(&mut chunk) {
+                                    Ok(0) | Err(_) => return,
+                                    Ok(read) => buffer.extend_from_slice(&chunk[..read]),
+                                }
+                                continue;
+                            }
+                            Err(error) => panic!("invalid packet: {error:?}"),
+                        };
+
+                        let reply = match &packet {
+                            Packet::Connect(_) => Some(Packet::ConnAck(ConnAck::new(
+                                ConnectReturnCode::Success,
+                                false,
+                            ))),
+                            Packet::Subscribe(subscribe) => Some(Packet::SubAck(SubAck::new(
+                                subscribe.pkid,
+                                vec![SubscribeReasonCode::Success(QoS::AtLeastOnce)],
+                            ))),
+                            Packet::Publish(publish) if publish.qos != QoS::AtMostOnce => {
+                                Some(Packet::PubAck(PubAck::new(publish.pkid)))
+                            }
+                            Packet::PingReq => Some(Packet::PingResp),
+                            _ => None,
+                        };
+                        if let Some(reply) = reply {
+                            write_packet(&stream, &reply);
+                        }
+                        if tx.send(packet).is_err() {
+                            return;
+                        }
+                    }
+                }
+            });
+
+            Self { stream, packets }
+        }
+
+        /// Sends a message to the client.
+        fn publish(&self, topic: &str, payload: Value) {
+            let publish = Publish::new(topic, QoS::AtMostOnce, payload.to_string());
+            write_packet(&self.stream, &Packet::Publish(publish));
+        }
+
+        /// Waits for the client to subscribe, returning the topic.
+        fn next_subscription(&self) -> String {
+            loop {
+                if let Packet::Subscribe(mut subscribe) = self.next_packet() {
+                    return subscribe.filters.remove(0).path;
+                }
+            }
+        }
+
+        /// Waits for the client to publish a message, returning its topic and
+        /// payload.
+        fn next_publish(&self) -> (String, Value) {
+            loop {
+                if let Packet::Publish(publish) = self.next_packet() {
+                    let payload = serde_json::from_slice(&publish.payload).unwrap();
+                    return (publish.topic, payload);
+                }
+            }
+        }
+
+        fn next_packet(&self) -> Packet {
+            self.packets.recv_timeout(TIMEOUT).unwrap()
+        }
+    }
+
+    fn write_packet(stream: &Mutex<TcpStream>, packet: &Packet) {
+        let mut buffer = BytesMut::new();
+        packet.write(&mut buffer, MAX_PACKET_SIZE).unwrap();
+        let _ = stream.lock().unwrap().write_all(&buffer);
+    }
+
+    fn reminder() -> Reminder {
+        Reminder {
+            id: ReminderId(3),
+            frequency: ReminderFrequency::Daily(ReminderFrequencyDaily {
+                time: ReminderTimeOfDay::AllDay,
+            }),
+            message: "Stretch".to_string(),
+            completed: false,
+            snoozed_until: None,
+            actions: Vec::new(),
+            contact: None,
+        }
+    }
+
+    #[test]
+    fn publishes_events_and_receives_commands() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let settings = MqttSettings {
+            enabled: true,
+            host: "127.0.0.1".into(),
+            port: listener.local_addr().unwrap().port(),
+            topic_prefix: "test/".into(),
+            ..Default::default()
+        };
+        let (request_tx, request_rx) = mpsc::channel();
+        let request_tx = Mutex::new(request_tx);
+        let service = MqttService::start(&settings, move |request| {
+            let _ = request_tx.lock().unwrap().send(request);
+        })
+        .unwrap();
+        let broker = FakeBroker::accept(&listener);
+        assert_eq!(broker.next_subscription(), "test/command");
+
+        // Commands are passed to the app
+        broker.publish("test/command", json!({ "command": "complete", "id": 3 }));
+        let complete = request_rx.recv_timeout(TIMEOUT).unwrap();
+        assert!(matches!(
+            complete.request,
+            Request::Complete {
+                id: ReminderId(3),
+                undo: false,
+            }
+        ));
+
+        // Events are still published while a command is being handled
+        let occurrence = ReminderOccurrence {
+            id: ReminderId(3),
+            at: NaiveDate::from_ymd_opt(2024, 3, 1)
+                .unwrap()
+                .and_hms_opt(9, 0, 0)
+                .unwrap(),
+        };
+        let alert = ReminderAlert {
+            occurrence,
+            missed: true,
+        };
+        service.publish_due(&reminder(), &alert);
+        assert_eq!(
+            broker.next_publish(),
+            (
+                "test/due".to_string(),
+                json!({
+                    "id": 3,
+                    "title": "Stretch",
+                    "occurrence": "2024-03-01T09:00:00",
+                    "missed": true,
+                })
+            )
+        );
+
+        service.publish_applied(&complete.request, &Ok(Response::Reminder(reminder())));
+        assert_eq!(
+            broker.next_publish(),
+            (
+                "test/completed".to_string(),
+                json!({ "id": 3, "title": "Stretch" })
+            )
+        );
+        complete.respond(Ok(Response::None));
+
+        // Snoozing without a duration uses the default
+        broker.publish("test/command", json!({ "command": "snooze", "id": 3 }));
+        let snooze = request_rx.recv_timeout(TIMEOUT).unwrap();
+        assert!(matches!(
+            snooze.request,
+            Request::Snooze {
+                id: ReminderId(3),
+                minutes: SNOOZE_MINUTES,
+            }
+        ));
+
+        let snoozed = Reminder {
+            snoozed_until: Some(occurrence.at),
+            ..reminder()
+        };
+        service.publish_applied(&snooze.request, &Ok(Response::Reminder(snoozed)));
+        assert_eq!(
+            broker.next_publish(),
+            (
+                "test/snoozed".to_string(),
+                json!({ "id": 3, "title": "Stretch", "until": "2024-03-01T09:00:00" })
+            )
+        );
+        snooze.respond(Ok(Response::None));
+    }
+}
@@ -0,0 +1,49 @@
+use notify_rust::Notification;
+
+use crate::models::reminders::{Reminder, ReminderAlert};
+
+/// The name notifications are sent from.
+const APP_NAME: &str = "Reminders";
+
+/// Shows a desktop notification for an alert.
+pub fn notify_alert(reminder: &Reminder, alert: &ReminderAlert) -> color_eyre::Result<()> {
+    let summary = if alert.missed {
+        format!("Missed: {}", reminder.message)
+    } else {
+        reminder.message.clone()
+    };
+    let body = alert
+        .occurrence
+        .at
+        .format("%a %-d %b, %-I:%M %p")
+        .to_string();
+
+    Notification::new()
+        .appname(APP_NAME)
+        .summary(&summary)
+        .body(&body)
+        .show()?;
+    Ok(())
+}
+
+/// Shows a single desktop notification summarizing alerts that were held back
+/// during quiet hours.
+pub fn notify_summary<'a>(
+    reminders: impl IntoIterator<Item = &'a Reminder>,
+) -> color_eyre::Result<()> {
+    let messages: Vec<_> = reminders
+        .into_iter()
+        .map(|reminder| reminder.message.as_str())
+        .collect();
+    let summary = match messages.len() {
+        1 => "1 reminder came due during quiet hours".to_string(),
+        count => format!("{count} reminders came due during quiet hours"),
+    };
+
+    Notification::new()
+        .appname(APP_NAME)
+        .summary(&summary)
+        .body(&messages.join("\n"))
+        .show()?;
+    Ok(())
+}
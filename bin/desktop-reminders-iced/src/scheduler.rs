@@ -0,0 +1,294 @@
+use std::{collections::BTreeSet, time::Instant};
+
+use chrono::{Duration, Local, NaiveDateTime};
+use tracing::{info, warn};
+
+use crate::models::reminders::{Reminder, ReminderAlert, ReminderOccurrence};
+
+/// How often the scheduler should be polled to notice clock discontinuities,
+/// even if no reminders are due.
+pub const CLOCK_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// How often the time the app was last running is saved while nothing else
+/// changes, so occurrences missed after a crash are caught up on from a
+/// recent time.
+pub const LAST_SEEN_SAVE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5 * 60);
+
+/// How far (in seconds) the system clock may drift from the monotonic clock
+/// between polls before it is treated as a discontinuity.
+const MAX_CLOCK_DRIFT_SECS: i64 = 5;
+
+/// How late (in seconds) an occurrence can be reported before it is
+/// considered missed.
+const MISSED_AFTER_SECS: i64 = 60;
+
+/// A reading of both the system clock and the monotonic clock.
+#[derive(Clone, Copy, Debug)]
+pub struct ClockSample {
+    /// The local time according to the system clock.
+    pub wall: NaiveDateTime,
+    /// The time according to the monotonic clock.
+    pub monotonic: Instant,
+}
+
+impl ClockSample {
+    /// Reads the current time.
+    #[inline]
+    pub fn now() -> Self {
+        Self {
+            wall: Local::now().naive_local(),
+            monotonic: Instant::now(),
+        }
+    }
+}
+
+/// Determines when reminders come due.
+///
+/// The scheduler keeps track of the (local) time up to which it has reported
+/// occurrences, so each occurrence is reported at most once even if the
+/// scheduler is polled irregularly or was not running when the occurrence
+/// started.
+///
+/// The system clock is compared against the monotonic clock on each poll to
+/// detect discontinuities such as suspend/resume or clock corrections.
+/// Occurrences crossed by a forward jump are reported once, and occurrences
+/// that are crossed again after a backward jump are not reported again.
+#[derive(Clone, Debug)]
+pub struct Scheduler {
+    checked_until: NaiveDateTime,
+    last_sample: Option<ClockSample>,
+    reported_ahead: BTreeSet<ReminderOccurrence>,
+}
+
+impl Scheduler {
+    /// Creates a new scheduler which has reported all occurrences up to and
+    /// including `checked_until`.
+    #[inline]
+    pub fn new(checked_until: NaiveDateTime) -> Self {
+        Self {
+            checked_until,
+            last_sample: None,
+            reported_ahead: BTreeSet::new(),
+        }
+    }
+
+    /// Gets the alerts for the occurrences that came due since the last poll,
+    /// in chronological order.
+    pub fn poll(&mut self, reminders: &[Reminder], now: ClockSample) -> Vec<ReminderAlert> {
+        if let Some(last_sample) = self.last_sample.replace(now) {
+            self.check_clock(reminders, last_sample, now);
+        }
+
+        if now.wall <= self.checked_until {
+            return Vec::new();
+        }
+
+        let mut due: Vec<_> = reminders
+            .iter()
+            .flat_map(|reminder| reminder.occurrences_between(self.checked_until, now.wall))
+            .filter(|occurrence| !self.reported_ahead.contains(occurrence))
+            .collect();
+        due.sort();
+
+        self.checked_until = now.wall;
+        self.reported_ahead
+            .retain(|occurrence| occurrence.at > now.wall);
+
+        let missed_after = Duration::seconds(MISSED_AFTER_SECS);
+        due.into_iter()
+            .map(|occurrence| ReminderAlert {
+                occurrence,
+                missed: now.wall - occurrence.at > missed_after,
+            })
+            .collect()
+    }
+
+    /// Gets the time the next occurrence comes due, if any.
+    pub fn next_due(&self, reminders: &[Reminder]) -> Option<NaiveDateTime> {
+        reminders
+            .iter()
+            .filter_map(|reminder| reminder.next_occurrence_after(self.checked_until))
+            .min()
+    }
+
+    /// Checks whether the system clock jumped since the last sample.
+    fn check_clock(&mut self, reminders: &[Reminder], last: ClockSample, now: ClockSample) {
+        let elapsed = now.monotonic.saturating_duration_since(last.monotonic);
+        let elapsed = Duration::from_std(elapsed).unwrap_or_else(|_| Duration::max_value());
+        let drift = (now.wall - last.wall) - elapsed;
+        if drift.num_seconds().abs() <= MAX_CLOCK_DRIFT_SECS {
+            return;
+        }
+
+        if drift > Duration::zero() {
+            // Occurrences crossed by the jump are reported by the next poll
+            info!(
+                drift_secs = drift.num_seconds(),
+                "system clock jumped forward or the system resumed from suspend"
+            );
+            return;
+        }
+
+        warn!(
+            drift_secs = drift.num_seconds(),
+            "system clock jumped backward"
+        );
+        if now.wall < self.checked_until {
+            // Remember what has already been reported so it is not reported
+            // again once the clock catches up
+            self.reported_ahead.extend(
+                reminders.iter().flat_map(|reminder| {
+                    reminder.occurrences_between(now.wall, self.checked_until)
+                }),
+            );
+            self.checked_until = now.wall;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+
+    use crate::models::reminders::{
+        Reminder, ReminderAlert, ReminderFrequency, ReminderFrequencyOnce, ReminderId,
+        ReminderTimeOfDay,
+    };
+
+    use super::{ClockSample, Scheduler};
+
+    fn at(hour: u32, minute: u32, second: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2024, 3, 1)
+            .unwrap()
+            .and_hms_opt(hour, minute, second)
+            .unwrap()
+    }
+
+    fn once(id: u64, hour: u32, minute: u32) -> Reminder {
+        Reminder {
+            id: ReminderId(id),
+            frequency: ReminderFrequency::Once(ReminderFrequencyOnce {
+                date: NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+                time: ReminderTimeOfDay::Time {
+                    time: NaiveTime::from_hms_opt(hour, minute, 0).unwrap(),
+                },
+            }),
+            message: format!("reminder {id}"),
+            completed: false,
+            snoozed_until: None,
+            actions: Vec::new(),
+            contact: None,
+        }
+    }
+
+    /// A clock that can be moved independently of the monotonic clock.
+    struct Clock {
+        start: Instant,
+        elapsed_secs: u64,
+    }
+
+    impl Clock {
+        fn new() -> Self {
+            Self {
+                start: Instant::now(),
+                elapsed_secs: 0,
+            }
+        }
+
+        /// Advances the monotonic clock and reads the given wall time.
+        fn sample(&mut self, elapsed_secs: u64, wall: NaiveDateTime) -> ClockSample {
+            self.elapsed_secs += elapsed_secs;
+            ClockSample {
+                wall,
+                monotonic: self.start + std::time::Duration::from_secs(self.elapsed_secs),
+            }
+        }
+    }
+
+    fn reported(alerts: &[ReminderAlert]) -> Vec<(u64, bool)> {
+        alerts
+            .iter()
+            .map(|alert| (alert.occurrence.id.0, alert.missed))
+            .collect()
+    }
+
+    #[test]
+    fn reports_on_time_occurrences_once() {
+        let reminders = [once(1, 10, 1)];
+        let mut clock = Clock::new();
+        let mut scheduler = Scheduler::new(at(10, 0, 0));
+
+        assert!(scheduler
+            .poll(&reminders, clock.sample(0, at(10, 0, 30)))
+            .is_empty());
+        let alerts = scheduler.poll(&reminders, clock.sample(30, at(10, 1, 0)));
+        assert_eq!(reported(&alerts), [(1, false)]);
+        assert!(scheduler
+            .poll(&reminders, clock.sample(30, at(10, 1, 30)))
+            .is_empty());
+    }
+
+    #[test]
+    fn forward_jump_reports_crossed_occurrences_once() {
+        let reminders = [once(1, 11, 0), once(2, 13, 0)];
+        let mut clock = Clock::new();
+        let mut scheduler = Scheduler::new(at(10, 0, 0));
+        scheduler.poll(&reminders, clock.sample(0, at(10, 0, 30)));
+
+        // The wall clock jumps two hours while only 30 seconds pass
+        let alerts = scheduler.poll(&reminders, clock.sample(30, at(12, 0, 30)));
+        assert_eq!(reported(&alerts), [(1, true)]);
+        assert!(scheduler
+            .poll(&reminders, clock.sample(30, at(12, 1, 0)))
+            .is_empty());
+        assert_eq!(scheduler.next_due(&reminders), Some(at(13, 0, 0)));
+    }
+
+    #[test]
+    fn backward_jump_does_not_report_again() {
+        let reminders = [once(1, 11, 0), once(2, 11, 15)];
+        let mut clock = Clock::new();
+        let mut scheduler = Scheduler::new(at(10, 59, 0));
+        scheduler.poll(&reminders, clock.sample(0, at(10, 59, 30)));
+        let alerts = scheduler.poll(&reminders, clock.sample(60, at(11, 0, 30)));
+        assert_eq!(reported(&alerts), [(1, false)]);
+
+        // The wall clock jumps back an hour, then catches up normally
+        assert!(scheduler
+            .poll(&reminders, clock.sample(30, at(10, 1, 0)))
+            .is_empty());
+        let alerts = scheduler.poll(&reminders, clock.sample(3600, at(11, 1, 0)));
+        assert!(alerts.is_empty(), "reported again: {alerts:?}");
+
+        // Occurrences past the previous check are still reported
+        let alerts = scheduler.poll(&reminders, clock.sample(840, at(11, 15, 0)));
+        assert_eq!(reported(&alerts), [(2, false)]);
+    }
+
+    #[test]
+    fn suspend_reports_missed_occurrences_once() {
+        let reminders = [once(1, 11, 0), once(2, 13, 59)];
+        let mut clock = Clock::new();
+        let mut scheduler = Scheduler::new(at(10, 0, 0));
+        scheduler.poll(&reminders, clock.sample(0, at(10, 0, 30)));
+
+        // The monotonic clock does not advance while the system is suspended
+        let alerts = scheduler.poll(&reminders, clock.sample(1, at(13, 59, 30)));
+        assert_eq!(reported(&alerts), [(1, true), (2, false)]);
+        assert!(scheduler
+            .poll(&reminders, clock.sample(30, at(14, 0, 0)))
+            .is_empty());
+    }
+
+    #[test]
+    fn next_due_skips_past_reminders() {
+        let reminders = [once(1, 9, 0), once(2, 12, 0)];
+        let scheduler = Scheduler::new(at(10, 0, 0));
+        assert_eq!(scheduler.next_due(&reminders), Some(at(12, 0, 0)));
+
+        let scheduler = Scheduler::new(at(12, 0, 0));
+        assert_eq!(scheduler.next_due(&reminders), None);
+    }
+}
@@ -1,5 +1,7 @@
-use std::{error::Error, sync::Arc};
+use std::{error::Error, sync::Arc, time::Duration};
 
+use chrono::Local;
+use color_eyre::eyre::bail;
 use iced_native::{renderer::Style, Color, Debug, Theme};
 use iced_wgpu::Settings as WgpuSettings;
 use iced_winit::{
@@ -7,18 +9,34 @@ use iced_winit::{
     winit::{
         dpi::{LogicalSize, PhysicalPosition},
         event::{Event, ModifiersState, WindowEvent},
-        event_loop::EventLoop,
+        event_loop::EventLoopBuilder,
         window::WindowBuilder,
     },
     Clipboard,
 };
-use tracing::{error, info, metadata::LevelFilter};
+use tracing::{error, info, metadata::LevelFilter, warn};
 use tracing_error::ErrorLayer;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Registry};
 
-use crate::{backend::AppPainter, ui::app::App};
+use crate::{
+    backend::{AppPainter, AppRuntime},
+    cli::Args,
+    dbus::DbusService,
+    ipc::{lock_path, socket_path, InstanceLock, IpcClient, IpcServer, Request},
+    mqtt::MqttService,
+    storage::{SettingsFile, StoreFile},
+    tray::TrayIcon,
+    ui::app::{App, AppMessage},
+    web::WebServer,
+};
+
+/// How many times to try reaching the running instance when launched again.
+const FORWARD_ATTEMPTS: u32 = 20;
+
+/// How long to wait between attempts to reach the running instance.
+const FORWARD_RETRY_DELAY: Duration = Duration::from_millis(100);
 
-pub fn start() -> color_eyre::Result<()> {
+pub fn start(args: Args) -> color_eyre::Result<()> {
     Registry::default()
         .with(
             EnvFilter::builder()
@@ -26,14 +44,36 @@ pub fn start() -> color_eyre::Result<()> {
                 .from_env_lossy(),
         )
         .with(ErrorLayer::default())
-        .with(tracing_subscriber::fmt::layer().compact())
+        .with(
+            tracing_subscriber::fmt::layer()
+                .compact()
+                .with_writer(std::io::stderr),
+        )
         .try_init()?;
 
-    run()
+    match args.command {
+        Some(command) => crate::cli::run(command),
+        None if args.daemon => crate::daemon::run(),
+        None => run(args.new),
+    }
 }
 
-fn run() -> color_eyre::Result<()> {
-    let event_loop = EventLoop::default();
+fn run(new_reminder: Option<String>) -> color_eyre::Result<()> {
+    // Only run one instance at a time. The lock is held until the process
+    // exits since the event loop never returns.
+    let Some(_lock) = InstanceLock::acquire(&lock_path())? else {
+        return forward(new_reminder);
+    };
+
+    // Load the reminders
+    let store_file = StoreFile::from_env()?;
+    info!(path = %store_file.path().display(), "Loading reminders");
+    let store = store_file.load()?;
+    let settings_file = SettingsFile::from_env()?;
+    info!(path = %settings_file.path().display(), "Loading settings");
+    let settings = settings_file.load()?;
+
+    let event_loop = EventLoopBuilder::<AppMessage>::with_user_event().build();
     let window = WindowBuilder::new()
         .with_title("Reminders")
         .with_inner_size(LogicalSize::new(300, 300))
@@ -44,12 +84,70 @@ fn run() -> color_eyre::Result<()> {
     let window = Arc::new(window);
 
     info!("Creating painter");
-    let settings = WgpuSettings::from_env();
+    let wgpu_settings = WgpuSettings::from_env();
     let mut painter =
-        futures::executor::block_on(unsafe { AppPainter::init(window.clone(), settings) })?;
+        futures::executor::block_on(unsafe { AppPainter::init(window.clone(), wgpu_settings) })?;
+
+    // Create the runtime for commands and subscriptions
+    let mut runtime = AppRuntime::new(event_loop.create_proxy())?;
 
     // Create program
-    let program = App::default();
+    let mut program =
+        runtime.enter(|| App::new(store_file.clone(), store, settings_file.clone(), settings));
+
+    // Serve requests from the CLI, scripts and the desktop
+    let (request_sender, requests) = tokio::sync::mpsc::unbounded_channel();
+    let send_request = move |request| {
+        let _ = request_sender.send(request);
+    };
+    program = program.with_requests(requests);
+    match IpcServer::bind(socket_path(), send_request.clone()) {
+        Ok(server) => program = program.with_ipc(server),
+        Err(error) => {
+            let error: &dyn Error = error.as_ref();
+            warn!(error, "not listening for requests");
+        }
+    }
+    let web_settings = program.settings().web.clone();
+    let _web = if web_settings.enabled {
+        WebServer::start(web_settings.address, send_request.clone())
+            .inspect_err(|error| {
+                let error: &dyn Error = error.as_ref();
+                warn!(error, "not serving the dashboard");
+            })
+            .ok()
+    } else {
+        None
+    };
+    let mqtt_settings = program.settings().mqtt.clone();
+    if mqtt_settings.enabled {
+        match MqttService::start(&mqtt_settings, send_request.clone()) {
+            Ok(service) => program = program.with_mqtt(service),
+            Err(error) => {
+                let error: &dyn Error = error.as_ref();
+                warn!(error, "not publishing events over MQTT");
+            }
+        }
+    }
+    match DbusService::start(send_request) {
+        Ok(service) => program = program.with_dbus(service),
+        Err(error) => {
+            let error: &dyn Error = error.as_ref();
+            warn!(error, "not exporting reminders on the session bus");
+        }
+    }
+    let proxy = event_loop.create_proxy();
+    match TrayIcon::start(move |action| {
+        let _ = proxy.send_event(AppMessage::Tray(action));
+    }) {
+        Ok(tray) => program = program.with_tray(tray),
+        Err(error) => {
+            let error: &dyn Error = error.as_ref();
+            warn!(error, "not showing an icon in the system tray");
+        }
+    }
+    program.sync_tray();
+    runtime.track(program.subscription());
     let mut clipboard = Clipboard::connect(window.as_ref());
     let mut debug = Debug::new();
 
@@ -64,6 +162,12 @@ fn run() -> color_eyre::Result<()> {
         &mut debug,
     );
 
+    // Deliver alerts for reminders missed while the app was not running
+    state.queue_message(AppMessage::Tick);
+    if new_reminder.is_some() {
+        state.queue_message(AppMessage::Activate(new_reminder));
+    }
+
     info!("Starting event loop");
     event_loop.run(move |event, _, control_flow| {
         control_flow.set_wait();
@@ -72,7 +176,7 @@ fn run() -> color_eyre::Result<()> {
             Event::WindowEvent { window_id, event } if window_id == window.id() => {
                 // Handle the event
                 match event {
-                    WindowEvent::CloseRequested => control_flow.set_exit(),
+                    WindowEvent::CloseRequested => state.queue_message(AppMessage::CloseRequested),
                     WindowEvent::Resized(_) | WindowEvent::ScaleFactorChanged { .. } => {
                         resize_requested = true
                     }
@@ -89,19 +193,36 @@ fn run() -> color_eyre::Result<()> {
                     state.queue_event(event);
                 }
             }
-            Event::MainEventsCleared if !state.is_queue_empty() => {
+            Event::UserEvent(message) => state.queue_message(message),
+            Event::MainEventsCleared => {
+                if state.is_queue_empty() {
+                    return;
+                }
+
                 // Update iced
-                let (_remaining_events, _command) = state.update(
-                    painter.viewport().logical_size(),
-                    iced_winit::conversion::cursor_position(cursor_pos, window.scale_factor()),
-                    painter.renderer_mut(),
-                    &Theme::Dark,
-                    &Style {
-                        text_color: Color::WHITE,
-                    },
-                    &mut clipboard,
-                    &mut debug,
-                );
+                let (events, command) = runtime.enter(|| {
+                    state.update(
+                        painter.viewport().logical_size(),
+                        iced_winit::conversion::cursor_position(cursor_pos, window.scale_factor()),
+                        painter.renderer_mut(),
+                        &Theme::Dark,
+                        &Style {
+                            text_color: Color::WHITE,
+                        },
+                        &mut clipboard,
+                        &mut debug,
+                    )
+                });
+
+                // Run any commands and update the subscriptions
+                runtime.broadcast(events);
+                if let Some(command) = command {
+                    if runtime.run_command(command, &window, &mut clipboard) {
+                        control_flow.set_exit();
+                    }
+                }
+                runtime.track(state.program().subscription());
+                state.program().sync_tray();
 
                 // Redraw the window
                 window.request_redraw();
@@ -128,7 +249,48 @@ fn run() -> color_eyre::Result<()> {
                     state.mouse_interaction(),
                 ));
             }
+            Event::LoopDestroyed => {
+                // Record when the app stopped running so missed reminders can
+                // be found on the next launch
+                let mut store = state.program().store().clone();
+                store.last_seen = Some(Local::now());
+                if let Err(error) = store_file.save(&store) {
+                    let error: &dyn Error = error.as_ref();
+                    error!(error, "failed to save reminders");
+                }
+                if let Err(error) = settings_file.save(state.program().settings()) {
+                    let error: &dyn Error = error.as_ref();
+                    error!(error, "failed to save settings");
+                }
+            }
             _ => {}
         }
     })
 }
+
+/// Forwards a launch to the running instance, which brings its window to the
+/// front.
+fn forward(new_reminder: Option<String>) -> color_eyre::Result<()> {
+    // Launches can only be forwarded over the socket
+    if !cfg!(unix) {
+        bail!("another instance is already running");
+    }
+
+    info!("Another instance is running, bringing it to the front");
+    let path = socket_path();
+
+    // The running instance might still be starting up
+    for _ in 0..FORWARD_ATTEMPTS {
+        if let Some(mut client) = IpcClient::connect(&path)? {
+            client.call(Request::Activate { new_reminder })?;
+            return Ok(());
+        }
+
+        std::thread::sleep(FORWARD_RETRY_DELAY);
+    }
+
+    bail!(
+        "another instance is running, but is not listening on {}",
+        path.display()
+    )
+}
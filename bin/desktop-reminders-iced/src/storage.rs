@@ -0,0 +1,523 @@
+use std::{
+    error::Error,
+    fs::File,
+    io::{BufWriter, ErrorKind, Write},
+    marker::PhantomData,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::SystemTime,
+};
+
+use color_eyre::eyre::{bail, eyre, Context, ContextCompat};
+use serde::{de::DeserializeOwned, Serialize};
+use tracing::{debug, error, info, warn};
+
+use crate::{
+    formats::{store_from_toml, store_to_toml, TomlError},
+    models::{settings::AppSettings, store::ReminderStore},
+};
+
+/// The environment variable that overrides the location of the store file.
+pub const STORE_PATH_VAR: &str = "DESKTOP_REMINDERS_STORE";
+
+/// The environment variable that overrides the location of the settings file.
+pub const SETTINGS_PATH_VAR: &str = "DESKTOP_REMINDERS_SETTINGS";
+
+/// The file that reminders are persisted to.
+pub type StoreFile = DataFile<ReminderStore>;
+
+/// The file that settings are persisted to.
+pub type SettingsFile = DataFile<AppSettings>;
+
+/// A value that can be persisted to a [`DataFile`].
+pub trait DataFileContents: Default + Serialize + DeserializeOwned {
+    /// Reads the value from TOML.
+    fn from_toml(source: &str) -> Result<Self, TomlError> {
+        Ok(toml::from_str(source)?)
+    }
+
+    /// Writes the value as TOML.
+    fn to_toml(&self) -> color_eyre::Result<String> {
+        Ok(toml::to_string(self)?)
+    }
+}
+
+impl DataFileContents for AppSettings {}
+
+impl DataFileContents for ReminderStore {
+    fn from_toml(source: &str) -> Result<Self, TomlError> {
+        store_from_toml(source)
+    }
+
+    fn to_toml(&self) -> color_eyre::Result<String> {
+        Ok(store_to_toml(self)?)
+    }
+}
+
+/// The format of a [`DataFile`], chosen by its extension.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DataFileFormat {
+    /// JSON, used for any extension other than `.toml`.
+    Json,
+    /// TOML, which is easier to edit by hand.
+    Toml,
+}
+
+impl DataFileFormat {
+    /// Gets the format of a file from its path.
+    #[must_use]
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension() {
+            Some(extension) if extension.eq_ignore_ascii_case("toml") => DataFileFormat::Toml,
+            _ => DataFileFormat::Json,
+        }
+    }
+}
+
+/// A JSON or TOML file containing a `T`. Clones of a file share the same
+/// saves, so values saved from several threads are written one at a time.
+#[derive(Debug)]
+pub struct DataFile<T> {
+    path: PathBuf,
+    saves: Arc<SaveState>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+/// Keeps track of the values being saved to a [`DataFile`].
+#[derive(Debug, Default)]
+struct SaveState {
+    /// The generation of the last value that was queued to be saved.
+    queued: AtomicU64,
+    /// The generation of the last value that was written, locked while a
+    /// value is being written.
+    written: Mutex<u64>,
+}
+
+/// Counts the temporary files created by this process so each one is unique.
+static TEMP_FILES: AtomicU64 = AtomicU64::new(0);
+
+impl<T> DataFile<T> {
+    /// Creates a new [`DataFile`] at the given path.
+    #[inline]
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            saves: Default::default(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Gets the path of the file.
+    #[inline]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Gets the format of the file.
+    #[inline]
+    pub fn format(&self) -> DataFileFormat {
+        DataFileFormat::from_path(&self.path)
+    }
+
+    /// Gets when the file was last modified and how long it is, if it exists.
+    fn stamp(&self) -> FileStamp {
+        let metadata = std::fs::metadata(&self.path).ok()?;
+        Some((metadata.modified().ok()?, metadata.len()))
+    }
+
+    /// Gets the file at the location specified by `var`, or at `file_name`
+    /// within `dir` otherwise.
+    fn from_env_or(var: &str, dir: Option<PathBuf>, file_name: &str) -> color_eyre::Result<Self> {
+        if let Some(path) = std::env::var_os(var) {
+            return Ok(Self::new(path));
+        }
+
+        let dir = dir.wrap_err_with(|| format!("failed to find a directory for {file_name}"))?;
+        let dir = dir.join(env!("CARGO_PKG_NAME"));
+
+        // A TOML file is used instead if the user created one
+        let toml_path = dir.join(file_name).with_extension("toml");
+        if toml_path.exists() {
+            return Ok(Self::new(toml_path));
+        }
+
+        Ok(Self::new(dir.join(file_name)))
+    }
+}
+
+impl<T> Clone for DataFile<T> {
+    fn clone(&self) -> Self {
+        Self {
+            path: self.path.clone(),
+            saves: self.saves.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> DataFile<T>
+where
+    T: DataFileContents,
+{
+    /// Loads the file. If the file does not exist yet, the default value is
+    /// returned. Errors in TOML files include the line and column they are
+    /// on.
+    pub fn load(&self) -> color_eyre::Result<T> {
+        debug!(path = %self.path.display(), "loading file");
+        let contents = match std::fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(Default::default()),
+            Err(error) => {
+                return Err(error)
+                    .wrap_err_with(|| format!("failed to open {}", self.path.display()))
+            }
+        };
+
+        match self.format() {
+            DataFileFormat::Json => serde_json::from_str(&contents)
+                .wrap_err_with(|| format!("failed to parse {}", self.path.display())),
+            DataFileFormat::Toml => {
+                T::from_toml(&contents).map_err(|error| match error.position(&contents) {
+                    Some((line, column)) => {
+                        eyre!("{}:{line}:{column}: {error}", self.path.display())
+                    }
+                    None => eyre!("{}: {error}", self.path.display()),
+                })
+            }
+        }
+    }
+
+    /// Saves the file. The value is written to a temporary file first so that
+    /// the existing file is never left partially written.
+    pub fn save(&self, value: &T) -> color_eyre::Result<()> {
+        self.save_queued(self.queue_save(), value)
+    }
+
+    /// Reserves a place for a value to be saved later with
+    /// [`DataFile::save_queued`]. Values are saved in the order their places
+    /// were reserved in.
+    pub fn queue_save(&self) -> u64 {
+        self.saves.queued.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    /// Saves a value queued with [`DataFile::queue_save`]. If a value queued
+    /// later was already written, this value is outdated and is not written.
+    pub fn save_queued(&self, generation: u64, value: &T) -> color_eyre::Result<()> {
+        let mut written = self
+            .saves
+            .written
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if *written > generation {
+            debug!(path = %self.path.display(), "skipping outdated save");
+            return Ok(());
+        }
+
+        self.write(value)?;
+        *written = generation;
+        Ok(())
+    }
+
+    /// Writes a value to the file through a temporary file.
+    fn write(&self, value: &T) -> color_eyre::Result<()> {
+        debug!(path = %self.path.display(), "saving file");
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)
+                .wrap_err_with(|| format!("failed to create {}", parent.display()))?;
+        }
+
+        let mut temp_path = self.path.clone().into_os_string();
+        let count = TEMP_FILES.fetch_add(1, Ordering::Relaxed);
+        temp_path.push(format!(".{}.{count}.tmp", std::process::id()));
+        let temp_path = PathBuf::from(temp_path);
+        let result = self.write_to(&temp_path, value).and_then(|()| {
+            std::fs::rename(&temp_path, &self.path)
+                .wrap_err_with(|| format!("failed to write {}", self.path.display()))
+        });
+        if result.is_err() {
+            let _ = std::fs::remove_file(&temp_path);
+        }
+
+        result
+    }
+
+    /// Writes a value to a path.
+    fn write_to(&self, path: &Path, value: &T) -> color_eyre::Result<()> {
+        let file =
+            File::create(path).wrap_err_with(|| format!("failed to create {}", path.display()))?;
+        let mut writer = BufWriter::new(file);
+        match self.format() {
+            DataFileFormat::Json => serde_json::to_writer_pretty(&mut writer, value)?,
+            DataFileFormat::Toml => writer.write_all(value.to_toml()?.as_bytes())?,
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+impl DataFile<ReminderStore> {
+    /// Gets the store file at its default location, or the location specified
+    /// by [`STORE_PATH_VAR`]. `reminders.toml` is used instead of
+    /// `reminders.json` if it exists.
+    pub fn from_env() -> color_eyre::Result<Self> {
+        Self::from_env_or(STORE_PATH_VAR, dirs::data_dir(), "reminders.json")
+    }
+}
+
+impl DataFile<AppSettings> {
+    /// Gets the settings file at its default location, or the location
+    /// specified by [`SETTINGS_PATH_VAR`].
+    pub fn from_env() -> color_eyre::Result<Self> {
+        Self::from_env_or(SETTINGS_PATH_VAR, dirs::config_dir(), "settings.json")
+    }
+}
+
+/// How many times a change is applied again when the file is changed by
+/// something else while it is being saved.
+const MAX_UPDATE_ATTEMPTS: usize = 3;
+
+/// When a file was last modified and how long it was, used to tell whether it
+/// was changed by something else.
+type FileStamp = Option<(SystemTime, u64)>;
+
+/// The contents of a [`DataFile`], which are reloaded when the file changes.
+///
+/// If the file is edited into something that cannot be loaded, the last
+/// contents that loaded successfully are kept and the file is not saved over
+/// until it is fixed.
+#[derive(Debug)]
+pub struct LoadedFile<T> {
+    file: DataFile<T>,
+    value: T,
+    stamp: FileStamp,
+    broken: bool,
+}
+
+impl<T> LoadedFile<T>
+where
+    T: DataFileContents,
+{
+    /// Loads a file, failing if it cannot be loaded.
+    pub fn load(file: DataFile<T>) -> color_eyre::Result<Self> {
+        let stamp = file.stamp();
+        let value = file.load()?;
+        Ok(Self {
+            file,
+            value,
+            stamp,
+            broken: false,
+        })
+    }
+
+    /// Gets the contents that were last loaded or saved.
+    #[inline]
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    /// Loads the file again if it changed since it was last loaded or saved.
+    /// Errors are logged, and the last contents that loaded successfully are
+    /// kept.
+    pub fn reload(&mut self) {
+        let stamp = self.file.stamp();
+        if stamp == self.stamp {
+            return;
+        }
+
+        self.stamp = stamp;
+        match self.file.load() {
+            Ok(value) => {
+                if self.broken {
+                    info!(path = %self.file.path().display(), "file can be loaded again");
+                }
+                self.value = value;
+                self.broken = false;
+            }
+            Err(error) => {
+                let error: &dyn Error = error.as_ref();
+                error!(error, "failed to reload file, keeping its last contents");
+                self.broken = true;
+            }
+        }
+    }
+
+    /// Changes the contents and saves them. The file is reloaded first so
+    /// changes made by other programs are not overwritten, and the change is
+    /// applied again if the file changes while it is being made. Nothing is
+    /// changed if the file cannot be loaded.
+    pub fn update<R>(&mut self, mut change: impl FnMut(&mut T) -> R) -> color_eyre::Result<R> {
+        for _ in 0..MAX_UPDATE_ATTEMPTS {
+            self.reload();
+            if self.broken {
+                bail!(
+                    "{} cannot be loaded, so it is not saved over until it is fixed",
+                    self.file.path().display()
+                );
+            }
+
+            let result = change(&mut self.value);
+            if self.file.stamp() != self.stamp {
+                warn!(path = %self.file.path().display(), "file changed while saving, retrying");
+                continue;
+            }
+
+            self.file.save(&self.value)?;
+            self.stamp = self.file.stamp();
+            return Ok(result);
+        }
+
+        bail!(
+            "{} kept changing while it was being saved",
+            self.file.path().display()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::models::{
+        reminders::{Reminder, ReminderFrequency, ReminderFrequencyDaily, ReminderTimeOfDay},
+        store::ReminderStore,
+    };
+
+    use super::{DataFile, LoadedFile, StoreFile};
+
+    fn temp_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("desktop-reminders-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    fn reminder(message: &str) -> Reminder {
+        Reminder {
+            id: Default::default(),
+            frequency: ReminderFrequency::Daily(ReminderFrequencyDaily {
+                time: ReminderTimeOfDay::AllDay,
+            }),
+            message: message.to_string(),
+            completed: false,
+            snoozed_until: None,
+            actions: Vec::new(),
+            contact: None,
+        }
+    }
+
+    fn messages(store: &ReminderStore) -> Vec<&str> {
+        store
+            .reminders
+            .iter()
+            .map(|reminder| reminder.message.as_str())
+            .collect()
+    }
+
+    /// Writes the file as another program would, making sure its stamp
+    /// changes.
+    fn write_externally(path: &PathBuf, contents: &str) {
+        let before = std::fs::metadata(path).and_then(|m| m.modified()).ok();
+        loop {
+            std::fs::write(path, contents).unwrap();
+            let after = std::fs::metadata(path).and_then(|m| m.modified()).ok();
+            if after != before {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn reload_keeps_last_good_contents() {
+        let path = temp_path("reload.toml");
+        let file: StoreFile = DataFile::new(&path);
+        let mut store = LoadedFile::load(file.clone()).unwrap();
+        store.update(|store| store.add(reminder("first"))).unwrap();
+
+        write_externally(
+            &path,
+            "[[reminders]]\nmessage = \"typo\nrepeat = \"daily\"\n",
+        );
+        store.reload();
+        assert_eq!(messages(store.value()), ["first"]);
+
+        // The broken file is not saved over
+        assert!(store.update(|store| store.add(reminder("second"))).is_err());
+        assert!(std::fs::read_to_string(&path).unwrap().contains("typo"));
+
+        // Fixing the file picks it up again
+        write_externally(
+            &path,
+            "[[reminders]]\nmessage = \"fixed\"\nrepeat = \"daily\"\n",
+        );
+        store.reload();
+        assert_eq!(messages(store.value()), ["fixed"]);
+    }
+
+    #[test]
+    fn format_follows_extension() {
+        let mut store = ReminderStore::default();
+        store.add(reminder("first"));
+
+        // TOML files are written and read as TOML
+        let path = temp_path("format.TOML");
+        let file: StoreFile = DataFile::new(&path);
+        file.save(&store).unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(contents.contains("[[reminders]]"), "{contents}");
+        assert!(toml::from_str::<toml::Table>(&contents).is_ok());
+        assert_eq!(file.load().unwrap(), store);
+
+        write_externally(
+            &path,
+            "[[reminders]]\nmessage = \"second\"\nschedule = \"daily @ 09:00\"\n",
+        );
+        assert_eq!(messages(&file.load().unwrap()), ["second"]);
+
+        // Anything else is JSON
+        let path = temp_path("format.dat");
+        let file: StoreFile = DataFile::new(&path);
+        file.save(&store).unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(serde_json::from_str::<serde_json::Value>(&contents).is_ok());
+        assert_eq!(file.load().unwrap(), store);
+    }
+
+    #[test]
+    fn update_keeps_changes_from_other_programs() {
+        let path = temp_path("update.json");
+        let file: StoreFile = DataFile::new(&path);
+        let mut store = LoadedFile::load(file.clone()).unwrap();
+        store.update(|store| store.add(reminder("first"))).unwrap();
+
+        // Another program adds a reminder without the store noticing yet
+        let mut other = file.load().unwrap();
+        other.add(reminder("other"));
+        let json = serde_json::to_string(&other).unwrap();
+        write_externally(&path, &json);
+
+        store.update(|store| store.add(reminder("mine"))).unwrap();
+        assert_eq!(messages(&file.load().unwrap()), ["first", "other", "mine"]);
+    }
+
+    #[test]
+    fn outdated_saves_are_skipped() {
+        let path = temp_path("queued.json");
+        let file: StoreFile = DataFile::new(&path);
+        let mut older = ReminderStore::default();
+        older.add(reminder("older"));
+        let mut newer = ReminderStore::default();
+        newer.add(reminder("newer"));
+
+        // The newer save finishes first
+        let older_save = file.queue_save();
+        let newer_save = file.clone().queue_save();
+        file.save_queued(newer_save, &newer).unwrap();
+        file.save_queued(older_save, &older).unwrap();
+        assert_eq!(messages(&file.load().unwrap()), ["newer"]);
+    }
+}
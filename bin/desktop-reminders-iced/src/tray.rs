@@ -0,0 +1,5 @@
+mod badge;
+mod icon;
+
+pub use badge::*;
+pub use icon::*;
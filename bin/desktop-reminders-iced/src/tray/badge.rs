@@ -0,0 +1,96 @@
+use ksni::Icon;
+
+/// The width and height of the badge, in pixels.
+const SIZE: i32 = 32;
+
+/// How many pixels each dot of a glyph covers along each side.
+const SCALE: i32 = 4;
+
+/// The width of a glyph, in dots.
+const GLYPH_WIDTH: i32 = 3;
+
+/// The height of a glyph, in dots.
+const GLYPH_HEIGHT: i32 = 5;
+
+/// The color of the badge, as ARGB.
+const BADGE_COLOR: [u8; 4] = [0xff, 0xda, 0x44, 0x53];
+
+/// The color of the count on the badge, as ARGB.
+const TEXT_COLOR: [u8; 4] = [0xff, 0xff, 0xff, 0xff];
+
+/// The glyphs for the count, as rows of 3 dots from the top. The last glyph
+/// is `+`.
+const GLYPHS: [[u8; GLYPH_HEIGHT as usize]; 11] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b010, 0b010, 0b010],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+    [0b000, 0b010, 0b111, 0b010, 0b000],
+];
+
+/// The index of the `+` glyph.
+const PLUS: usize = 10;
+
+/// Draws a round badge showing how many reminders are due. Counts above 9
+/// are shown as "9+".
+pub fn badge_icon(count: usize) -> Icon {
+    let glyphs: &[usize] = match count {
+        0..=9 => &[count],
+        _ => &[9, PLUS],
+    };
+
+    let mut data = vec![0; (SIZE * SIZE * 4) as usize];
+    let mut fill = |x: i32, y: i32, color: [u8; 4]| {
+        let index = ((y * SIZE + x) * 4) as usize;
+        data[index..index + 4].copy_from_slice(&color);
+    };
+
+    // The badge itself
+    let radius = SIZE / 2;
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            let (dx, dy) = (2 * (x - radius) + 1, 2 * (y - radius) + 1);
+            if dx * dx + dy * dy <= 4 * radius * radius {
+                fill(x, y, BADGE_COLOR);
+            }
+        }
+    }
+
+    // The count, centered with a dot of space between glyphs
+    let glyph_count = glyphs.len() as i32;
+    let width = (glyph_count * (GLYPH_WIDTH + 1) - 1) * SCALE;
+    let left = (SIZE - width) / 2;
+    let top = (SIZE - GLYPH_HEIGHT * SCALE) / 2;
+    for (position, &glyph) in glyphs.iter().enumerate() {
+        let glyph_left = left + position as i32 * (GLYPH_WIDTH + 1) * SCALE;
+        for (row, bits) in GLYPHS[glyph].iter().enumerate() {
+            for column in 0..GLYPH_WIDTH {
+                if bits & (1 << (GLYPH_WIDTH - 1 - column)) == 0 {
+                    continue;
+                }
+
+                for y in 0..SCALE {
+                    for x in 0..SCALE {
+                        fill(
+                            glyph_left + column * SCALE + x,
+                            top + row as i32 * SCALE + y,
+                            TEXT_COLOR,
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    Icon {
+        width: SIZE,
+        height: SIZE,
+        data,
+    }
+}
@@ -0,0 +1,200 @@
+use std::cell::Cell;
+
+use chrono::{Local, NaiveDateTime};
+use ksni::{
+    blocking::{Handle, TrayMethods},
+    menu::{StandardItem, SubMenu},
+    Category, Icon, MenuItem, Status, ToolTip, Tray,
+};
+use tracing::info;
+
+use super::badge_icon;
+
+/// The icon shown when no reminders are due.
+const ICON_NAME: &str = "appointment-soon";
+
+/// How long "do not disturb" can be turned on for from the tray, in hours.
+const DO_NOT_DISTURB_HOURS: &[i64] = &[1, 2, 4, 8];
+
+/// Something the user chose from the tray icon.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TrayAction {
+    /// Show the window.
+    Open,
+    /// Show the window on the page for adding a reminder.
+    AddReminder,
+    /// Turn "do not disturb" on for some hours, or off.
+    DoNotDisturb(Option<i64>),
+    /// Exit the app.
+    Quit,
+}
+
+/// What the tray icon shows.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct TrayState {
+    /// How many reminders are due.
+    pub due: usize,
+    /// The local time "do not disturb" is on until, if it is on.
+    pub do_not_disturb_until: Option<NaiveDateTime>,
+}
+
+/// A StatusNotifierItem icon in the system tray, showing how many reminders
+/// are due and offering quick actions.
+pub struct TrayIcon {
+    handle: Handle<RemindersTray>,
+    state: Cell<TrayState>,
+}
+
+impl TrayIcon {
+    /// Adds the icon to the tray, passing each action the user chooses to
+    /// `on_action`.
+    pub fn start<F>(on_action: F) -> color_eyre::Result<Self>
+    where
+        F: Fn(TrayAction) + Send + 'static,
+    {
+        let tray = RemindersTray {
+            state: TrayState::default(),
+            on_action: Box::new(on_action),
+        };
+        let handle = tray.spawn()?;
+        info!("added icon to the system tray");
+
+        Ok(Self {
+            handle,
+            state: Cell::default(),
+        })
+    }
+
+    /// Updates what the icon shows, if it changed.
+    pub fn update(&self, state: TrayState) {
+        if self.state.replace(state) == state {
+            return;
+        }
+
+        self.handle.update(|tray| tray.state = state);
+    }
+}
+
+impl Drop for TrayIcon {
+    fn drop(&mut self) {
+        self.handle.shutdown().wait();
+    }
+}
+
+impl std::fmt::Debug for TrayIcon {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TrayIcon")
+            .field("state", &self.state)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The tray icon's state, owned by the tray service.
+struct RemindersTray {
+    state: TrayState,
+    on_action: Box<dyn Fn(TrayAction) + Send>,
+}
+
+impl RemindersTray {
+    /// Describes how many reminders are due.
+    fn due_text(&self) -> String {
+        match self.state.due {
+            0 => "No reminders due".to_string(),
+            1 => "1 reminder due".to_string(),
+            due => format!("{due} reminders due"),
+        }
+    }
+
+    /// Creates a menu item that passes `action` to the app.
+    fn item(label: impl Into<String>, action: TrayAction) -> MenuItem<Self> {
+        StandardItem {
+            label: label.into(),
+            activate: Box::new(move |tray: &mut Self| (tray.on_action)(action)),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+impl Tray for RemindersTray {
+    fn id(&self) -> String {
+        env!("CARGO_PKG_NAME").into()
+    }
+
+    fn category(&self) -> Category {
+        Category::ApplicationStatus
+    }
+
+    fn title(&self) -> String {
+        format!("Reminders ({})", self.due_text().to_lowercase())
+    }
+
+    fn status(&self) -> Status {
+        match self.state.due {
+            0 => Status::Active,
+            _ => Status::NeedsAttention,
+        }
+    }
+
+    fn icon_name(&self) -> String {
+        match self.state.due {
+            0 => ICON_NAME.into(),
+            _ => String::new(),
+        }
+    }
+
+    fn icon_pixmap(&self) -> Vec<Icon> {
+        match self.state.due {
+            0 => Vec::new(),
+            due => vec![badge_icon(due)],
+        }
+    }
+
+    fn attention_icon_pixmap(&self) -> Vec<Icon> {
+        self.icon_pixmap()
+    }
+
+    fn tool_tip(&self) -> ToolTip {
+        ToolTip {
+            title: "Reminders".into(),
+            description: self.due_text(),
+            ..Default::default()
+        }
+    }
+
+    fn activate(&mut self, _x: i32, _y: i32) {
+        (self.on_action)(TrayAction::Open);
+    }
+
+    fn menu(&self) -> Vec<MenuItem<Self>> {
+        let now = Local::now().naive_local();
+        let do_not_disturb = match self.state.do_not_disturb_until {
+            Some(until) if now < until => {
+                format!("Do not disturb until {}", until.format("%-I:%M %p"))
+            }
+            _ => "Do not disturb".to_string(),
+        };
+
+        let mut do_not_disturb_items = vec![Self::item("Off", TrayAction::DoNotDisturb(None))];
+        do_not_disturb_items.extend(DO_NOT_DISTURB_HOURS.iter().map(|&hours| {
+            let label = match hours {
+                1 => "1 hour".to_string(),
+                hours => format!("{hours} hours"),
+            };
+            Self::item(label, TrayAction::DoNotDisturb(Some(hours)))
+        }));
+
+        vec![
+            Self::item("Open", TrayAction::Open),
+            Self::item("Add reminder", TrayAction::AddReminder),
+            SubMenu {
+                label: do_not_disturb,
+                submenu: do_not_disturb_items,
+                ..Default::default()
+            }
+            .into(),
+            MenuItem::Separator,
+            Self::item("Quit", TrayAction::Quit),
+        ]
+    }
+}
@@ -1,21 +1,40 @@
-use chrono::NaiveTime;
+use std::{sync::Arc, time::Instant};
+
+use chrono::{Duration, Local, NaiveDate, NaiveDateTime};
+use color_eyre::Report;
 use iced_native::{
-    alignment::Horizontal,
-    widget::{column, container, horizontal_rule, text, vertical_space},
-    Color, Command, Element, Length, Program, Theme,
+    alignment::{Horizontal, Vertical},
+    command, subscription,
+    theme::Button,
+    widget::{
+        button, column, container, horizontal_rule, horizontal_space, row, text, vertical_space,
+    },
+    window::{self, UserAttention},
+    Color, Command, Element, Length, Program, Subscription, Theme,
 };
+use tokio::sync::{mpsc::UnboundedReceiver, Mutex};
+use tracing::{error, info};
 
 use crate::{
-    models::reminders::{
-        Reminder, ReminderDaysOfWeek, ReminderFrequency, ReminderFrequencyDaily,
-        ReminderFrequencyWeekly, ReminderTimeOfDay,
+    actions::{is_superseded, spawn_actions},
+    dbus::DbusService,
+    digest::{send_digest, DigestReport, DIGEST_RETRY_INTERVAL},
+    ipc::{IpcRequest, IpcServer, Request, Response},
+    models::{
+        reminders::{Reminder, ReminderAlert, ReminderFrequency, SNOOZE_MINUTES},
+        settings::AppSettings,
+        store::ReminderStore,
     },
-    ui::pages::add_reminder_page,
+    mqtt::MqttService,
+    scheduler::{ClockSample, Scheduler, CLOCK_CHECK_INTERVAL, LAST_SEEN_SAVE_INTERVAL},
+    storage::{DataFile, DataFileContents, SettingsFile, StoreFile},
+    tray::{TrayAction, TrayIcon, TrayState},
+    ui::pages::{add_reminder_page, FrequencyType},
 };
 
 use super::{
-    components::{tab_list, Tab},
-    pages::reminder_page,
+    components::{tab_list, AlertAction, Tab},
+    pages::{reminder_page, settings_page},
 };
 
 /// The renderer for the app.
@@ -24,54 +43,305 @@ pub type Renderer = iced_wgpu::Renderer<Theme>;
 /// The entry to the app's UI.
 pub struct App {
     tab: AppTab,
-    reminders: Vec<Reminder>,
+    new_title: String,
+    new_frequency_type: FrequencyType,
+    new_schedule: String,
+    quick_add: String,
+    store_file: StoreFile,
+    store: ReminderStore,
+    settings_file: SettingsFile,
+    settings: AppSettings,
+    scheduler: Scheduler,
+    last_saved: Instant,
+    digest_attempt: Option<Instant>,
+    alerts: Vec<ReminderAlert>,
+    quiet_summary: Option<usize>,
+    requests: Option<Arc<Mutex<UnboundedReceiver<IpcRequest>>>>,
+    ipc: Option<IpcServer>,
+    dbus: Option<DbusService>,
+    mqtt: Option<MqttService>,
+    tray: Option<TrayIcon>,
 }
 
 impl App {
+    /// Creates a new app for the given store and settings. Reminders that
+    /// came due while the app was not running are delivered on the first
+    /// [`AppMessage::Tick`].
+    pub fn new(
+        store_file: StoreFile,
+        store: ReminderStore,
+        settings_file: SettingsFile,
+        settings: AppSettings,
+    ) -> Self {
+        let now = ClockSample::now();
+        let last_seen = store
+            .last_seen
+            .map_or(now.wall, |last_seen| last_seen.naive_local());
+        Self {
+            tab: Default::default(),
+            new_title: String::new(),
+            new_frequency_type: FrequencyType::default(),
+            new_schedule: String::new(),
+            quick_add: String::new(),
+            store_file,
+            store,
+            settings_file,
+            settings,
+            scheduler: Scheduler::new(last_seen),
+            last_saved: Instant::now(),
+            digest_attempt: None,
+            alerts: Vec::new(),
+            quiet_summary: None,
+            requests: None,
+            ipc: None,
+            dbus: None,
+            mqtt: None,
+            tray: None,
+        }
+    }
+
+    /// Handles requests sent from outside the app through `requests`.
+    pub fn with_requests(mut self, requests: UnboundedReceiver<IpcRequest>) -> Self {
+        self.requests = Some(Arc::new(Mutex::new(requests)));
+        self
+    }
+
+    /// Tells subscribers to `server` when the reminders change.
+    pub fn with_ipc(mut self, server: IpcServer) -> Self {
+        self.ipc = Some(server);
+        self
+    }
+
+    /// Emits signals from `service` when reminders come due or change.
+    pub fn with_dbus(mut self, service: DbusService) -> Self {
+        self.dbus = Some(service);
+        self
+    }
+
+    /// Publishes events to `service` when reminders come due, are completed
+    /// or are snoozed.
+    pub fn with_mqtt(mut self, service: MqttService) -> Self {
+        self.mqtt = Some(service);
+        self
+    }
+
+    /// Shows how many reminders are due on `tray`, and hides the window
+    /// instead of exiting when it is closed.
+    pub fn with_tray(mut self, tray: TrayIcon) -> Self {
+        self.tray = Some(tray);
+        self
+    }
+
     /// Gets the background color of the app.
     pub fn background_color(&self) -> Color {
         Color::BLACK
     }
-}
 
-impl Default for App {
-    fn default() -> Self {
-        let mut reminders = vec![
-            Reminder {
-                frequency: ReminderFrequency::Weekly(ReminderFrequencyWeekly {
-                    days: ReminderDaysOfWeek::WEEKDAYS,
-                    time: ReminderTimeOfDay::AllDay,
-                }),
-                message: "Do work".to_string(),
-                completed: false,
-            },
-            Reminder {
-                frequency: ReminderFrequency::Weekly(ReminderFrequencyWeekly {
-                    days: ReminderDaysOfWeek::WEEKDAYS,
-                    time: ReminderTimeOfDay::TimeRange {
-                        start: NaiveTime::from_hms_opt(12, 30, 0).unwrap(),
-                        end: NaiveTime::from_hms_opt(16, 30, 0).unwrap(),
-                    },
-                }),
-                message: "Dunno".to_string(),
-                completed: false,
+    /// Gets the store containing the app's reminders.
+    pub fn store(&self) -> &ReminderStore {
+        &self.store
+    }
+
+    /// Gets the user's settings.
+    pub fn settings(&self) -> &AppSettings {
+        &self.settings
+    }
+
+    /// Updates the tray icon to match the app's state.
+    pub fn sync_tray(&self) {
+        if let Some(tray) = &self.tray {
+            tray.update(TrayState {
+                due: self.alerts.len() + self.store.queued_alerts.len(),
+                do_not_disturb_until: self.settings.quiet_hours.do_not_disturb_until,
+            });
+        }
+    }
+
+    /// Gets the subscriptions the app is interested in.
+    pub fn subscription(&self) -> Subscription<AppMessage> {
+        let mut subscriptions = vec![scheduler_ticks(
+            self.scheduler.next_due(&self.store.reminders),
+        )];
+        if let Some(requests) = &self.requests {
+            subscriptions.push(external_requests(requests.clone()));
+        }
+
+        Subscription::batch(subscriptions)
+    }
+
+    /// Checks whether alerts are being held back at the given local time.
+    pub fn is_quiet(&self, at: NaiveDateTime) -> bool {
+        self.settings.quiet_hours.is_quiet(at)
+    }
+
+    /// Handles an occurrence that came due, running the reminder's actions if
+    /// `run_actions` is set.
+    fn fire(&mut self, alert: ReminderAlert, run_actions: bool) {
+        let Some(reminder) = self.store.mark_due(&alert.occurrence) else {
+            return;
+        };
+
+        info!(
+            id = %reminder.id,
+            message = reminder.message,
+            missed = alert.missed,
+            "reminder due"
+        );
+        if run_actions {
+            spawn_actions(reminder, &alert);
+        }
+        if let Some(dbus) = &self.dbus {
+            dbus.emit_due(reminder, &alert);
+        }
+        if let Some(mqtt) = &self.mqtt {
+            mqtt.publish_due(reminder, &alert);
+        }
+        if self.is_quiet(Local::now().naive_local()) {
+            self.store.queued_alerts.push(alert);
+        } else {
+            self.push_alert(alert);
+        }
+    }
+
+    /// Adds an alert, replacing any existing alert for the same reminder.
+    fn push_alert(&mut self, alert: ReminderAlert) {
+        self.alerts
+            .retain(|existing| existing.occurrence.id != alert.occurrence.id);
+        self.alerts.push(alert);
+    }
+
+    /// Checks for reminders that came due and delivers alerts that were held
+    /// back if quiet hours ended.
+    fn check_due(&mut self) -> Command<AppMessage> {
+        let now = ClockSample::now();
+        let due = self.scheduler.poll(&self.store.reminders, now);
+        let mut changed = !due.is_empty();
+        for (index, &alert) in due.iter().enumerate() {
+            self.fire(alert, !is_superseded(&due, index));
+        }
+
+        // Send the morning digest, even during quiet hours. Failed sends are
+        // retried after a while
+        let digest = if self
+            .settings
+            .digest
+            .is_due(self.store.last_digest, now.wall)
+            && self
+                .digest_attempt
+                .is_none_or(|at| at.elapsed() >= DIGEST_RETRY_INTERVAL)
+        {
+            self.digest_attempt = Some(Instant::now());
+            self.send_digest(now.wall)
+        } else {
+            Command::none()
+        };
+
+        if self.is_quiet(now.wall) {
+            let save = if changed {
+                self.save_store()
+            } else {
+                self.save_last_seen()
+            };
+            return Command::batch([digest, save]);
+        }
+
+        if !self.store.queued_alerts.is_empty() {
+            let queued = std::mem::take(&mut self.store.queued_alerts);
+            info!(
+                count = queued.len(),
+                "delivering alerts held back during quiet hours"
+            );
+            self.quiet_summary = Some(self.quiet_summary.unwrap_or(0) + queued.len());
+            for alert in queued {
+                self.push_alert(alert);
+            }
+            changed = true;
+        }
+
+        if !changed {
+            return Command::batch([digest, self.save_last_seen()]);
+        }
+
+        Command::batch([
+            digest,
+            Command::single(command::Action::Window(
+                window::Action::RequestUserAttention(Some(UserAttention::Informational)),
+            )),
+            self.save_store(),
+        ])
+    }
+
+    /// Sends the digest for the day of `now` in the background.
+    fn send_digest(&self, now: NaiveDateTime) -> Command<AppMessage> {
+        let settings = self.settings.digest.clone();
+        let report = DigestReport::new(&self.store, now);
+        let date = now.date();
+        Command::perform(
+            async move {
+                tokio::task::spawn_blocking(move || send_digest(&settings, &report))
+                    .await
+                    .map_err(Report::from)
+                    .and_then(|result| result)
+                    .map_err(Arc::new)
             },
-        ];
+            move |result| AppMessage::DigestSent(date, result),
+        )
+    }
 
-        for idx in 1..=100 {
-            reminders.push(Reminder {
-                frequency: ReminderFrequency::Daily(ReminderFrequencyDaily {
-                    time: ReminderTimeOfDay::AllDay,
-                }),
-                message: format!("Reminder {idx}"),
-                completed: false,
-            })
+    /// Brings the window to the front, opening the "New" tab if a title is
+    /// given.
+    fn activate(&mut self, new_reminder: Option<String>) -> Command<AppMessage> {
+        if let Some(title) = new_reminder {
+            self.tab = AppTab::AddReminder;
+            if !title.is_empty() {
+                self.new_title = title;
+            }
         }
 
-        Self {
-            tab: Default::default(),
-            reminders,
+        Command::batch(
+            [
+                window::Action::Minimize(false),
+                window::Action::ChangeMode(window::Mode::Windowed),
+                window::Action::GainFocus,
+            ]
+            .map(|action| Command::single(command::Action::Window(action))),
+        )
+    }
+
+    /// Saves the store in the background and tells subscribers about the
+    /// change.
+    fn save_store(&mut self) -> Command<AppMessage> {
+        if let Some(ipc) = &self.ipc {
+            ipc.notify_changed(&self.store.reminders);
+        }
+        if let Some(dbus) = &self.dbus {
+            dbus.emit_changed();
+        }
+
+        self.write_store()
+    }
+
+    /// Saves the store in the background if it has not been saved for a
+    /// while, recording that the app is still running.
+    fn save_last_seen(&mut self) -> Command<AppMessage> {
+        if self.last_saved.elapsed() < LAST_SEEN_SAVE_INTERVAL {
+            return Command::none();
         }
+
+        self.write_store()
+    }
+
+    /// Saves the store in the background without telling anyone.
+    fn write_store(&mut self) -> Command<AppMessage> {
+        self.last_saved = Instant::now();
+        let mut store = self.store.clone();
+        store.last_seen = Some(Local::now());
+        save(self.store_file.clone(), store)
+    }
+
+    /// Saves the settings in the background.
+    fn save_settings(&self) -> Command<AppMessage> {
+        save(self.settings_file.clone(), self.settings.clone())
     }
 }
 
@@ -85,21 +355,156 @@ impl Program for App {
                 self.tab = tab;
                 Command::none()
             }
+            AppMessage::NewTitleChanged(title) => {
+                self.new_title = title;
+                Command::none()
+            }
+            AppMessage::NewFrequencyTypeChanged(frequency_type) => {
+                self.new_frequency_type = frequency_type;
+                Command::none()
+            }
+            AppMessage::NewScheduleChanged(schedule) => {
+                self.new_schedule = schedule;
+                Command::none()
+            }
             AppMessage::AddReminder(reminder) => {
-                self.reminders.push(reminder);
+                self.store.add(reminder);
+                self.new_title.clear();
+                self.new_frequency_type = FrequencyType::default();
+                self.new_schedule.clear();
                 self.tab = AppTab::Reminders;
+                self.save_store()
+            }
+            AppMessage::QuickAddChanged(text) => {
+                self.quick_add = text;
+                Command::none()
+            }
+            AppMessage::QuickAdd(reminder) => {
+                self.store.add(reminder);
+                self.quick_add.clear();
+                self.save_store()
+            }
+            AppMessage::QuickAddEdit(title, frequency) => {
+                self.new_title = title;
+                if let Some(frequency) = frequency {
+                    self.new_frequency_type = FrequencyType::Schedule;
+                    self.new_schedule = frequency.to_string();
+                }
+                self.quick_add.clear();
+                self.tab = AppTab::AddReminder;
                 Command::none()
             }
             AppMessage::ReminderToggled(index, checked) => {
-                if let Some(reminder) = self.reminders.get_mut(index) {
-                    reminder.completed = checked;
+                let Some(reminder) = self.store.reminders.get_mut(index) else {
+                    return Command::none();
+                };
+
+                reminder.completed = checked;
+                let id = reminder.id;
+                if checked {
+                    if let Some(mqtt) = &self.mqtt {
+                        mqtt.publish_completed(reminder);
+                    }
+                    self.store.record_completion(id, Local::now().naive_local());
+                } else {
+                    self.store.forget_completion(id);
                 }
+                self.save_store()
+            }
+            AppMessage::Tick => self.check_due(),
+            AppMessage::QuietSummaryDismissed => {
+                self.quiet_summary = None;
+                Command::none()
+            }
+            AppMessage::SettingsChanged(settings) => {
+                self.settings = *settings;
+                self.save_settings()
+            }
+            AppMessage::AlertAction(index, action) => {
+                if index >= self.alerts.len() {
+                    return Command::none();
+                }
+
+                let alert = self.alerts.remove(index);
+                let now = Local::now().naive_local();
+                match action {
+                    AlertAction::Complete => {
+                        let Some(reminder) = self.store.complete(alert.occurrence.id, now) else {
+                            return Command::none();
+                        };
+                        if let Some(mqtt) = &self.mqtt {
+                            mqtt.publish_completed(reminder);
+                        }
+                    }
+                    AlertAction::Snooze => {
+                        let Some(reminder) = self.store.get_mut(alert.occurrence.id) else {
+                            return Command::none();
+                        };
+                        reminder.snoozed_until = Some(now + Duration::minutes(SNOOZE_MINUTES));
+                        if let Some(mqtt) = &self.mqtt {
+                            mqtt.publish_snoozed(reminder);
+                        }
+                    }
+                    AlertAction::Dismiss => {}
+                }
+                self.save_store()
+            }
+            AppMessage::Activate(new_reminder) => self.activate(new_reminder),
+            AppMessage::CloseRequested => {
+                // Keep running in the tray so reminders still fire
+                let action = match self.tray {
+                    Some(_) => window::Action::ChangeMode(window::Mode::Hidden),
+                    None => window::Action::Close,
+                };
+                Command::single(command::Action::Window(action))
+            }
+            AppMessage::Tray(action) => match action {
+                TrayAction::Open => self.activate(None),
+                TrayAction::AddReminder => self.activate(Some(String::new())),
+                TrayAction::DoNotDisturb(hours) => {
+                    self.settings.quiet_hours.do_not_disturb_until =
+                        hours.map(|hours| Local::now().naive_local() + Duration::hours(hours));
+                    self.save_settings()
+                }
+                TrayAction::Quit => Command::single(command::Action::Window(window::Action::Close)),
+            },
+            AppMessage::IpcRequest(request) => {
+                if let Request::Activate { new_reminder } = &request.request {
+                    let new_reminder = new_reminder.clone();
+                    request.respond(Ok(Response::None));
+                    return self.activate(new_reminder);
+                }
+
+                let result = request.request.clone().apply(&mut self.store);
+                let changed = request.request.is_change() && result.is_ok();
+                if let Some(mqtt) = &self.mqtt {
+                    mqtt.publish_applied(&request.request, &result);
+                }
+                request.respond(result);
+                if changed {
+                    self.save_store()
+                } else {
+                    Command::none()
+                }
+            }
+            AppMessage::Saved(Ok(())) => Command::none(),
+            AppMessage::Saved(Err(error)) => {
+                error!(?error, "failed to save");
+                Command::none()
+            }
+            AppMessage::DigestSent(date, Ok(())) => {
+                info!(to = ?self.settings.digest.to, "sent digest");
+                self.store.last_digest = Some(date);
+                self.write_store()
+            }
+            AppMessage::DigestSent(_, Err(error)) => {
+                error!(?error, "failed to send digest");
                 Command::none()
             }
         }
     }
 
-    fn view(&self) -> Element<Self::Message, Self::Renderer> {
+    fn view(&self) -> Element<'_, Self::Message, Self::Renderer> {
         const TABS: &[(&str, AppTab)] = &[
             ("Reminders", AppTab::Reminders),
             ("New", AppTab::AddReminder),
@@ -117,21 +522,54 @@ impl Program for App {
         )
         .on_selected(AppMessage::TabSelected);
         let page = match self.tab {
-            AppTab::Reminders => reminder_page(&self.reminders)
+            AppTab::Reminders => reminder_page(&self.store.reminders)
+                .alerts(&self.alerts)
                 .on_toggle(AppMessage::ReminderToggled)
+                .on_alert_action(AppMessage::AlertAction)
+                .quick_add(&self.quick_add)
+                .on_quick_add_change(AppMessage::QuickAddChanged)
+                .on_quick_add(AppMessage::QuickAdd)
+                .on_quick_add_edit(AppMessage::QuickAddEdit)
+                .into(),
+            AppTab::AddReminder => add_reminder_page(&self.new_title)
+                .frequency_type(self.new_frequency_type)
+                .schedule(&self.new_schedule)
+                .on_title_change(AppMessage::NewTitleChanged)
+                .on_frequency_type_change(AppMessage::NewFrequencyTypeChanged)
+                .on_schedule_change(AppMessage::NewScheduleChanged)
+                .on_add(AppMessage::AddReminder)
+                .into(),
+            AppTab::Settings => settings_page(&self.settings)
+                .on_change(|settings| AppMessage::SettingsChanged(Box::new(settings)))
                 .into(),
-            AppTab::AddReminder => add_reminder_page().on_add(AppMessage::AddReminder).into(),
-            AppTab::Settings => text("Settings WIP").into(),
         };
 
-        column(vec![
+        let mut rows = vec![
             title.into(),
             horizontal_rule(2).into(),
             container(tabs).width(Length::Fill).into(),
-            page,
-            vertical_space(Length::Fill).into(),
-        ])
-        .into()
+        ];
+        if let Some(count) = self.quiet_summary {
+            let summary = match count {
+                1 => "1 reminder came due during quiet hours".to_string(),
+                count => format!("{count} reminders came due during quiet hours"),
+            };
+            rows.push(
+                row(vec![
+                    text(summary).vertical_alignment(Vertical::Center).into(),
+                    horizontal_space(Length::Fill).into(),
+                    button(text("Dismiss"))
+                        .on_press(AppMessage::QuietSummaryDismissed)
+                        .style(Button::Text)
+                        .into(),
+                ])
+                .into(),
+            );
+        }
+        rows.push(page);
+        rows.push(vertical_space(Length::Fill).into());
+
+        column(rows).into()
     }
 }
 
@@ -144,6 +582,40 @@ pub enum AppMessage {
     AddReminder(Reminder),
     /// A reminder was toggled.
     ReminderToggled(usize, bool),
+    /// The user acted on an alert.
+    AlertAction(usize, AlertAction),
+    /// Reminders may have come due.
+    Tick,
+    /// The user dismissed the summary of alerts delivered after quiet hours.
+    QuietSummaryDismissed,
+    /// The user changed their settings.
+    SettingsChanged(Box<AppSettings>),
+    /// The title of the reminder being added changed.
+    NewTitleChanged(String),
+    /// The kind of frequency of the reminder being added changed.
+    NewFrequencyTypeChanged(FrequencyType),
+    /// The schedule of the reminder being added changed.
+    NewScheduleChanged(String),
+    /// The text typed into quick add changed.
+    QuickAddChanged(String),
+    /// A reminder was added with quick add.
+    QuickAdd(Reminder),
+    /// The reminder typed into quick add should be finished in the "New"
+    /// tab, with its title and frequency if it could be understood.
+    QuickAddEdit(String, Option<ReminderFrequency>),
+    /// The app was launched again, so the window should be brought to the
+    /// front.
+    Activate(Option<String>),
+    /// The user tried to close the window.
+    CloseRequested,
+    /// The user chose an action from the tray icon.
+    Tray(TrayAction),
+    /// A request was received from outside the app.
+    IpcRequest(IpcRequest),
+    /// A file was saved in the background.
+    Saved(Result<(), Arc<Report>>),
+    /// The digest for a day was sent in the background.
+    DigestSent(NaiveDate, Result<(), Arc<Report>>),
 }
 
 /// A tab in the app.
@@ -154,3 +626,50 @@ pub enum AppTab {
     AddReminder,
     Settings,
 }
+
+/// Creates a subscription which ticks when the next reminder comes due, or
+/// periodically to notice if the system clock jumps.
+fn scheduler_ticks(next_due: Option<NaiveDateTime>) -> Subscription<AppMessage> {
+    subscription::unfold(("scheduler", next_due), (), move |()| async move {
+        let mut wait = CLOCK_CHECK_INTERVAL;
+        if let Some(next_due) = next_due {
+            let until = next_due - Local::now().naive_local();
+            wait = wait.min(until.to_std().unwrap_or_default());
+        }
+
+        tokio::time::sleep(wait).await;
+        (AppMessage::Tick, ())
+    })
+}
+
+/// Creates a subscription which receives requests sent from outside the app.
+fn external_requests(
+    requests: Arc<Mutex<UnboundedReceiver<IpcRequest>>>,
+) -> Subscription<AppMessage> {
+    subscription::unfold("requests", requests, |requests| async move {
+        let request = requests.lock().await.recv().await;
+        match request {
+            Some(request) => (AppMessage::IpcRequest(request), requests),
+            None => futures::future::pending().await,
+        }
+    })
+}
+
+/// Saves a value to a file in the background.
+fn save<T>(file: DataFile<T>, value: T) -> Command<AppMessage>
+where
+    T: DataFileContents + Send + 'static,
+{
+    // Saves finish in any order, so outdated ones are skipped
+    let generation = file.queue_save();
+    Command::perform(
+        async move {
+            tokio::task::spawn_blocking(move || file.save_queued(generation, &value))
+                .await
+                .map_err(Report::from)
+                .and_then(|result| result)
+                .map_err(Arc::new)
+        },
+        AppMessage::Saved,
+    )
+}
@@ -1,9 +1,11 @@
 pub mod config;
 
+mod alert;
 mod reminder;
 mod tab_button;
 mod tab_list;
 
+pub use alert::*;
 pub use reminder::*;
 pub use tab_button::*;
 pub use tab_list::*;
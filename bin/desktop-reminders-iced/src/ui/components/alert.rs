@@ -0,0 +1,134 @@
+use iced_lazy::{component, Component};
+use iced_native::{
+    alignment::{Horizontal, Vertical},
+    theme::Button,
+    widget::{button, column, container, horizontal_space, row, text},
+    Element, Length, Padding,
+};
+
+use crate::{
+    models::reminders::{Reminder, ReminderAlert},
+    ui::app::Renderer,
+};
+
+/// Creates an [`AlertComponent`].
+#[inline]
+pub fn alert<'a, Message>(
+    alert: &'a ReminderAlert,
+    reminder: &'a Reminder,
+) -> AlertComponent<'a, Message> {
+    AlertComponent {
+        alert,
+        reminder,
+        on_action: None,
+    }
+}
+
+/// A component for displaying a [`ReminderAlert`].
+#[must_use]
+pub struct AlertComponent<'a, Message> {
+    alert: &'a ReminderAlert,
+    reminder: &'a Reminder,
+    on_action: Option<Box<dyn Fn(AlertAction) -> Message + 'a>>,
+}
+
+impl<'a, Message> AlertComponent<'a, Message> {
+    /// Sets the function to be called when the user acts on the alert.
+    #[inline]
+    pub fn on_action<F>(mut self, f: F) -> Self
+    where
+        F: Fn(AlertAction) -> Message + 'a,
+    {
+        self.on_action = Some(Box::new(f));
+        self
+    }
+}
+
+impl<'a, Message> Component<Message, Renderer> for AlertComponent<'a, Message> {
+    type State = ();
+    type Event = AlertComponentEvent;
+
+    fn update(&mut self, (): &mut Self::State, event: Self::Event) -> Option<Message> {
+        match event {
+            AlertComponentEvent::Action(action) => self.on_action.as_ref().map(|f| f(action)),
+        }
+    }
+
+    fn view(&self, (): &Self::State) -> Element<'_, Self::Event, Renderer> {
+        const ACTIONS: &[(&str, AlertAction)] = &[
+            ("Done", AlertAction::Complete),
+            ("Snooze", AlertAction::Snooze),
+            ("Dismiss", AlertAction::Dismiss),
+        ];
+
+        let title = if self.alert.missed {
+            format!("Missed: {}", self.reminder.message)
+        } else {
+            self.reminder.message.clone()
+        };
+        let at = self.alert.occurrence.at.format("%a %-d %b, %-I:%M %p");
+        let header = row(vec![
+            text(title).into(),
+            horizontal_space(Length::Fill).into(),
+            text(at).into(),
+        ]);
+
+        let buttons = ACTIONS
+            .iter()
+            .copied()
+            .map(|(label, action)| {
+                button(
+                    text(label)
+                        .width(Length::Fill)
+                        .horizontal_alignment(Horizontal::Center)
+                        .vertical_alignment(Vertical::Center),
+                )
+                .on_press(AlertComponentEvent::Action(action))
+                .width(Length::Fill)
+                .style(match action {
+                    AlertAction::Complete => Button::Positive,
+                    AlertAction::Snooze => Button::Secondary,
+                    AlertAction::Dismiss => Button::Text,
+                })
+                .into()
+            })
+            .collect();
+
+        container(column(vec![header.into(), row(buttons).into()]))
+            .padding(Padding {
+                top: 5.0,
+                right: 20.0,
+                bottom: 5.0,
+                left: 10.0,
+            })
+            .into()
+    }
+}
+
+impl<'a, Message> From<AlertComponent<'a, Message>> for Element<'a, Message, Renderer>
+where
+    Message: 'a,
+{
+    #[inline]
+    fn from(value: AlertComponent<'a, Message>) -> Self {
+        component(value)
+    }
+}
+
+/// An action the user can take on an alert.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum AlertAction {
+    /// Marks the occurrence as completed.
+    Complete,
+    /// Shows the alert again later.
+    Snooze,
+    /// Hides the alert.
+    Dismiss,
+}
+
+/// The event of an [`AlertComponent`].
+#[derive(Clone, Debug)]
+pub enum AlertComponentEvent {
+    /// The user acted on the alert.
+    Action(AlertAction),
+}
@@ -136,12 +136,15 @@ impl<'a, Message> Component<Message, Renderer> for TimeOfDayComponent<'a, Messag
         ];
 
         let tabs = tab_list(TABS.iter().copied().map(|(label, id)| {
-            let selected = match (self.time_of_day, id) {
-                (ReminderTimeOfDay::AllDay, TimeOfDayKind::AllDay) => true,
-                (ReminderTimeOfDay::Time { .. }, TimeOfDayKind::Time) => true,
-                (ReminderTimeOfDay::TimeRange { .. }, TimeOfDayKind::TimeRange) => true,
-                _ => false,
-            };
+            let selected = matches!(
+                (self.time_of_day, id),
+                (ReminderTimeOfDay::AllDay, TimeOfDayKind::AllDay)
+                    | (ReminderTimeOfDay::Time { .. }, TimeOfDayKind::Time)
+                    | (
+                        ReminderTimeOfDay::TimeRange { .. },
+                        TimeOfDayKind::TimeRange
+                    )
+            );
 
             Tab::new(label, id, selected)
         }))
@@ -1,5 +1,7 @@
 mod add_reminder;
 mod reminders;
+mod settings;
 
 pub use add_reminder::*;
 pub use reminders::*;
+pub use settings::*;
@@ -2,12 +2,13 @@ use chrono::Local;
 use iced_lazy::{component, Component};
 use iced_native::{
     alignment::{Horizontal, Vertical},
-    theme::Button,
+    theme::{Button, Text},
     widget::{button, column, text, text_input, vertical_space},
-    Element, Length,
+    Color, Element, Length,
 };
 
 use crate::{
+    formats::{CronExpression, Rrule},
     models::reminders::{
         Reminder, ReminderDaysOfWeek, ReminderFrequency, ReminderFrequencyDaily,
         ReminderFrequencyMonthly, ReminderFrequencyOnce, ReminderFrequencyWeekly,
@@ -22,19 +23,81 @@ use crate::{
     },
 };
 
-/// Creates a new [`AddReminderPage`].
+/// The color of the message shown for an invalid rule or expression.
+pub const ERROR_COLOR: Color = Color::from_rgb(1.0, 0.33, 0.33);
+
+/// Creates a new [`AddReminderPage`] with the given title entered.
 #[inline]
-pub fn add_reminder_page<'a, Message>() -> AddReminderPage<'a, Message> {
-    AddReminderPage { on_add: None }
+pub fn add_reminder_page<'a, Message>(title: &'a str) -> AddReminderPage<'a, Message> {
+    AddReminderPage {
+        title,
+        frequency_type: FrequencyType::default(),
+        schedule: "",
+        on_title_change: None,
+        on_frequency_type_change: None,
+        on_schedule_change: None,
+        on_add: None,
+    }
 }
 
 /// A page that allows the user to add a reminder.
 #[must_use]
 pub struct AddReminderPage<'a, Message> {
+    title: &'a str,
+    frequency_type: FrequencyType,
+    schedule: &'a str,
+    on_title_change: Option<Box<dyn Fn(String) -> Message + 'a>>,
+    on_frequency_type_change: Option<Box<dyn Fn(FrequencyType) -> Message + 'a>>,
+    on_schedule_change: Option<Box<dyn Fn(String) -> Message + 'a>>,
     on_add: Option<Box<dyn Fn(Reminder) -> Message + 'a>>,
 }
 
 impl<'a, Message> AddReminderPage<'a, Message> {
+    /// Sets the kind of frequency that is chosen.
+    #[inline]
+    pub fn frequency_type(mut self, frequency_type: FrequencyType) -> Self {
+        self.frequency_type = frequency_type;
+        self
+    }
+
+    /// Sets the schedule entered for [`FrequencyType::Schedule`].
+    #[inline]
+    pub fn schedule(mut self, schedule: &'a str) -> Self {
+        self.schedule = schedule;
+        self
+    }
+
+    /// Sets the function to be called when the title changes.
+    #[inline]
+    pub fn on_title_change<F>(mut self, f: F) -> Self
+    where
+        F: Fn(String) -> Message + 'a,
+    {
+        self.on_title_change = Some(Box::new(f));
+        self
+    }
+
+    /// Sets the function to be called when a different kind of frequency is
+    /// chosen.
+    #[inline]
+    pub fn on_frequency_type_change<F>(mut self, f: F) -> Self
+    where
+        F: Fn(FrequencyType) -> Message + 'a,
+    {
+        self.on_frequency_type_change = Some(Box::new(f));
+        self
+    }
+
+    /// Sets the function to be called when the schedule changes.
+    #[inline]
+    pub fn on_schedule_change<F>(mut self, f: F) -> Self
+    where
+        F: Fn(String) -> Message + 'a,
+    {
+        self.on_schedule_change = Some(Box::new(f));
+        self
+    }
+
     /// Sets the function to be called when a reminder is added.
     #[inline]
     pub fn on_add<F>(mut self, f: F) -> Self
@@ -54,7 +117,7 @@ impl<'a, Message> Component<Message, Renderer> for AddReminderPage<'a, Message>
         match event {
             AddReminderPageEvent::AddReminder => {
                 let date = Local::now().date_naive();
-                let frequency = match state.frequency_type {
+                let frequency = match self.frequency_type {
                     FrequencyType::Once => ReminderFrequency::Once(ReminderFrequencyOnce {
                         date,
                         time: state.time_of_day,
@@ -79,24 +142,39 @@ impl<'a, Message> Component<Message, Renderer> for AddReminderPage<'a, Message>
                         }],
                         time: state.time_of_day,
                     }),
+                    FrequencyType::Rule => match state.rule.parse::<Rrule>() {
+                        Ok(rule) => rule.frequency(state.time_of_day),
+                        Err(_) => return None,
+                    },
+                    FrequencyType::Cron => match state.cron.parse::<CronExpression>() {
+                        Ok(expression) => expression.frequency().clone(),
+                        Err(_) => return None,
+                    },
+                    FrequencyType::Schedule => match self.schedule.parse() {
+                        Ok(frequency) => frequency,
+                        Err(_) => return None,
+                    },
                 };
                 let reminder = Reminder {
+                    id: Default::default(),
                     frequency,
-                    message: std::mem::take(&mut state.title),
+                    message: self.title.to_string(),
                     completed: false,
+                    snoozed_until: None,
+                    actions: Vec::new(),
+                    contact: None,
                 };
 
                 *state = Default::default();
                 self.on_add.as_ref().map(|f| f(reminder))
             }
             AddReminderPageEvent::SetTitle(title) => {
-                state.title = title;
-                None
-            }
-            AddReminderPageEvent::SetFrequencyType(frequency_type) => {
-                state.frequency_type = frequency_type;
-                None
+                self.on_title_change.as_ref().map(|f| f(title))
             }
+            AddReminderPageEvent::SetFrequencyType(frequency_type) => self
+                .on_frequency_type_change
+                .as_ref()
+                .map(|f| f(frequency_type)),
             AddReminderPageEvent::SetDaysOfWeek(days_of_week) => {
                 state.days_of_week = days_of_week;
                 None
@@ -105,6 +183,17 @@ impl<'a, Message> Component<Message, Renderer> for AddReminderPage<'a, Message>
                 state.time_of_day = time_of_day;
                 None
             }
+            AddReminderPageEvent::SetRule(rule) => {
+                state.rule = rule;
+                None
+            }
+            AddReminderPageEvent::SetCron(cron) => {
+                state.cron = cron;
+                None
+            }
+            AddReminderPageEvent::SetSchedule(schedule) => {
+                self.on_schedule_change.as_ref().map(|f| f(schedule))
+            }
         }
     }
 
@@ -115,13 +204,16 @@ impl<'a, Message> Component<Message, Renderer> for AddReminderPage<'a, Message>
             ("W", FrequencyType::Weekly),
             ("M", FrequencyType::Monthly),
             ("Y", FrequencyType::Yearly),
+            ("R", FrequencyType::Rule),
+            ("C", FrequencyType::Cron),
+            ("S", FrequencyType::Schedule),
         ];
 
         let mut rows = Vec::with_capacity(10);
 
         // Title
         rows.push(
-            text_input("Title", &state.title)
+            text_input("Title", self.title)
                 .on_input(AddReminderPageEvent::SetTitle)
                 .width(Length::Fill)
                 .into(),
@@ -141,14 +233,14 @@ impl<'a, Message> Component<Message, Renderer> for AddReminderPage<'a, Message>
                 FREQUENCY_TABS
                     .iter()
                     .copied()
-                    .map(|(label, id)| Tab::new(label, id, state.frequency_type == id)),
+                    .map(|(label, id)| Tab::new(label, id, self.frequency_type == id)),
             )
-            .on_selected(|id| AddReminderPageEvent::SetFrequencyType(id))
+            .on_selected(AddReminderPageEvent::SetFrequencyType)
             .into(),
         );
 
         // Days of week
-        if state.frequency_type == FrequencyType::Weekly {
+        if self.frequency_type == FrequencyType::Weekly {
             rows.push(
                 days_of_week(state.days_of_week)
                     .on_change(AddReminderPageEvent::SetDaysOfWeek)
@@ -156,27 +248,97 @@ impl<'a, Message> Component<Message, Renderer> for AddReminderPage<'a, Message>
             );
         }
 
-        // Time of day
-        rows.push(
-            time_of_day(state.time_of_day)
-                .on_change(AddReminderPageEvent::SetTimeOfDay)
-                .into(),
-        );
+        // Recurrence rule, cron expression or schedule
+        let pattern = match self.frequency_type {
+            FrequencyType::Rule => Some((
+                "FREQ=WEEKLY;BYDAY=MO,WE,FR",
+                state.rule.as_str(),
+                AddReminderPageEvent::SetRule as fn(String) -> AddReminderPageEvent,
+                state
+                    .rule
+                    .parse::<Rrule>()
+                    .map(|rule| rule.time().map(|time| ReminderTimeOfDay::Time { time }))
+                    .map_err(|error| error.to_string()),
+            )),
+            FrequencyType::Cron => Some((
+                "30 12 * * 1-5",
+                state.cron.as_str(),
+                AddReminderPageEvent::SetCron as fn(String) -> AddReminderPageEvent,
+                state
+                    .cron
+                    .parse::<CronExpression>()
+                    .map(|expression| {
+                        Some(ReminderTimeOfDay::Time {
+                            time: expression.time(),
+                        })
+                    })
+                    .map_err(|error| error.to_string()),
+            )),
+            FrequencyType::Schedule => Some((
+                "weekly mon,wed @ 12:30-16:30",
+                self.schedule,
+                AddReminderPageEvent::SetSchedule as fn(String) -> AddReminderPageEvent,
+                self.schedule
+                    .parse::<ReminderFrequency>()
+                    .map(|frequency| Some(*frequency.time_of_day()))
+                    .map_err(|error| error.to_string()),
+            )),
+            _ => None,
+        };
+        let mut can_add = true;
+        let mut rule_time = None;
+        if let Some((placeholder, value, on_input, parsed)) = pattern {
+            rows.push(
+                text_input(placeholder, value)
+                    .on_input(on_input)
+                    .width(Length::Fill)
+                    .into(),
+            );
+            match parsed {
+                Ok(time) => rule_time = time,
+                Err(_) if value.trim().is_empty() => can_add = false,
+                Err(error) => {
+                    can_add = false;
+                    rows.push(
+                        text(error)
+                            .size(14)
+                            .style(Text::Color(ERROR_COLOR))
+                            .width(Length::Fill)
+                            .into(),
+                    );
+                }
+            }
+        }
+
+        // Time of day, unless the rule, expression or schedule sets one
+        match rule_time {
+            Some(time) => rows.push(
+                text(describe_time_of_day(time))
+                    .width(Length::Fill)
+                    .horizontal_alignment(Horizontal::Center)
+                    .into(),
+            ),
+            None => rows.push(
+                time_of_day(state.time_of_day)
+                    .on_change(AddReminderPageEvent::SetTimeOfDay)
+                    .into(),
+            ),
+        }
 
         // Add reminder
         rows.push(vertical_space(Length::Fill).into());
-        rows.push(
-            button(
-                text("+")
-                    .horizontal_alignment(Horizontal::Center)
-                    .vertical_alignment(Vertical::Center)
-                    .width(Length::Fill),
-            )
-            .on_press(AddReminderPageEvent::AddReminder)
-            .width(Length::Fill)
-            .style(Button::Positive)
-            .into(),
-        );
+        let mut add_button = button(
+            text("+")
+                .horizontal_alignment(Horizontal::Center)
+                .vertical_alignment(Vertical::Center)
+                .width(Length::Fill),
+        )
+        .width(Length::Fill)
+        .style(Button::Positive);
+        if can_add {
+            add_button = add_button.on_press(AddReminderPageEvent::AddReminder);
+        }
+        rows.push(add_button.into());
 
         column(rows).into()
     }
@@ -204,25 +366,31 @@ pub enum AddReminderPageEvent {
     SetDaysOfWeek(ReminderDaysOfWeek),
     /// Sets the time of day.
     SetTimeOfDay(ReminderTimeOfDay),
+    /// Sets the recurrence rule.
+    SetRule(String),
+    /// Sets the cron expression.
+    SetCron(String),
+    /// Sets the schedule.
+    SetSchedule(String),
 }
 
 /// The state for [`AddReminderPage`].
 #[derive(Clone, Debug)]
 pub struct AddReminderPageState {
-    title: String,
-    frequency_type: FrequencyType,
     days_of_week: ReminderDaysOfWeek,
     time_of_day: ReminderTimeOfDay,
+    rule: String,
+    cron: String,
 }
 
 impl Default for AddReminderPageState {
     fn default() -> Self {
         let now = Local::now().naive_local();
         Self {
-            title: Default::default(),
-            frequency_type: Default::default(),
             days_of_week: Default::default(),
             time_of_day: ReminderTimeOfDay::Time { time: now.time() },
+            rule: String::new(),
+            cron: String::new(),
         }
     }
 }
@@ -241,4 +409,23 @@ pub enum FrequencyType {
     Monthly,
     /// Maps to [`ReminderFrequency::Yearly`].
     Yearly,
+    /// Parsed from an RFC 5545 RRULE.
+    Rule,
+    /// Parsed from a cron expression.
+    Cron,
+    /// Parsed from a schedule, like `weekly mon,wed @ 12:30`.
+    Schedule,
+}
+
+/// Describes the time of day set by a rule, expression or schedule.
+fn describe_time_of_day(time: ReminderTimeOfDay) -> String {
+    match time {
+        ReminderTimeOfDay::AllDay => "All day".to_string(),
+        ReminderTimeOfDay::Time { time } => format!("At {}", time.format("%-I:%M %p")),
+        ReminderTimeOfDay::TimeRange { start, end } => format!(
+            "From {} to {}",
+            start.format("%-I:%M %p"),
+            end.format("%-I:%M %p")
+        ),
+    }
 }
@@ -1,12 +1,19 @@
+use chrono::Local;
 use iced_lazy::{component, Component};
 use iced_native::{
-    widget::{column, scrollable},
+    theme::{Button, Text},
+    widget::{button, column, horizontal_rule, row, scrollable, text, text_input},
     Element, Length,
 };
 
 use crate::{
-    models::reminders::Reminder,
-    ui::{app::Renderer, components::reminder},
+    formats::QuickAdd,
+    models::reminders::{Reminder, ReminderAlert, ReminderFrequency},
+    ui::{
+        app::Renderer,
+        components::{alert, reminder, AlertAction},
+        pages::ERROR_COLOR,
+    },
 };
 
 /// A page that displays reminders for a specific day.
@@ -14,18 +21,47 @@ use crate::{
 pub fn reminder_page<'a, Message>(reminders: &'a [Reminder]) -> ReminderPage<'a, Message> {
     ReminderPage {
         reminders,
+        alerts: &[],
+        quick_add: "",
         on_reminder_toggled: None,
+        on_alert_action: None,
+        on_quick_add_change: None,
+        on_quick_add: None,
+        on_quick_add_edit: None,
     }
 }
 
+/// A function given the title and frequency typed into quick add.
+type QuickAddEditFn<'a, Message> = dyn Fn(String, Option<ReminderFrequency>) -> Message + 'a;
+
 /// The state of a day page.
 #[must_use]
 pub struct ReminderPage<'a, Message> {
     reminders: &'a [Reminder],
+    alerts: &'a [ReminderAlert],
+    quick_add: &'a str,
     on_reminder_toggled: Option<Box<dyn Fn(usize, bool) -> Message + 'a>>,
+    on_alert_action: Option<Box<dyn Fn(usize, AlertAction) -> Message + 'a>>,
+    on_quick_add_change: Option<Box<dyn Fn(String) -> Message + 'a>>,
+    on_quick_add: Option<Box<dyn Fn(Reminder) -> Message + 'a>>,
+    on_quick_add_edit: Option<Box<QuickAddEditFn<'a, Message>>>,
 }
 
 impl<'a, Message> ReminderPage<'a, Message> {
+    /// Sets the alerts to display above the reminders.
+    #[inline]
+    pub fn alerts(mut self, alerts: &'a [ReminderAlert]) -> Self {
+        self.alerts = alerts;
+        self
+    }
+
+    /// Sets the text typed into quick add.
+    #[inline]
+    pub fn quick_add(mut self, quick_add: &'a str) -> Self {
+        self.quick_add = quick_add;
+        self
+    }
+
     /// Sets the function to be called when a reminder is toggled.
     #[inline]
     pub fn on_toggle<F>(mut self, f: F) -> Self
@@ -35,6 +71,60 @@ impl<'a, Message> ReminderPage<'a, Message> {
         self.on_reminder_toggled = Some(Box::new(f));
         self
     }
+
+    /// Sets the function to be called when the user acts on an alert.
+    #[inline]
+    pub fn on_alert_action<F>(mut self, f: F) -> Self
+    where
+        F: Fn(usize, AlertAction) -> Message + 'a,
+    {
+        self.on_alert_action = Some(Box::new(f));
+        self
+    }
+
+    /// Sets the function to be called when the text typed into quick add
+    /// changes.
+    #[inline]
+    pub fn on_quick_add_change<F>(mut self, f: F) -> Self
+    where
+        F: Fn(String) -> Message + 'a,
+    {
+        self.on_quick_add_change = Some(Box::new(f));
+        self
+    }
+
+    /// Sets the function to be called when a reminder is added with quick
+    /// add.
+    #[inline]
+    pub fn on_quick_add<F>(mut self, f: F) -> Self
+    where
+        F: Fn(Reminder) -> Message + 'a,
+    {
+        self.on_quick_add = Some(Box::new(f));
+        self
+    }
+
+    /// Sets the function to be called when the user wants to finish the
+    /// reminder typed into quick add with the full form. It is given the
+    /// title and, if the text could be understood, the frequency.
+    #[inline]
+    pub fn on_quick_add_edit<F>(mut self, f: F) -> Self
+    where
+        F: Fn(String, Option<ReminderFrequency>) -> Message + 'a,
+    {
+        self.on_quick_add_edit = Some(Box::new(f));
+        self
+    }
+
+    /// Understands the text typed into quick add.
+    fn parse_quick_add(&self) -> Option<Result<QuickAdd, String>> {
+        if self.quick_add.trim().is_empty() {
+            return None;
+        }
+
+        let parsed = QuickAdd::parse(self.quick_add, Local::now().naive_local());
+        Some(parsed.map_err(|error| error.to_string()))
+    }
 }
 
 impl<'a, Message> Component<Message, Renderer> for ReminderPage<'a, Message> {
@@ -46,24 +136,104 @@ impl<'a, Message> Component<Message, Renderer> for ReminderPage<'a, Message> {
             ReminderPageEvent::CompletedChanged(index, state) => {
                 self.on_reminder_toggled.as_ref().map(|f| f(index, state))
             }
+            ReminderPageEvent::AlertAction(index, action) => {
+                self.on_alert_action.as_ref().map(|f| f(index, action))
+            }
+            ReminderPageEvent::SetQuickAdd(text) => {
+                self.on_quick_add_change.as_ref().map(|f| f(text))
+            }
+            ReminderPageEvent::QuickAdd => match self.parse_quick_add()? {
+                Ok(quick_add) => self
+                    .on_quick_add
+                    .as_ref()
+                    .map(|f| f(quick_add.into_reminder())),
+                Err(_) => None,
+            },
+            ReminderPageEvent::EditQuickAdd => {
+                let (title, frequency) = match self.parse_quick_add() {
+                    Some(Ok(quick_add)) => (quick_add.title, Some(quick_add.frequency)),
+                    _ => (self.quick_add.trim().to_string(), None),
+                };
+                self.on_quick_add_edit.as_ref().map(|f| f(title, frequency))
+            }
         }
     }
 
-    fn view(&self, (): &Self::State) -> Element<Self::Event, Renderer> {
-        let reminders = self
-            .reminders
+    fn view(&self, (): &Self::State) -> Element<'_, Self::Event, Renderer> {
+        let mut rows: Vec<_> = self
+            .alerts
             .iter()
             .enumerate()
-            .map(|(index, r)| {
-                reminder(r)
-                    .on_completed_changed(move |state| {
-                        ReminderPageEvent::CompletedChanged(index, state)
-                    })
-                    .into()
+            .filter_map(|(index, a)| {
+                let reminder = self
+                    .reminders
+                    .iter()
+                    .find(|reminder| reminder.id == a.occurrence.id)?;
+                let element = alert(a, reminder)
+                    .on_action(move |action| ReminderPageEvent::AlertAction(index, action))
+                    .into();
+                Some(element)
             })
             .collect();
+        if !rows.is_empty() {
+            rows.push(horizontal_rule(3).into());
+        }
+
+        rows.extend(self.reminders.iter().enumerate().map(|(index, r)| {
+            reminder(r)
+                .on_completed_changed(move |state| {
+                    ReminderPageEvent::CompletedChanged(index, state)
+                })
+                .into()
+        }));
+
+        // Quick add
+        let parsed = self.parse_quick_add();
+        let mut add_button = button(text("Add")).style(Button::Positive);
+        if let Some(Ok(_)) = parsed {
+            add_button = add_button.on_press(ReminderPageEvent::QuickAdd);
+        }
+        let mut page = vec![row(vec![
+            text_input(
+                "Quick add, like \"call mom next tuesday 6pm\"",
+                self.quick_add,
+            )
+            .on_input(ReminderPageEvent::SetQuickAdd)
+            .on_submit(ReminderPageEvent::QuickAdd)
+            .width(Length::Fill)
+            .into(),
+            add_button.into(),
+            button(text("Edit"))
+                .on_press(ReminderPageEvent::EditQuickAdd)
+                .style(Button::Secondary)
+                .into(),
+        ])
+        .spacing(5)
+        .into()];
+        match parsed {
+            Some(Ok(quick_add)) => page.push(
+                text(format!(
+                    "{:?}: {}",
+                    quick_add.title,
+                    quick_add.frequency.describe()
+                ))
+                .size(14)
+                .width(Length::Fill)
+                .into(),
+            ),
+            Some(Err(error)) => page.push(
+                text(error)
+                    .size(14)
+                    .style(Text::Color(ERROR_COLOR))
+                    .width(Length::Fill)
+                    .into(),
+            ),
+            None => {}
+        }
+        page.push(horizontal_rule(3).into());
+        page.push(scrollable(column(rows)).width(Length::Fill).into());
 
-        scrollable(column(reminders)).width(Length::Fill).into()
+        column(page).into()
     }
 }
 
@@ -78,7 +248,16 @@ where
 }
 
 /// An event for [`ReminderPage`].
+#[derive(Clone, Debug)]
 pub enum ReminderPageEvent {
     /// A reminder's completed state was changed.
     CompletedChanged(usize, bool),
+    /// The user acted on an alert.
+    AlertAction(usize, AlertAction),
+    /// The text typed into quick add changed.
+    SetQuickAdd(String),
+    /// Adds the reminder typed into quick add.
+    QuickAdd,
+    /// Opens the reminder typed into quick add in the full form.
+    EditQuickAdd,
 }
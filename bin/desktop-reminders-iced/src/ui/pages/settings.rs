@@ -0,0 +1,245 @@
+use chrono::{Duration, Local, NaiveTime};
+use iced_aw::{time_picker::Time, TimePicker};
+use iced_lazy::{component, Component};
+use iced_native::{
+    alignment::{Horizontal, Vertical},
+    theme::Button,
+    widget::{button, checkbox, column, row, text},
+    Element, Length,
+};
+
+use crate::{
+    models::{reminders::ReminderDaysOfWeek, settings::AppSettings},
+    ui::{app::Renderer, components::config::days_of_week},
+};
+
+/// Creates a new [`SettingsPage`].
+#[inline]
+pub fn settings_page<'a, Message>(settings: &AppSettings) -> SettingsPage<'a, Message> {
+    SettingsPage {
+        settings: settings.clone(),
+        on_change: None,
+    }
+}
+
+/// A page that allows the user to change their settings.
+#[must_use]
+pub struct SettingsPage<'a, Message> {
+    settings: AppSettings,
+    on_change: Option<Box<dyn Fn(AppSettings) -> Message + 'a>>,
+}
+
+impl<'a, Message> SettingsPage<'a, Message> {
+    /// Sets the function to be called when the settings are changed.
+    #[inline]
+    pub fn on_change<F>(mut self, f: F) -> Self
+    where
+        F: Fn(AppSettings) -> Message + 'a,
+    {
+        self.on_change = Some(Box::new(f));
+        self
+    }
+}
+
+impl<'a, Message> Component<Message, Renderer> for SettingsPage<'a, Message> {
+    type State = SettingsPageState;
+    type Event = SettingsPageEvent;
+
+    fn update(&mut self, state: &mut Self::State, event: Self::Event) -> Option<Message> {
+        let quiet_hours = &mut self.settings.quiet_hours;
+        match event {
+            SettingsPageEvent::DoNotDisturbSelected(hours) => {
+                quiet_hours.do_not_disturb_until =
+                    hours.map(|hours| Local::now().naive_local() + Duration::hours(hours));
+            }
+            SettingsPageEvent::QuietHoursToggled(enabled) => {
+                quiet_hours.enabled = enabled;
+            }
+            SettingsPageEvent::QuietDaysChanged(days) => {
+                quiet_hours.days = days;
+            }
+            SettingsPageEvent::StartPickerOpened => {
+                state.setting_start = true;
+                return None;
+            }
+            SettingsPageEvent::EndPickerOpened => {
+                state.setting_end = true;
+                return None;
+            }
+            SettingsPageEvent::StartPickerCancelled => {
+                state.setting_start = false;
+                return None;
+            }
+            SettingsPageEvent::EndPickerCancelled => {
+                state.setting_end = false;
+                return None;
+            }
+            SettingsPageEvent::StartChanged(time) => {
+                state.setting_start = false;
+                quiet_hours.start = time.into();
+            }
+            SettingsPageEvent::EndChanged(time) => {
+                state.setting_end = false;
+                quiet_hours.end = time.into();
+            }
+        }
+
+        self.on_change.as_ref().map(|f| f(self.settings.clone()))
+    }
+
+    fn view(&self, state: &Self::State) -> Element<'_, Self::Event, Renderer> {
+        const DO_NOT_DISTURB: &[(&str, Option<i64>)] = &[
+            ("Off", None),
+            ("1h", Some(1)),
+            ("2h", Some(2)),
+            ("4h", Some(4)),
+            ("8h", Some(8)),
+        ];
+
+        let quiet_hours = &self.settings.quiet_hours;
+        let mut rows = Vec::with_capacity(8);
+
+        // Do not disturb
+        let now = Local::now().naive_local();
+        let status = match quiet_hours.do_not_disturb_until {
+            Some(until) if now < until => {
+                format!("Do not disturb until {}", until.format("%-I:%M %p"))
+            }
+            _ => "Do not disturb".to_string(),
+        };
+        rows.push(header(status));
+        rows.push(
+            row(DO_NOT_DISTURB
+                .iter()
+                .copied()
+                .map(|(label, hours)| {
+                    button(
+                        text(label)
+                            .width(Length::Fill)
+                            .horizontal_alignment(Horizontal::Center)
+                            .vertical_alignment(Vertical::Center),
+                    )
+                    .on_press(SettingsPageEvent::DoNotDisturbSelected(hours))
+                    .width(Length::Fill)
+                    .style(Button::Secondary)
+                    .into()
+                })
+                .collect())
+            .into(),
+        );
+
+        // Quiet hours
+        rows.push(header("Quiet hours".to_string()));
+        rows.push(
+            checkbox(
+                "Hold back alerts every day between",
+                quiet_hours.enabled,
+                SettingsPageEvent::QuietHoursToggled,
+            )
+            .into(),
+        );
+        rows.push(
+            row(vec![
+                time_picker(
+                    state.setting_start,
+                    quiet_hours.start,
+                    SettingsPageEvent::StartPickerOpened,
+                    SettingsPageEvent::StartPickerCancelled,
+                    SettingsPageEvent::StartChanged,
+                ),
+                text(" - ")
+                    .horizontal_alignment(Horizontal::Center)
+                    .vertical_alignment(Vertical::Center)
+                    .into(),
+                time_picker(
+                    state.setting_end,
+                    quiet_hours.end,
+                    SettingsPageEvent::EndPickerOpened,
+                    SettingsPageEvent::EndPickerCancelled,
+                    SettingsPageEvent::EndChanged,
+                ),
+            ])
+            .into(),
+        );
+        rows.push(text("and all day on").into());
+        rows.push(
+            days_of_week(quiet_hours.days)
+                .on_change(SettingsPageEvent::QuietDaysChanged)
+                .into(),
+        );
+
+        column(rows).into()
+    }
+}
+
+impl<'a, Message> From<SettingsPage<'a, Message>> for Element<'a, Message, Renderer>
+where
+    Message: 'a,
+{
+    #[inline]
+    fn from(value: SettingsPage<'a, Message>) -> Self {
+        component(value)
+    }
+}
+
+/// Creates a section header.
+fn header<'a>(label: String) -> Element<'a, SettingsPageEvent, Renderer> {
+    text(label)
+        .width(Length::Fill)
+        .height(Length::Fixed(25.0))
+        .horizontal_alignment(Horizontal::Center)
+        .vertical_alignment(Vertical::Bottom)
+        .into()
+}
+
+/// Creates a button which opens a time picker.
+fn time_picker<'a>(
+    show_picker: bool,
+    time: NaiveTime,
+    on_open: SettingsPageEvent,
+    on_cancel: SettingsPageEvent,
+    on_submit: fn(Time) -> SettingsPageEvent,
+) -> Element<'a, SettingsPageEvent, Renderer> {
+    TimePicker::new(
+        show_picker,
+        time,
+        button(text(time.format("%-I:%M %p")))
+            .on_press(on_open)
+            .style(Button::Secondary),
+        on_cancel,
+        on_submit,
+    )
+    .into()
+}
+
+/// An event for [`SettingsPage`].
+#[derive(Clone, Debug)]
+pub enum SettingsPageEvent {
+    /// Turns "do not disturb" on for a number of hours, or off.
+    DoNotDisturbSelected(Option<i64>),
+    /// Sets whether the daily quiet window is enabled.
+    QuietHoursToggled(bool),
+    /// Sets the days that are quiet for the whole day.
+    QuietDaysChanged(ReminderDaysOfWeek),
+    /// The user is starting to set the start of the quiet window.
+    StartPickerOpened,
+    /// The user is starting to set the end of the quiet window.
+    EndPickerOpened,
+    /// The user cancelled setting the start of the quiet window.
+    StartPickerCancelled,
+    /// The user cancelled setting the end of the quiet window.
+    EndPickerCancelled,
+    /// The user set the start of the quiet window.
+    StartChanged(Time),
+    /// The user set the end of the quiet window.
+    EndChanged(Time),
+}
+
+/// The state for [`SettingsPage`].
+#[derive(Clone, Debug, Default)]
+pub struct SettingsPageState {
+    /// Whether the user is setting the start of the quiet window.
+    setting_start: bool,
+    /// Whether the user is setting the end of the quiet window.
+    setting_end: bool,
+}
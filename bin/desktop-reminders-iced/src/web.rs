@@ -0,0 +1,5 @@
+mod dashboard;
+mod server;
+
+pub use dashboard::*;
+pub use server::*;
@@ -0,0 +1,278 @@
+use std::fmt::Write;
+
+use chrono::{Duration, NaiveDateTime};
+use serde::Serialize;
+
+use crate::models::reminders::{Reminder, ReminderId, ReminderOccurrence, ReminderTimeOfDay};
+
+/// How many days after today are shown as upcoming.
+const UPCOMING_DAYS: i64 = 7;
+
+/// How often the dashboard page reloads itself, in seconds.
+const REFRESH_SECS: u32 = 60;
+
+/// Today's and upcoming occurrences, as shown on the dashboard.
+#[derive(Clone, Debug, Serialize)]
+pub struct Dashboard {
+    /// The local time the dashboard was generated at.
+    pub generated_at: NaiveDateTime,
+    /// The occurrences today, including ones that already passed.
+    pub today: Vec<DashboardEntry>,
+    /// The occurrences over the next few days.
+    pub upcoming: Vec<DashboardEntry>,
+}
+
+/// An occurrence of a reminder on the dashboard.
+#[derive(Clone, Debug, Serialize)]
+pub struct DashboardEntry {
+    /// The ID of the reminder.
+    pub id: ReminderId,
+    /// The message for the reminder.
+    pub message: String,
+    /// The local time the occurrence starts.
+    pub start: NaiveDateTime,
+    /// The local time the occurrence ends, if it covers a range of time.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end: Option<NaiveDateTime>,
+    /// Whether the occurrence lasts all day.
+    pub all_day: bool,
+}
+
+impl DashboardEntry {
+    /// Creates an entry for an occurrence of a reminder.
+    fn new(reminder: &Reminder, occurrence: &ReminderOccurrence) -> Self {
+        let date = occurrence.at.date();
+        let time = reminder.frequency.time_of_day();
+        // Snoozed occurrences start at arbitrary times
+        let scheduled = occurrence.at.time() == time.start();
+        let (all_day, end) = match *time {
+            ReminderTimeOfDay::AllDay => (scheduled, None),
+            ReminderTimeOfDay::Time { .. } => (false, None),
+            ReminderTimeOfDay::TimeRange { end, .. } => {
+                (false, scheduled.then(|| date.and_time(end)))
+            }
+        };
+
+        Self {
+            id: reminder.id,
+            message: reminder.message.clone(),
+            start: occurrence.at,
+            end,
+            all_day,
+        }
+    }
+
+    /// Formats when the occurrence happens, without the date.
+    pub fn time_text(&self) -> String {
+        if self.all_day {
+            return "All day".to_string();
+        }
+
+        let start = self.start.format("%-I:%M %p");
+        match self.end {
+            Some(end) => format!("{start} - {}", end.format("%-I:%M %p")),
+            None => start.to_string(),
+        }
+    }
+}
+
+impl Dashboard {
+    /// Collects today's and upcoming occurrences of the reminders.
+    pub fn new(reminders: &[Reminder], now: NaiveDateTime) -> Self {
+        let today = now.date();
+        let start_of_day = today.and_hms_opt(0, 0, 0).unwrap_or(now) - Duration::nanoseconds(1);
+        let end_of_today = start_of_day + Duration::days(1);
+        let end_of_upcoming = end_of_today + Duration::days(UPCOMING_DAYS);
+
+        let entries = |after, until| {
+            let mut entries: Vec<_> = reminders
+                .iter()
+                .flat_map(|reminder| {
+                    reminder
+                        .occurrences_between(after, until)
+                        .into_iter()
+                        .map(move |occurrence| DashboardEntry::new(reminder, &occurrence))
+                })
+                .collect();
+            entries.sort_by_key(|entry| (entry.start, !entry.all_day, entry.id));
+            entries
+        };
+
+        Self {
+            generated_at: now,
+            today: entries(start_of_day, end_of_today),
+            upcoming: entries(end_of_today, end_of_upcoming),
+        }
+    }
+
+    /// Renders the dashboard as an HTML page.
+    pub fn to_html(&self) -> String {
+        let mut html = String::new();
+        let _ = write!(
+            html,
+            r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<meta http-equiv="refresh" content="{REFRESH_SECS}">
+<title>Reminders</title>
+<style>
+body {{ background: #000; color: #fff; font-family: sans-serif; margin: 2em auto; max-width: 40em; padding: 0 1em; }}
+h1 {{ font-weight: normal; }}
+h2 {{ border-bottom: 1px solid #444; font-weight: normal; padding-bottom: 0.25em; }}
+ul {{ list-style: none; padding: 0; }}
+li {{ display: flex; justify-content: space-between; padding: 0.4em 0; }}
+.past {{ color: #888; }}
+.time {{ color: #aaa; white-space: nowrap; margin-left: 1em; }}
+footer {{ color: #666; font-size: 0.8em; margin-top: 2em; }}
+</style>
+</head>
+<body>
+<h1>Reminders</h1>
+"#
+        );
+
+        let _ = writeln!(html, "<h2>Today</h2>");
+        self.write_entries(&mut html, &self.today, false, "Nothing today.");
+        let _ = writeln!(html, "<h2>Upcoming</h2>");
+        self.write_entries(&mut html, &self.upcoming, true, "Nothing in the next week.");
+
+        let _ = write!(
+            html,
+            "<footer>Updated {}</footer>\n</body>\n</html>\n",
+            self.generated_at.format("%a %b %-d, %-I:%M %p")
+        );
+        html
+    }
+
+    /// Writes a list of entries to the page.
+    fn write_entries(
+        &self,
+        html: &mut String,
+        entries: &[DashboardEntry],
+        show_date: bool,
+        empty: &str,
+    ) {
+        if entries.is_empty() {
+            let _ = writeln!(html, "<p class=\"past\">{empty}</p>");
+            return;
+        }
+
+        let _ = writeln!(html, "<ul>");
+        for entry in entries {
+            let past = !entry.all_day && entry.end.unwrap_or(entry.start) < self.generated_at;
+            let class = if past { " class=\"past\"" } else { "" };
+            let when = if show_date {
+                format!("{} {}", entry.start.format("%a %b %-d"), entry.time_text())
+            } else {
+                entry.time_text()
+            };
+            let _ = writeln!(
+                html,
+                "<li{class}><span>{}</span><span class=\"time\">{}</span></li>",
+                escape_html(&entry.message),
+                escape_html(&when),
+            );
+        }
+        let _ = writeln!(html, "</ul>");
+    }
+}
+
+/// Escapes text for use in HTML.
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDateTime;
+
+    use crate::models::reminders::{Reminder, ReminderId};
+
+    use super::Dashboard;
+
+    fn reminder(id: u64, schedule: &str, message: &str) -> Reminder {
+        Reminder {
+            id: ReminderId(id),
+            frequency: schedule.parse().unwrap(),
+            message: message.to_string(),
+            completed: false,
+            snoozed_until: None,
+            actions: Vec::new(),
+            contact: None,
+        }
+    }
+
+    fn now() -> NaiveDateTime {
+        "2024-03-01T12:00:00".parse().unwrap()
+    }
+
+    #[test]
+    fn splits_today_from_upcoming() {
+        let reminders = [
+            reminder(1, "weekly fri,mon @ 09:00-10:00", "Standup"),
+            reminder(2, "once 2024-03-01 @ all-day", "Rent"),
+            reminder(3, "once 2024-03-08 @ 18:00", "Dinner"),
+            reminder(4, "once 2024-03-09 @ 00:00", "Too far"),
+            reminder(5, "once 2024-02-29 @ 23:59", "Yesterday"),
+        ];
+        let dashboard = Dashboard::new(&reminders, now());
+
+        let today: Vec<_> = dashboard
+            .today
+            .iter()
+            .map(|entry| (entry.id.0, entry.time_text()))
+            .collect();
+        assert_eq!(
+            today,
+            [
+                (2, "All day".to_string()),
+                (1, "9:00 AM - 10:00 AM".to_string()),
+            ]
+        );
+        let upcoming: Vec<_> = dashboard
+            .upcoming
+            .iter()
+            .map(|entry| (entry.id.0, entry.start.to_string()))
+            .collect();
+        assert_eq!(
+            upcoming,
+            [
+                (1, "2024-03-04 09:00:00".to_string()),
+                (1, "2024-03-08 09:00:00".to_string()),
+                (3, "2024-03-08 18:00:00".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn escapes_messages_in_html() {
+        let reminders = [reminder(
+            1,
+            "once 2024-03-01 @ 15:00",
+            "<script>alert(\"Tom & Jerry's\")</script>",
+        )];
+        let html = Dashboard::new(&reminders, now()).to_html();
+        assert!(!html.contains("<script>"), "{html}");
+        assert!(
+            html.contains(
+                "<li><span>&lt;script&gt;alert(&quot;Tom &amp; Jerry&#39;s&quot;)&lt;/script&gt;</span>\
+                 <span class=\"time\">3:00 PM</span></li>"
+            ),
+            "{html}"
+        );
+        assert!(html.contains("<p class=\"past\">Nothing in the next week.</p>"));
+    }
+}
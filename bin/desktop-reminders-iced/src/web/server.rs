@@ -0,0 +1,160 @@
+use std::{net::SocketAddr, sync::Arc};
+
+use chrono::Local;
+use color_eyre::eyre::eyre;
+use tiny_http::{Header, Method, Request as HttpRequest, Response as HttpResponse, Server};
+use tracing::{debug, info, warn};
+
+use crate::ipc::{IpcRequest, Request, Response};
+
+use super::Dashboard;
+
+/// Serves a read-only dashboard of today's and upcoming reminders over HTTP.
+/// The page is at `/` and the same data is at `/api/occurrences` as JSON.
+pub struct WebServer {
+    server: Arc<Server>,
+}
+
+impl WebServer {
+    /// Starts serving the dashboard at `address`, fetching the reminders by
+    /// passing requests to `on_request`.
+    pub fn start<F>(address: SocketAddr, on_request: F) -> color_eyre::Result<Self>
+    where
+        F: Fn(IpcRequest) + Send + Sync + 'static,
+    {
+        let server = Server::http(address).map_err(|error| eyre!(error))?;
+        let server = Arc::new(server);
+
+        std::thread::spawn({
+            let server = server.clone();
+            move || {
+                for request in server.incoming_requests() {
+                    if let Err(error) = serve(request, &on_request) {
+                        debug!(%error, "failed to respond to a request");
+                    }
+                }
+            }
+        });
+
+        let web = Self { server };
+        // The port is chosen by the system if it was 0
+        let address = web.address().unwrap_or(address);
+        info!(%address, "serving the dashboard");
+        Ok(web)
+    }
+
+    /// Gets the address the dashboard is served at.
+    pub fn address(&self) -> Option<SocketAddr> {
+        self.server.server_addr().to_ip()
+    }
+}
+
+impl Drop for WebServer {
+    fn drop(&mut self) {
+        self.server.unblock();
+    }
+}
+
+/// Responds to a request for the dashboard.
+fn serve(request: HttpRequest, on_request: &impl Fn(IpcRequest)) -> std::io::Result<()> {
+    debug!(method = %request.method(), url = request.url(), "received request");
+    if !matches!(request.method(), Method::Get | Method::Head) {
+        return request
+            .respond(HttpResponse::from_string("Method not allowed").with_status_code(405));
+    }
+
+    let path = request.url().split('?').next().unwrap_or_default();
+    if !matches!(path, "/" | "/api/occurrences") {
+        return request.respond(HttpResponse::from_string("Not found").with_status_code(404));
+    }
+
+    // Ask the app for its reminders
+    let (list, response) = IpcRequest::new(Request::List);
+    on_request(list);
+    let reminders = match futures::executor::block_on(response.recv()) {
+        Ok(Response::Reminders(reminders)) => reminders,
+        result => {
+            warn!(?result, "failed to get the reminders");
+            return request.respond(
+                HttpResponse::from_string("Failed to get the reminders").with_status_code(500),
+            );
+        }
+    };
+
+    let dashboard = Dashboard::new(&reminders, Local::now().naive_local());
+    let (body, content_type) = match path {
+        "/" => (dashboard.to_html(), "text/html; charset=utf-8"),
+        _ => (
+            serde_json::to_string(&dashboard).map_err(std::io::Error::other)?,
+            "application/json",
+        ),
+    };
+    let content_type = Header::from_bytes("Content-Type", content_type)
+        .unwrap_or_else(|()| unreachable!("the header is valid"));
+    request.respond(HttpResponse::from_string(body).with_header(content_type))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use crate::{
+        ipc::{Request, Response},
+        models::reminders::{Reminder, ReminderId},
+    };
+
+    use super::WebServer;
+
+    #[test]
+    fn serves_page_and_json() {
+        let reminders = Arc::new(Mutex::new(vec![Reminder {
+            id: ReminderId(1),
+            frequency: "daily @ all-day".parse().unwrap(),
+            message: "Water <plants>".to_string(),
+            completed: false,
+            snoozed_until: None,
+            actions: Vec::new(),
+            contact: None,
+        }]));
+        let server = WebServer::start("127.0.0.1:0".parse().unwrap(), {
+            let reminders = reminders.clone();
+            move |request| {
+                let result = match request.request {
+                    Request::List => Ok(Response::Reminders(reminders.lock().unwrap().clone())),
+                    _ => unreachable!("the dashboard only lists reminders"),
+                };
+                request.respond(result);
+            }
+        })
+        .unwrap();
+        let base = format!("http://{}", server.address().unwrap());
+
+        let page = ureq::get(&format!("{base}/")).call().unwrap();
+        assert_eq!(
+            page.header("Content-Type"),
+            Some("text/html; charset=utf-8")
+        );
+        let page = page.into_string().unwrap();
+        assert!(
+            page.contains(
+                "<li><span>Water &lt;plants&gt;</span><span class=\"time\">All day</span></li>"
+            ),
+            "{page}"
+        );
+
+        let json = ureq::get(&format!("{base}/api/occurrences"))
+            .call()
+            .unwrap();
+        assert_eq!(json.header("Content-Type"), Some("application/json"));
+        let json: serde_json::Value = json.into_json().unwrap();
+        let today = json["today"].as_array().unwrap();
+        assert_eq!(today.len(), 1, "{json}");
+        assert_eq!(today[0]["id"], 1);
+        assert_eq!(today[0]["message"], "Water <plants>");
+        assert_eq!(today[0]["all_day"], true);
+        assert_eq!(json["upcoming"].as_array().unwrap().len(), 7, "{json}");
+
+        let error = ureq::get(&format!("{base}/missing")).call().unwrap_err();
+        assert!(matches!(error, ureq::Error::Status(404, _)), "{error}");
+    }
+}